@@ -0,0 +1,27 @@
+use specs::prelude::*;
+
+/// What an animated point does at the end of its range
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum AnimationMode {
+  Loop, // Start over from the other end
+  Bounce, // Turn around
+}
+
+/// A point sliding along whatever it is constrained to by itself
+#[derive(Debug, Copy, Clone)]
+pub struct Animated {
+  pub speed: f64, // Fraction of the range per second
+  pub direction: f64, // 1.0 or -1.0
+  pub mode: AnimationMode,
+  pub start: f64, // Parameter to go back to when stopped
+}
+
+impl Animated {
+  pub fn new(start: f64) -> Self {
+    Self { speed: 0.1, direction: 1.0, mode: AnimationMode::Loop, start }
+  }
+}
+
+impl Component for Animated {
+  type Storage = VecStorage<Self>;
+}
\ No newline at end of file
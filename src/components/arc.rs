@@ -0,0 +1,51 @@
+use specs::prelude::*;
+use crate::utilities::Color;
+pub use crate::utilities::Arc;
+
+#[derive(Debug, Copy, Clone)]
+pub enum ArcFill {
+  None,
+  Sector(Color), // Region between the arc and the center
+  Segment(Color), // Region between the arc and its chord
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct ArcStyle {
+  pub width: f64,
+  pub color: Color,
+  pub fill: ArcFill,
+}
+
+impl Component for ArcStyle {
+  type Storage = VecStorage<Self>;
+}
+
+#[derive(Debug, Copy, Clone)]
+pub enum SymbolicArc {
+  CenterTwoPoints(Entity, Entity, Entity), // (center, from, to)
+  ThreePoints(Entity, Entity, Entity), // (from, through, to)
+}
+
+impl SymbolicArc {
+  pub fn parents(&self) -> Vec<Entity> {
+    match self {
+      SymbolicArc::CenterTwoPoints(p1_ent, p2_ent, p3_ent) | SymbolicArc::ThreePoints(p1_ent, p2_ent, p3_ent) => vec![*p1_ent, *p2_ent, *p3_ent],
+    }
+  }
+
+  /// The same arc defined on the parents given by `f`
+  pub fn map_parents<F: Fn(Entity) -> Entity>(&self, f: F) -> Self {
+    match *self {
+      SymbolicArc::CenterTwoPoints(p1_ent, p2_ent, p3_ent) => SymbolicArc::CenterTwoPoints(f(p1_ent), f(p2_ent), f(p3_ent)),
+      SymbolicArc::ThreePoints(p1_ent, p2_ent, p3_ent) => SymbolicArc::ThreePoints(f(p1_ent), f(p2_ent), f(p3_ent)),
+    }
+  }
+}
+
+impl Component for SymbolicArc {
+  type Storage = VecStorage<Self>;
+}
+
+impl Component for Arc {
+  type Storage = VecStorage<Self>;
+}
\ No newline at end of file
@@ -0,0 +1,50 @@
+use specs::prelude::*;
+use crate::utilities::Color;
+pub use crate::utilities::Conic;
+
+#[derive(Debug, Copy, Clone)]
+pub struct ConicStyle {
+  pub width: f64,
+  pub color: Color,
+}
+
+impl Component for ConicStyle {
+  type Storage = VecStorage<Self>;
+}
+
+#[derive(Debug, Copy, Clone)]
+#[allow(dead_code)]
+pub enum SymbolicConic {
+  Ellipse(Entity, Entity, Entity), // (focus_1, focus_2, point)
+  Hyperbola(Entity, Entity, Entity), // (focus_1, focus_2, point)
+  Parabola(Entity, Entity), // (focus, directrix_line)
+  FivePoints(Entity, Entity, Entity, Entity, Entity),
+}
+
+impl SymbolicConic {
+  pub fn parents(&self) -> Vec<Entity> {
+    match self {
+      SymbolicConic::Ellipse(f1_ent, f2_ent, p_ent) | SymbolicConic::Hyperbola(f1_ent, f2_ent, p_ent) => vec![*f1_ent, *f2_ent, *p_ent],
+      SymbolicConic::Parabola(focus_ent, line_ent) => vec![*focus_ent, *line_ent],
+      SymbolicConic::FivePoints(p1_ent, p2_ent, p3_ent, p4_ent, p5_ent) => vec![*p1_ent, *p2_ent, *p3_ent, *p4_ent, *p5_ent],
+    }
+  }
+
+  /// The same conic defined on the parents given by `f`
+  pub fn map_parents<F: Fn(Entity) -> Entity>(&self, f: F) -> Self {
+    match *self {
+      SymbolicConic::Ellipse(f1_ent, f2_ent, p_ent) => SymbolicConic::Ellipse(f(f1_ent), f(f2_ent), f(p_ent)),
+      SymbolicConic::Hyperbola(f1_ent, f2_ent, p_ent) => SymbolicConic::Hyperbola(f(f1_ent), f(f2_ent), f(p_ent)),
+      SymbolicConic::Parabola(focus_ent, line_ent) => SymbolicConic::Parabola(f(focus_ent), f(line_ent)),
+      SymbolicConic::FivePoints(p1_ent, p2_ent, p3_ent, p4_ent, p5_ent) => SymbolicConic::FivePoints(f(p1_ent), f(p2_ent), f(p3_ent), f(p4_ent), f(p5_ent)),
+    }
+  }
+}
+
+impl Component for SymbolicConic {
+  type Storage = VecStorage<Self>;
+}
+
+impl Component for Conic {
+  type Storage = VecStorage<Self>;
+}
\ No newline at end of file
@@ -0,0 +1,32 @@
+use specs::prelude::*;
+use crate::utilities::{Expression, Vector2};
+
+/// A curve sampled over a fixed parameter range. Parametric curves are given
+/// by `(x(t), y(t))` and polar curves by `r(t)`, where `t` is the angle
+#[derive(Debug, Clone)]
+pub enum SymbolicCurve {
+  Parametric { x: Expression, y: Expression, t_min: f64, t_max: f64 },
+  Polar { r: Expression, t_min: f64, t_max: f64 },
+}
+
+impl SymbolicCurve {
+  pub fn range(&self) -> (f64, f64) {
+    match self {
+      SymbolicCurve::Parametric { t_min, t_max, .. } | SymbolicCurve::Polar { t_min, t_max, .. } => (*t_min, *t_max),
+    }
+  }
+
+  /// The point at `t`, if the curve is defined there. `t` is not limited to
+  /// the range
+  pub fn point_at(&self, t: f64) -> Option<Vector2> {
+    let p = match self {
+      SymbolicCurve::Parametric { x, y, .. } => vec2![x.evaluate_at("t", t), y.evaluate_at("t", t)],
+      SymbolicCurve::Polar { r, .. } => r.evaluate_at("t", t) * vec2![t.cos(), t.sin()],
+    };
+    if p.x.is_finite() && p.y.is_finite() { Some(p) } else { None }
+  }
+}
+
+impl Component for SymbolicCurve {
+  type Storage = VecStorage<Self>;
+}
\ No newline at end of file
@@ -0,0 +1,133 @@
+use specs::prelude::*;
+use crate::utilities::{Expression, ParseError, Line, Vector2, find_root_near};
+
+/// Graph of `y = f(x)`. Besides `x`, the expression may reference the
+/// coordinates of the points in `references`: `p0_x` and `p0_y` are those of
+/// the first one, `p1_x` and `p1_y` those of the second one, and so on. The
+/// points are renamed to their current names only for display.
+#[derive(Debug, Clone)]
+pub struct SymbolicFunction {
+  pub expression: Expression,
+  pub references: Vec<Entity>,
+}
+
+/// The name of the point a variable references, and whether it is the x
+/// coordinate
+fn referenced_coordinate(var: &str) -> Option<(&str, bool)> {
+  let is_x = var.ends_with("_x");
+  if (is_x || var.ends_with("_y")) && var.len() > 2 {
+    Some((&var[..var.len() - 2], is_x))
+  } else {
+    None
+  }
+}
+
+/// The index into the references of a `p0_x`-like variable, and whether it
+/// is the x coordinate
+fn referenced_index(var: &str) -> Option<(usize, bool)> {
+  let (name, is_x) = referenced_coordinate(var)?;
+  let mut chars = name.chars();
+  if chars.next() == Some('p') {
+    chars.as_str().parse().ok().map(|index| (index, is_x))
+  } else {
+    None
+  }
+}
+
+fn coordinate(name: &str, is_x: bool) -> String {
+  format!("{}_{}", name, if is_x { "x" } else { "y" })
+}
+
+impl SymbolicFunction {
+
+  /// Parse an expression in `x` that may reference point coordinates
+  pub fn parse_expression(s: &str) -> Result<Expression, ParseError> {
+    let expr = Expression::parse(s)?;
+    match expr.variables().into_iter().find(|v| v != "x" && referenced_coordinate(v).is_none()) {
+      Some(unknown) => Err(ParseError::UnknownVariable(unknown)),
+      None => Ok(expr),
+    }
+  }
+
+  /// The function of an expression referencing points by name, e.g. `A_x`,
+  /// with the points looked up by `entity_named`. Fails with the first name
+  /// that names no point.
+  pub fn with_names<F: Fn(&str) -> Option<Entity>>(expression: &Expression, entity_named: F) -> Result<Self, String> {
+    let mut names : Vec<String> = vec![];
+    let mut references = vec![];
+    for var in expression.variables() {
+      if let Some((name, _)) = referenced_coordinate(&var) {
+        if !names.iter().any(|n| n == name) {
+          references.push(entity_named(name).ok_or_else(|| name.to_string())?);
+          names.push(name.to_string());
+        }
+      }
+    }
+    let expression = expression.rename(&|var: &str| {
+      let (name, is_x) = referenced_coordinate(var)?;
+      let index = names.iter().position(|n| n == name)?;
+      Some(coordinate(&format!("p{}", index), is_x))
+    });
+    Ok(Self { expression, references })
+  }
+
+  /// The expression with the references named by `name_of`, e.g. `A_x`
+  pub fn named<F: Fn(Entity) -> String>(&self, name_of: F) -> Expression {
+    self.expression.rename(&|var: &str| {
+      let (index, is_x) = referenced_index(var)?;
+      self.references.get(index).map(|ent| coordinate(&name_of(*ent), is_x))
+    })
+  }
+
+  /// Whether every coordinate the expression references is one of a point
+  /// in `references`
+  pub fn is_well_formed(&self) -> bool {
+    self.expression.variables().iter().all(|var| {
+      var == "x" || match referenced_index(var) {
+        Some((index, _)) => index < self.references.len(),
+        None => false,
+      }
+    })
+  }
+
+  /// The function with the referenced coordinates replaced by the positions
+  /// given by `point_of`. Fails with the first reference that has none.
+  pub fn bind<F: Fn(Entity) -> Option<Vector2>>(&self, point_of: F) -> Result<Self, Entity> {
+    let mut positions = Vec::with_capacity(self.references.len());
+    for ent in &self.references {
+      positions.push(point_of(*ent).ok_or(*ent)?);
+    }
+    let expression = self.expression.substitute(&|var: &str| {
+      let (index, is_x) = referenced_index(var)?;
+      positions.get(index).map(|p| if is_x { p.x } else { p.y })
+    });
+    Ok(Self { expression, references: vec![] })
+  }
+
+  pub fn evaluate(&self, x: f64) -> f64 {
+    self.expression.evaluate_at("x", x)
+  }
+
+  /// The point `(x, f(x))`, if `f` is defined at `x`
+  pub fn point_at(&self, x: f64) -> Option<Vector2> {
+    let y = self.evaluate(x);
+    if y.is_finite() { Some(vec2![x, y]) } else { None }
+  }
+
+  /// Intersection with `line` whose x is closest to `hint`, searched within
+  /// `radius` of it
+  pub fn intersect_line(&self, line: Line, hint: f64, radius: f64) -> Option<Vector2> {
+    let Line { origin, direction } = line;
+    if direction.x.abs() < 1e-12 {
+      self.point_at(origin.x)
+    } else {
+      let slope = direction.y / direction.x;
+      let line_y = |x: f64| origin.y + (x - origin.x) * slope;
+      find_root_near(|x| self.evaluate(x) - line_y(x), hint, radius).and_then(|x| self.point_at(x))
+    }
+  }
+}
+
+impl Component for SymbolicFunction {
+  type Storage = VecStorage<Self>;
+}
\ No newline at end of file
@@ -0,0 +1,19 @@
+use specs::prelude::*;
+
+/// Marks a geometry that is not drawn nor hit, e.g. when stepping back
+/// through the construction protocol
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Hidden;
+
+impl Component for Hidden {
+  type Storage = NullStorage<Self>;
+}
+
+/// Marks a geometry hidden from its context menu, until everything hidden
+/// is shown again
+#[derive(Debug, Copy, Clone, Default)]
+pub struct HiddenByUser;
+
+impl Component for HiddenByUser {
+  type Storage = NullStorage<Self>;
+}
\ No newline at end of file
@@ -17,6 +17,31 @@ impl Component for LineStyle {
 pub enum SymbolicLine {
   TwoPoints(Entity, Entity), // Should be two points
   Parallel(Entity, Entity), // (line_entity, point_entity)
+  Perpendicular(Entity, Entity), // (line_entity, point_entity)
+  Tangent(Entity, Entity, bool), // (point_entity, arc_entity, whether it is the left one looking at the center)
+  TangentAt(Entity), // Point on an arc, tangent to the circle of that arc
+}
+
+impl SymbolicLine {
+  pub fn parents(&self) -> Vec<Entity> {
+    match self {
+      SymbolicLine::TwoPoints(p1_ent, p2_ent) => vec![*p1_ent, *p2_ent],
+      SymbolicLine::Parallel(line_ent, point_ent) | SymbolicLine::Perpendicular(line_ent, point_ent) => vec![*line_ent, *point_ent],
+      SymbolicLine::Tangent(point_ent, arc_ent, _) => vec![*point_ent, *arc_ent],
+      SymbolicLine::TangentAt(point_ent) => vec![*point_ent],
+    }
+  }
+
+  /// The same line defined on the parents given by `f`
+  pub fn map_parents<F: Fn(Entity) -> Entity>(&self, f: F) -> Self {
+    match *self {
+      SymbolicLine::TwoPoints(p1_ent, p2_ent) => SymbolicLine::TwoPoints(f(p1_ent), f(p2_ent)),
+      SymbolicLine::Parallel(line_ent, point_ent) => SymbolicLine::Parallel(f(line_ent), f(point_ent)),
+      SymbolicLine::Perpendicular(line_ent, point_ent) => SymbolicLine::Perpendicular(f(line_ent), f(point_ent)),
+      SymbolicLine::Tangent(point_ent, arc_ent, other) => SymbolicLine::Tangent(f(point_ent), f(arc_ent), other),
+      SymbolicLine::TangentAt(point_ent) => SymbolicLine::TangentAt(f(point_ent)),
+    }
+  }
 }
 
 impl Component for SymbolicLine {
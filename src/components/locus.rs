@@ -0,0 +1,24 @@
+use specs::prelude::*;
+
+/// Path of `traced` as the `driver` point slides along whatever it is
+/// constrained to. `traced` has to depend on `driver`
+#[derive(Debug, Copy, Clone)]
+pub struct SymbolicLocus {
+  pub driver: Entity,
+  pub traced: Entity,
+}
+
+impl SymbolicLocus {
+  pub fn parents(&self) -> Vec<Entity> {
+    vec![self.driver, self.traced]
+  }
+
+  /// The same locus defined on the parents given by `f`
+  pub fn map_parents<F: Fn(Entity) -> Entity>(&self, f: F) -> Self {
+    Self { driver: f(self.driver), traced: f(self.traced) }
+  }
+}
+
+impl Component for SymbolicLocus {
+  type Storage = VecStorage<Self>;
+}
\ No newline at end of file
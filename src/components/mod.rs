@@ -1,9 +1,27 @@
 mod point;
 mod line;
+mod arc;
+mod conic;
+mod function;
+mod curve;
+mod locus;
+mod plot;
 mod selected;
+mod traced;
+mod hidden;
+mod animated;
 mod rectangle;
 
 pub use point::{Point, SymbolicPoint, PointStyle};
 pub use line::{Line, SymbolicLine, LineStyle};
+pub use arc::{Arc, SymbolicArc, ArcStyle, ArcFill};
+pub use conic::{Conic, SymbolicConic, ConicStyle};
+pub use function::SymbolicFunction;
+pub use curve::SymbolicCurve;
+pub use locus::SymbolicLocus;
+pub use plot::{Plot, PlotStyle};
 pub use rectangle::{Rectangle, RectangleStyle};
-pub use selected::Selected;
\ No newline at end of file
+pub use selected::Selected;
+pub use traced::Traced;
+pub use hidden::{Hidden, HiddenByUser};
+pub use animated::{Animated, AnimationMode};
\ No newline at end of file
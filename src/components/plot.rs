@@ -0,0 +1,18 @@
+use specs::prelude::*;
+use crate::utilities::Color;
+pub use crate::utilities::Plot;
+
+/// Style shared by everything drawn from a sampled `Plot`
+#[derive(Debug, Copy, Clone)]
+pub struct PlotStyle {
+  pub width: f64,
+  pub color: Color,
+}
+
+impl Component for PlotStyle {
+  type Storage = VecStorage<Self>;
+}
+
+impl Component for Plot {
+  type Storage = VecStorage<Self>;
+}
\ No newline at end of file
@@ -17,24 +17,59 @@ pub enum SymbolicPoint {
   Free(Vector2),
   OnLine(Entity, f64), // Point on a line, distance t from origin
   LineLineIntersect(Entity, Entity), // Should be two entities of lines
+  OnArc(Entity, f64), // Point on an arc, t in [0, 1] from arc start to end
+  OnConic(Entity, f64), // Point on a conic, t is the conic parameter
+  OnFunction(Entity, f64), // Point on a function graph at x
+  FunctionLineIntersect(Entity, Entity, f64), // (function, line, x near the intersection)
+  OnCurve(Entity, f64), // Point on a parametric or polar curve at parameter t
 }
 
 impl SymbolicPoint {
-  pub fn is_on_same_line_with(&self, other: &SymbolicPoint) -> bool {
+  pub fn parents(&self) -> Vec<Entity> {
     match self {
-      Self::Free(_) => false,
-      Self::OnLine(line_ent, _) => match other {
-        Self::Free(_) => false,
-        Self::OnLine(l1_ent, _) => line_ent == l1_ent,
-        Self::LineLineIntersect(l1_ent, l2_ent) => line_ent == l1_ent || line_ent == l2_ent,
-      },
-      Self::LineLineIntersect(l1_ent, l2_ent) => match other {
-        Self::Free(_) => false,
-        Self::OnLine(line_ent, _) => l1_ent == line_ent || l2_ent == line_ent,
-        Self::LineLineIntersect(l3_ent, l4_ent) => {
-          l1_ent == l3_ent || l1_ent == l4_ent || l2_ent == l3_ent || l2_ent == l4_ent
-        },
-      },
+      Self::Free(_) => vec![],
+      Self::OnLine(line_ent, _) => vec![*line_ent],
+      Self::LineLineIntersect(l1_ent, l2_ent) => vec![*l1_ent, *l2_ent],
+      Self::OnArc(arc_ent, _) => vec![*arc_ent],
+      Self::OnConic(conic_ent, _) => vec![*conic_ent],
+      Self::OnFunction(function_ent, _) => vec![*function_ent],
+      Self::FunctionLineIntersect(function_ent, line_ent, _) => vec![*function_ent, *line_ent],
+      Self::OnCurve(curve_ent, _) => vec![*curve_ent],
+    }
+  }
+
+  /// The same point defined on the parents given by `f`
+  pub fn map_parents<F: Fn(Entity) -> Entity>(&self, f: F) -> Self {
+    match *self {
+      Self::Free(position) => Self::Free(position),
+      Self::OnLine(line_ent, t) => Self::OnLine(f(line_ent), t),
+      Self::LineLineIntersect(l1_ent, l2_ent) => Self::LineLineIntersect(f(l1_ent), f(l2_ent)),
+      Self::OnArc(arc_ent, t) => Self::OnArc(f(arc_ent), t),
+      Self::OnConic(conic_ent, t) => Self::OnConic(f(conic_ent), t),
+      Self::OnFunction(function_ent, x) => Self::OnFunction(f(function_ent), x),
+      Self::FunctionLineIntersect(function_ent, line_ent, x) => Self::FunctionLineIntersect(f(function_ent), f(line_ent), x),
+      Self::OnCurve(curve_ent, t) => Self::OnCurve(f(curve_ent), t),
+    }
+  }
+
+  /// The parameter the point slides along something by, if any
+  pub fn parameter(&self) -> Option<f64> {
+    match self {
+      Self::OnLine(_, t) | Self::OnArc(_, t) | Self::OnConic(_, t) | Self::OnFunction(_, t) | Self::OnCurve(_, t) => Some(*t),
+      Self::Free(_) | Self::LineLineIntersect(_, _) | Self::FunctionLineIntersect(_, _, _) => None,
+    }
+  }
+
+  /// The same constraint at parameter `t`, if the point slides along
+  /// something by a parameter
+  pub fn with_parameter(&self, t: f64) -> Option<SymbolicPoint> {
+    match self {
+      Self::OnLine(line_ent, _) => Some(Self::OnLine(*line_ent, t)),
+      Self::OnArc(arc_ent, _) => Some(Self::OnArc(*arc_ent, t)),
+      Self::OnConic(conic_ent, _) => Some(Self::OnConic(*conic_ent, t)),
+      Self::OnFunction(function_ent, _) => Some(Self::OnFunction(*function_ent, t)),
+      Self::OnCurve(curve_ent, _) => Some(Self::OnCurve(*curve_ent, t)),
+      Self::Free(_) | Self::LineLineIntersect(_, _) | Self::FunctionLineIntersect(_, _, _) => None,
     }
   }
 }
@@ -0,0 +1,10 @@
+use specs::prelude::*;
+
+/// Marks a point or line whose past positions are kept in the trace layer
+/// while it moves
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Traced;
+
+impl Component for Traced {
+  type Storage = NullStorage<Self>;
+}
\ No newline at end of file
@@ -21,7 +21,7 @@ fn main() {
   let mut world = World::new();
 
   // Create a window
-  let window : PistonWindow = WindowSettings::new("Geometry Sketchpad - Untitled.gsp", WINDOW_SIZE).build().unwrap();
+  let window : PistonWindow = WindowSettings::new(WINDOW_TITLE, WINDOW_SIZE).build().unwrap();
   let window_system = WindowSystem { window };
 
   // Create dispatcher
@@ -34,7 +34,20 @@ fn main() {
     .with(interactions::SeldeAllViaKeyboard, "selde_all_via_keyboard", &[])
     .with(interactions::RemoveSelectedViaDelete, "remove_selected_via_delete", &[])
     .with(interactions::AbortCreateLineViaKeyboard, "abort_create_line_via_keyboard", &[])
+    .with(interactions::CreateLocusViaKeyboard, "create_locus_via_keyboard", &[])
+    .with(interactions::TraceViaKeyboard, "trace_via_keyboard", &[])
+    .with(interactions::AnimateViaKeyboard, "animate_via_keyboard", &[])
+    .with(interactions::NudgeViaKeyboard, "nudge_via_keyboard", &[])
+    .with(interactions::ProtocolViaKeyboard, "protocol_via_keyboard", &[])
+    .with(interactions::IterateViaKeyboard, "iterate_via_keyboard", &[])
+    .with(interactions::ClipboardViaKeyboard, "clipboard_via_keyboard", &[])
+    .with(interactions::DuplicateViaKeyboard, "duplicate_via_keyboard", &[])
+    .with(interactions::RedefineViaKeyboard, "redefine_via_keyboard", &[])
+    .with(interactions::TangentsViaKeyboard, "tangents_via_keyboard", &[])
     .with(interactions::MouseEventEmitter::default(), "mouse_event_emitter", &[])
+    .with(interactions::EditPromptViaKeyboard, "edit_prompt_via_keyboard", &[])
+    .with(interactions::OpenPromptViaKeyboard, "open_prompt_via_keyboard", &["edit_prompt_via_keyboard"])
+    .with(interactions::CustomToolViaKeyboard, "custom_tool_via_keyboard", &["edit_prompt_via_keyboard"])
 
     // We put tooling handler here first
     .with(state_managers::ToolStateManager::default(), "tool_state_manager", &["change_tool_via_keyboard"])
@@ -42,37 +55,70 @@ fn main() {
     // Interations based on tool
     .with(interactions::MoveViewportViaDrag::default(), "move_viewport_via_drag", &["mouse_event_emitter", "tool_state_manager"])
     .with(interactions::SeldeViaMouse::default(), "selde_via_mouse", &["mouse_event_emitter", "tool_state_manager"])
-    .with(interactions::MovePointViaDrag::default(), "move_point_via_drag", &["mouse_event_emitter", "tool_state_manager"])
+    .with(interactions::TransformSelectionViaDrag::default(), "transform_selection_via_drag", &["mouse_event_emitter", "tool_state_manager"])
 
     // Other state Managers
     .with(state_managers::ExitStateManager::default(), "exit_state_manager", &["exit_via_keyboard"])
     .with(state_managers::ViewportStateManager::default(), "viewport_state_manager", &["move_viewport_via_scroll", "move_viewport_via_drag"])
+    .with(state_managers::SnapSettingsManager::default(), "snap_settings_manager", &["edit_prompt_via_keyboard"])
 
     // Data structures
     .with(cache_managers::DependencyGraphCache::default(), "dependency_graph_cache", &[])
-    .with(cache_managers::SpatialHashCache::default(), "spatial_hash_cache", &["viewport_state_manager"])
-
-    // Geometry action handlers
-    .with(geometry_actions::SeldeAllHandler::default(), "selde_all_handler", &["selde_all_via_keyboard", "selde_via_mouse"])
-    .with(geometry_actions::RemoveSelectedHandler::default(), "remove_selected_handler", &["remove_selected_via_delete", "dependency_graph_cache"])
+    .with(cache_managers::PlotCache::default(), "plot_cache", &["viewport_state_manager"])
+    .with(cache_managers::SpatialHashCache::default(), "spatial_hash_cache", &["viewport_state_manager", "plot_cache"])
+    .with(cache_managers::ProtocolCache::default(), "protocol_cache", &[])
 
     // Geometry helpers
-    .with(interactions::SnapPointSystem, "snap_point_system", &["spatial_hash_cache", "tool_state_manager", "viewport_state_manager"])
+    .with(interactions::SnapPointSystem, "snap_point_system", &["spatial_hash_cache", "tool_state_manager", "viewport_state_manager", "snap_settings_manager"])
+
+    // Dragged points move to where they snap
+    .with(interactions::MovePointViaDrag::default(), "move_point_via_drag", &["mouse_event_emitter", "tool_state_manager", "snap_point_system"])
+
+    // The context menu keeps the mouse from the others until it is closed
+    .with(interactions::ContextMenuViaMouse::default(), "context_menu_via_mouse", &["selde_via_mouse", "move_point_via_drag", "transform_selection_via_drag", "snap_point_system"])
+
+    // Geometry action handlers
+    .with(geometry_actions::SeldeAllHandler::default(), "selde_all_handler", &["selde_all_via_keyboard", "selde_via_mouse", "context_menu_via_mouse", "dependency_graph_cache"])
+    .with(geometry_actions::RemoveSelectedHandler::default(), "remove_selected_handler", &["remove_selected_via_delete", "context_menu_via_mouse", "dependency_graph_cache"])
+    .with(geometry_actions::CreateLocusHandler::default(), "create_locus_handler", &["create_locus_via_keyboard", "dependency_graph_cache"])
+    .with(geometry_actions::TraceHandler::default(), "trace_handler", &["trace_via_keyboard"])
+    .with(geometry_actions::ProtocolHandler::default(), "protocol_handler", &["protocol_via_keyboard", "selde_via_mouse", "context_menu_via_mouse", "protocol_cache", "spatial_hash_cache"])
+    .with(interactions::PickCustomToolGivens::default(), "pick_custom_tool_givens", &["selde_via_mouse"])
+    .with(geometry_actions::CustomToolHandler::default(), "custom_tool_handler", &["custom_tool_via_keyboard", "pick_custom_tool_givens", "dependency_graph_cache"])
+    .with(interactions::PickIterationImages::default(), "pick_iteration_images", &["selde_via_mouse", "dependency_graph_cache"])
+    .with(geometry_actions::IterationHandler::default(), "iteration_handler", &["iterate_via_keyboard", "pick_iteration_images", "dependency_graph_cache"])
+    .with(geometry_actions::ClipboardHandler::default(), "clipboard_handler", &["clipboard_via_keyboard"])
+    .with(geometry_actions::DuplicateHandler::default(), "duplicate_handler", &["duplicate_via_keyboard", "dependency_graph_cache"])
+    .with(geometry_actions::RedefineHandler::default(), "redefine_handler", &["redefine_via_keyboard", "move_point_via_drag", "dependency_graph_cache", "protocol_cache"])
+    .with(geometry_actions::RecolorHandler::default(), "recolor_handler", &["context_menu_via_mouse"])
+    .with(geometry_actions::PerpendicularHandler::default(), "perpendicular_handler", &["context_menu_via_mouse"])
+    .with(geometry_actions::TangentHandler::default(), "tangent_handler", &["context_menu_via_mouse", "tangents_via_keyboard"])
 
     // Create geometry systems
     .with(geometry_systems::SeldeHandler::default(), "selde_handler", &["selde_all_handler"])
-    .with(geometry_systems::RemoveHandler::default(), "geometry_remove_handler", &["remove_selected_handler"])
-    .with(geometry_systems::MovePointHandler::default(), "move_point_handler", &["move_point_via_drag"])
+    .with(geometry_systems::RemoveHandler::default(), "geometry_remove_handler", &["remove_selected_handler", "clipboard_handler"])
+    .with(geometry_systems::AnimationSystem::default(), "animation_system", &["animate_via_keyboard", "viewport_state_manager"])
+    .with(geometry_systems::MovePointHandler::default(), "move_point_handler", &["move_point_via_drag", "transform_selection_via_drag", "animation_system", "nudge_via_keyboard"])
     .with(geometry_systems::CreatePointSystem::default(), "create_point_system", &["snap_point_system"])
     .with(geometry_systems::CreateLineSystem::default(), "create_line_system", &["create_point_system"])
+    .with(geometry_systems::CreateArcSystem::default(), "create_arc_system", &["create_point_system"])
+    .with(geometry_systems::CreateConicSystem::default(), "create_conic_system", &["create_point_system"])
+    .with(geometry_systems::CreateFunctionSystem::default(), "create_function_system", &["edit_prompt_via_keyboard"])
+    .with(geometry_systems::CreateCurveSystem::default(), "create_curve_system", &["edit_prompt_via_keyboard"])
+    .with(geometry_systems::ExactPointSystem::default(), "exact_point_system", &["edit_prompt_via_keyboard", "open_prompt_via_keyboard", "context_menu_via_mouse"])
 
     // Renderers
     .with(geometry_renderers::SnapPointRenderer::default(), "snap_point_renderer", &["snap_point_system"])
     .with(geometry_renderers::CreateLineRenderer::default(), "create_line_renderer", &["create_line_system"])
     .with(geometry_renderers::SelectRectangleRenderer::default(), "select_rectangle_renderer", &["selde_via_mouse"])
+    .with(geometry_renderers::SelectionHandlesRenderer::default(), "selection_handles_renderer", &["transform_selection_via_drag"])
+    .with(geometry_renderers::ProtocolPanelRenderer::default(), "protocol_panel_renderer", &["protocol_handler"])
+    .with(geometry_renderers::ContextMenuRenderer::default(), "context_menu_renderer", &["context_menu_via_mouse"])
 
     // Solver & final rendering
-    .with(geometry_systems::SolverSystem::default(), "solver_system", &["create_point_system", "create_line_system"])
+    .with(geometry_systems::SolverSystem::default(), "solver_system", &["create_point_system", "create_line_system", "create_arc_system", "create_conic_system", "perpendicular_handler", "tangent_handler"])
+    .with(geometry_systems::LocusSystem::default(), "locus_system", &["solver_system", "create_locus_handler"])
+    .with(cache_managers::TraceCache::default(), "trace_cache", &["solver_system", "trace_handler"])
     .with_thread_local(window_system)
     .build();
 
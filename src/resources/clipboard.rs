@@ -0,0 +1,19 @@
+use crate::resources::Definition;
+
+/// # Clipboard
+///
+/// The copied geometries along with everything they are defined on, in the
+/// order they were created. Every paste is moved a bit further than the last
+/// one so that the copies do not cover each other.
+#[derive(Default)]
+pub struct Clipboard {
+  pub definitions: Vec<Definition>,
+  pub pastes: usize,
+}
+
+impl Clipboard {
+  pub fn set(&mut self, definitions: Vec<Definition>) {
+    self.definitions = definitions;
+    self.pastes = 0;
+  }
+}
\ No newline at end of file
@@ -0,0 +1,282 @@
+use std::collections::{HashMap, HashSet};
+use specs::prelude::*;
+use crate::{
+  utilities::{Vector2, AABB},
+  resources::Viewport,
+};
+
+static PANEL_WIDTH : f64 = 120.0; // In actual space
+static PANEL_MARGIN : f64 = 10.0; // In actual space
+static ROW_HEIGHT : f64 = 8.0; // In actual space
+
+/// A single construction step, e.g. "Line a through A, B"
+#[derive(Debug, Clone)]
+pub struct Step {
+  pub entity: Entity,
+  pub description: String,
+}
+
+/// # Construction Protocol
+///
+/// Every geometry in the order it was created. Points are named with upper
+/// case letters and everything else with lower case ones. The protocol can
+/// be stepped through, in which case only the geometries created up to the
+/// current step are shown.
+///
+/// The panel is a column of rows on the right of the window, and the
+/// description of the hovered row goes to the window title.
+#[derive(Default)]
+pub struct ConstructionProtocol {
+  steps: Vec<Step>,
+  names: HashMap<Entity, String>,
+  point_name_count: usize,
+  other_name_count: usize,
+  current: Option<usize>, // None when every step is shown
+  pub panel_visible: bool,
+}
+
+impl ConstructionProtocol {
+  pub fn name_point(&mut self, entity: Entity) -> String {
+    let name = nth_name(self.point_name_count, b'A');
+    self.point_name_count += 1;
+    self.names.insert(entity, name.clone());
+    name
+  }
+
+  pub fn name_other(&mut self, entity: Entity) -> String {
+    let name = nth_name(self.other_name_count, b'a');
+    self.other_name_count += 1;
+    self.names.insert(entity, name.clone());
+    name
+  }
+
+  pub fn name_of(&self, entity: Entity) -> String {
+    self.names.get(&entity).cloned().unwrap_or_else(|| "?".to_string())
+  }
+
+  pub fn entity_named(&self, name: &str) -> Option<Entity> {
+    self.names.iter().find(|(_, n)| *n == name).map(|(ent, _)| *ent)
+  }
+
+  pub fn push(&mut self, entity: Entity, description: String) {
+    self.steps.push(Step { entity, description });
+  }
+
+  pub fn describe(&mut self, entity: Entity, description: String) {
+    if let Some(step) = self.steps.iter_mut().find(|step| step.entity == entity) {
+      step.description = description;
+    }
+  }
+
+  pub fn remove(&mut self, entity: Entity) {
+    if let Some(index) = self.steps.iter().position(|step| step.entity == entity) {
+      self.steps.remove(index);
+      self.names.remove(&entity);
+      if let Some(current) = self.current {
+        self.current = if index <= current { current.checked_sub(1) } else { Some(current) };
+      }
+    }
+  }
+
+  /// Move steps down so that every step comes after the steps of its
+  /// parents, keeping the rest in the order they were created. Needed once a
+  /// geometry is defined on one created after it
+  pub fn reorder<F: Fn(Entity) -> Vec<Entity>>(&mut self, parents_of: F) {
+    let current_entity = self.current.map(|current| self.steps[current].entity);
+    let index_of : HashMap<Entity, usize> = self.steps.iter().enumerate().map(|(i, step)| (step.entity, i)).collect();
+    let mut placed = HashSet::new();
+    let mut order = Vec::with_capacity(self.steps.len());
+    for i in 0..self.steps.len() {
+
+      // Place the parents first, depth first. A step is expanded once
+      let mut stack = vec![(i, false)];
+      let mut expanded = HashSet::new();
+      while let Some((index, parents_placed)) = stack.pop() {
+        if placed.contains(&index) {
+          continue;
+        }
+        if parents_placed {
+          placed.insert(index);
+          order.push(index);
+        } else if expanded.insert(index) {
+          stack.push((index, true));
+          for parent in parents_of(self.steps[index].entity) {
+            if let Some(parent_index) = index_of.get(&parent) {
+              stack.push((*parent_index, false));
+            }
+          }
+        }
+      }
+    }
+    let mut steps : Vec<Option<Step>> = self.steps.drain(..).map(Some).collect();
+    self.steps = order.into_iter().filter_map(|index| steps[index].take()).collect();
+    if let Some(entity) = current_entity {
+      self.current = self.steps.iter().position(|step| step.entity == entity);
+    }
+  }
+
+  pub fn steps(&self) -> &[Step] {
+    &self.steps
+  }
+
+  /// The given entities in the order they were created, so that parents
+  /// come before their children
+  pub fn creation_order(&self, ents: &HashSet<Entity>) -> Vec<Entity> {
+    self.steps.iter().map(|step| step.entity).filter(|ent| ents.contains(ent)).collect()
+  }
+
+  /// The index of the last shown step, if any step is shown
+  pub fn current(&self) -> Option<usize> {
+    match self.current {
+      Some(current) => Some(current),
+      None => self.steps.len().checked_sub(1),
+    }
+  }
+
+  pub fn is_stepping(&self) -> bool {
+    self.current.is_some()
+  }
+
+  pub fn is_shown(&self, index: usize) -> bool {
+    match self.current {
+      Some(current) => index <= current,
+      None => true,
+    }
+  }
+
+  /// Hide the last shown step. The first step is always shown
+  pub fn step_backward(&mut self) -> bool {
+    match self.current() {
+      Some(current) if current > 0 => {
+        self.current = Some(current - 1);
+        true
+      },
+      _ => false,
+    }
+  }
+
+  pub fn step_forward(&mut self) -> bool {
+    match self.current {
+      Some(current) => {
+        self.current = if current + 2 >= self.steps.len() { None } else { Some(current + 1) };
+        true
+      },
+      None => false,
+    }
+  }
+
+  pub fn show_all(&mut self) {
+    self.current = None;
+  }
+
+  /// The row of the `index`th step on the panel, in actual space
+  pub fn row_aabb(&self, index: usize, vp: &Viewport) -> AABB {
+    let x = vp.actual_width() - PANEL_MARGIN - PANEL_WIDTH;
+    let y = PANEL_MARGIN + index as f64 * ROW_HEIGHT;
+    AABB::new(x, y, PANEL_WIDTH, ROW_HEIGHT)
+  }
+
+  /// The step whose row is at the actual position, if the panel is visible
+  pub fn row_at(&self, p: Vector2, vp: &Viewport) -> Option<usize> {
+    if !self.panel_visible || self.steps.is_empty() {
+      return None;
+    }
+    let AABB { x, y, width, .. } = self.row_aabb(0, vp);
+    if p.x < x || p.x > x + width || p.y < y {
+      return None;
+    }
+    let index = ((p.y - y) / ROW_HEIGHT) as usize;
+    if index < self.steps.len() { Some(index) } else { None }
+  }
+}
+
+/// A, B, ..., Z, A1, B1, ..., Z1, A2, ...
+fn nth_name(n: usize, first: u8) -> String {
+  let letter = (first + (n % 26) as u8) as char;
+  match n / 26 {
+    0 => letter.to_string(),
+    round => format!("{}{}", letter, round),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn protocol_with_steps(count: usize) -> (World, ConstructionProtocol) {
+    let mut world = World::new();
+    let mut protocol = ConstructionProtocol::default();
+    for i in 0..count {
+      let entity = world.create_entity().build();
+      protocol.push(entity, format!("Step {}", i));
+    }
+    (world, protocol)
+  }
+
+  #[test]
+  fn test_nth_name() {
+    assert_eq!(nth_name(0, b'A'), "A");
+    assert_eq!(nth_name(25, b'A'), "Z");
+    assert_eq!(nth_name(26, b'A'), "A1");
+    assert_eq!(nth_name(53, b'a'), "b2");
+  }
+
+  #[test]
+  fn test_step_backward_and_forward() {
+    let (_world, mut protocol) = protocol_with_steps(3);
+    assert_eq!(protocol.current(), Some(2));
+    assert!(!protocol.step_forward());
+
+    assert!(protocol.step_backward());
+    assert!(protocol.step_backward());
+    assert!(!protocol.step_backward());
+    assert_eq!(protocol.current(), Some(0));
+    assert!(protocol.is_shown(0));
+    assert!(!protocol.is_shown(1));
+
+    assert!(protocol.step_forward());
+    assert!(protocol.step_forward());
+    assert!(!protocol.is_stepping());
+    assert!(protocol.is_shown(2));
+  }
+
+  #[test]
+  fn test_remove_keeps_current_step() {
+    let (_world, mut protocol) = protocol_with_steps(4);
+    protocol.step_backward();
+    protocol.step_backward();
+    assert_eq!(protocol.current(), Some(1));
+
+    let first = protocol.steps()[0].entity;
+    protocol.remove(first);
+    assert_eq!(protocol.current(), Some(0));
+    assert_eq!(protocol.steps()[0].description, "Step 1");
+  }
+
+  #[test]
+  fn test_reorder() {
+    let (_world, mut protocol) = protocol_with_steps(4);
+    let ents : Vec<Entity> = protocol.steps().iter().map(|step| step.entity).collect();
+
+    // The first step is now defined on the third one, and the second one on
+    // the first one
+    protocol.reorder(|ent| {
+      if ent == ents[0] { vec![ents[2]] } else if ent == ents[1] { vec![ents[0]] } else { vec![] }
+    });
+    let reordered : Vec<Entity> = protocol.steps().iter().map(|step| step.entity).collect();
+    assert_eq!(reordered, vec![ents[2], ents[0], ents[1], ents[3]]);
+  }
+
+  #[test]
+  fn test_row_at() {
+    let vp = Viewport::new(vec2![0., 0.], vec2![2., 2.], vec2![400., 400.]);
+    let (_world, mut protocol) = protocol_with_steps(3);
+    let inside = vec2![400. - PANEL_MARGIN - 1., PANEL_MARGIN + ROW_HEIGHT * 1.5];
+    assert_eq!(protocol.row_at(inside, &vp), None);
+
+    protocol.panel_visible = true;
+    assert_eq!(protocol.row_at(inside, &vp), Some(1));
+    assert_eq!(protocol.row_at(vec2![200., PANEL_MARGIN + 1.], &vp), None);
+    assert_eq!(protocol.row_at(vec2![inside.x, PANEL_MARGIN + ROW_HEIGHT * 3.5], &vp), None);
+  }
+}
\ No newline at end of file
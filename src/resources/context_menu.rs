@@ -0,0 +1,133 @@
+use specs::prelude::*;
+use crate::utilities::{Vector2, AABB};
+
+static ROW_WIDTH : f64 = 80.0; // In actual space
+static ROW_HEIGHT : f64 = 12.0; // In actual space
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum MenuEntry {
+  Delete,
+  Hide,
+  Style,
+  Fill,
+  ConstructPerpendicular,
+  ConstructTangent,
+  Properties,
+}
+
+impl MenuEntry {
+  pub fn label(&self) -> &'static str {
+    match self {
+      MenuEntry::Delete => "Delete",
+      MenuEntry::Hide => "Hide",
+      MenuEntry::Style => "Change color",
+      MenuEntry::Fill => "Change fill",
+      MenuEntry::ConstructPerpendicular => "Construct perpendicular",
+      MenuEntry::ConstructTangent => "Construct tangent",
+      MenuEntry::Properties => "Properties",
+    }
+  }
+}
+
+pub struct Menu {
+  pub entity: Entity, // What was right clicked
+  pub position: Vector2, // Top left corner, in actual space
+  pub entries: Vec<MenuEntry>,
+  pub dismissed: bool, // No longer shown, but the press closing it is not released yet
+}
+
+/// # Context Menu
+///
+/// The entries for what was right clicked, shown as a column of rows from
+/// where it was clicked, with the label of the hovered row in the window
+/// title. The next press executes the row under it, if any, and dismisses
+/// the menu. Until that press is released, the mouse is left to the menu
+/// alone.
+#[derive(Default)]
+pub struct ContextMenu {
+  maybe_menu: Option<Menu>,
+}
+
+impl ContextMenu {
+  pub fn open(&mut self, entity: Entity, position: Vector2, entries: Vec<MenuEntry>) {
+    self.maybe_menu = Some(Menu { entity, position, entries, dismissed: false });
+  }
+
+  pub fn dismiss(&mut self) {
+    if let Some(menu) = &mut self.maybe_menu {
+      menu.dismissed = true;
+    }
+  }
+
+  pub fn close(&mut self) {
+    self.maybe_menu = None;
+  }
+
+  /// Whether the mouse is left to the menu
+  pub fn is_active(&self) -> bool {
+    self.maybe_menu.is_some()
+  }
+
+  /// The menu, if it is shown
+  pub fn get(&self) -> Option<&Menu> {
+    self.maybe_menu.as_ref().filter(|menu| !menu.dismissed)
+  }
+
+  /// The row of the `index`th entry, in actual space
+  pub fn row_aabb(&self, index: usize) -> Option<AABB> {
+    self.get().map(|menu| AABB::new(menu.position.x, menu.position.y + index as f64 * ROW_HEIGHT, ROW_WIDTH, ROW_HEIGHT))
+  }
+
+  /// The entry whose row is at the actual position, if the menu is shown
+  pub fn entry_at(&self, p: Vector2) -> Option<MenuEntry> {
+    let menu = self.get()?;
+    let Vector2 { x, y } = p - menu.position;
+    if x < 0.0 || x > ROW_WIDTH || y < 0.0 {
+      return None;
+    }
+    menu.entries.get((y / ROW_HEIGHT) as usize).cloned()
+  }
+
+  /// The hovered entry, or else every entry
+  pub fn status(&self, mouse_pos: Vector2) -> Option<String> {
+    self.get().map(|menu| match self.entry_at(mouse_pos) {
+      Some(entry) => entry.label().to_string(),
+      None => menu.entries.iter().map(MenuEntry::label).collect::<Vec<_>>().join(" | "),
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_entry_at() {
+    let mut world = World::new();
+    let entity = world.create_entity().build();
+    let mut menu = ContextMenu::default();
+    assert_eq!(menu.entry_at(vec2![0., 0.]), None);
+
+    menu.open(entity, vec2![100., 50.], vec![MenuEntry::Delete, MenuEntry::Hide]);
+    assert_eq!(menu.entry_at(vec2![110., 55.]), Some(MenuEntry::Delete));
+    assert_eq!(menu.entry_at(vec2![110., 55. + ROW_HEIGHT]), Some(MenuEntry::Hide));
+    assert_eq!(menu.entry_at(vec2![110., 55. + 2. * ROW_HEIGHT]), None);
+    assert_eq!(menu.entry_at(vec2![90., 55.]), None);
+    assert_eq!(menu.status(vec2![0., 0.]), Some("Delete | Hide".to_string()));
+  }
+
+  #[test]
+  fn test_dismiss() {
+    let mut world = World::new();
+    let entity = world.create_entity().build();
+    let mut menu = ContextMenu::default();
+    menu.open(entity, vec2![0., 0.], vec![MenuEntry::Delete]);
+    menu.dismiss();
+    assert!(menu.is_active());
+    assert!(menu.get().is_none());
+    assert_eq!(menu.entry_at(vec2![1., 1.]), None);
+
+    menu.close();
+    assert!(!menu.is_active());
+  }
+}
\ No newline at end of file
@@ -0,0 +1,487 @@
+use std::fmt;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use specs::{prelude::*, world::EntitiesRes};
+use crate::{
+  utilities::{Vector2, Color, Expression},
+  components::*,
+  resources::events::Geometry,
+};
+
+/// A geometry whose references are slots, i.e. indices into the list of
+/// geometries it is part of, instead of entities. It can then be
+/// instantiated again with other entities, and written to a file as a line
+/// of text like `line-two-points 0 1 | 2 0 0 1 1`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Definition {
+  kind: String,
+  slots: Vec<usize>,
+  numbers: Vec<f64>, // Parameters of the geometry, then its style
+  expressions: Vec<Expression>,
+}
+
+impl Definition {
+  /// The definition of the geometry, if every one of its references has a
+  /// slot
+  pub fn new<F: Fn(Entity) -> Option<usize>>(geom: &Geometry, slot_of: F) -> Option<Self> {
+    let (kind, numbers, expressions) : (&str, Vec<f64>, Vec<Expression>) = match geom {
+      Geometry::Point(sym_point, style) => {
+        let style = point_style_numbers(style);
+        match sym_point {
+          SymbolicPoint::Free(p) => ("point-free", concat(&[p.x, p.y], &style), vec![]),
+          SymbolicPoint::OnLine(_, t) => ("point-on-line", concat(&[*t], &style), vec![]),
+          SymbolicPoint::LineLineIntersect(_, _) => ("point-line-line", style, vec![]),
+          SymbolicPoint::OnArc(_, t) => ("point-on-arc", concat(&[*t], &style), vec![]),
+          SymbolicPoint::OnConic(_, t) => ("point-on-conic", concat(&[*t], &style), vec![]),
+          SymbolicPoint::OnFunction(_, x) => ("point-on-function", concat(&[*x], &style), vec![]),
+          SymbolicPoint::FunctionLineIntersect(_, _, x) => ("point-function-line", concat(&[*x], &style), vec![]),
+          SymbolicPoint::OnCurve(_, t) => ("point-on-curve", concat(&[*t], &style), vec![]),
+        }
+      },
+      Geometry::Line(sym_line, style) => {
+        let style = stroke_numbers(style.width, style.color);
+        match sym_line {
+          SymbolicLine::TwoPoints(_, _) => ("line-two-points", style, vec![]),
+          SymbolicLine::Parallel(_, _) => ("line-parallel", style, vec![]),
+          SymbolicLine::Perpendicular(_, _) => ("line-perpendicular", style, vec![]),
+          SymbolicLine::Tangent(_, _, left) => ("line-tangent", concat(&[if *left { 1.0 } else { 0.0 }], &style), vec![]),
+          SymbolicLine::TangentAt(_) => ("line-tangent-at", style, vec![]),
+        }
+      },
+      Geometry::Arc(sym_arc, style) => {
+        let style = arc_style_numbers(style);
+        match sym_arc {
+          SymbolicArc::CenterTwoPoints(_, _, _) => ("arc-center-two-points", style, vec![]),
+          SymbolicArc::ThreePoints(_, _, _) => ("arc-three-points", style, vec![]),
+        }
+      },
+      Geometry::Conic(sym_conic, style) => {
+        let style = stroke_numbers(style.width, style.color);
+        match sym_conic {
+          SymbolicConic::Ellipse(_, _, _) => ("ellipse", style, vec![]),
+          SymbolicConic::Hyperbola(_, _, _) => ("hyperbola", style, vec![]),
+          SymbolicConic::Parabola(_, _) => ("parabola", style, vec![]),
+          SymbolicConic::FivePoints(_, _, _, _, _) => ("conic-five-points", style, vec![]),
+        }
+      },
+      Geometry::Function(sym_function, style) => ("function", stroke_numbers(style.width, style.color), vec![sym_function.expression.clone()]),
+      Geometry::Curve(sym_curve, style) => {
+        let style = stroke_numbers(style.width, style.color);
+        match sym_curve {
+          SymbolicCurve::Parametric { x, y, t_min, t_max } => ("curve-parametric", concat(&[*t_min, *t_max], &style), vec![x.clone(), y.clone()]),
+          SymbolicCurve::Polar { r, t_min, t_max } => ("curve-polar", concat(&[*t_min, *t_max], &style), vec![r.clone()]),
+        }
+      },
+      Geometry::Locus(_, style) => ("locus", stroke_numbers(style.width, style.color), vec![]),
+    };
+    let slots = geom.parents().into_iter().map(slot_of).collect::<Option<Vec<_>>>()?;
+    Some(Self { kind: kind.to_string(), slots, numbers, expressions })
+  }
+
+  pub fn slots(&self) -> &[usize] {
+    &self.slots
+  }
+
+  /// The same definition moved by `offset` if it is a free point. Anything
+  /// else follows what it is defined on
+  pub fn translated(&self, offset: Vector2) -> Self {
+    let mut result = self.clone();
+    if result.kind == "point-free" {
+      result.numbers[0] += offset.x;
+      result.numbers[1] += offset.y;
+    }
+    result
+  }
+
+  /// The geometry with the given entities in its slots
+  pub fn geometry<F: Fn(usize) -> Option<Entity>>(&self, entity_of: F) -> Result<Geometry, String> {
+    let e = self.slots.iter().map(|slot| entity_of(*slot).ok_or_else(|| format!("nothing in slot {}", slot))).collect::<Result<Vec<_>, _>>()?;
+    let n = &self.numbers[..];
+    let x = &self.expressions[..];
+    let geom = match (&self.kind[..], &e[..], n.len(), x.len()) {
+      ("point-free", [], 7, 0) => Geometry::Point(SymbolicPoint::Free(vec2![n[0], n[1]]), point_style(&n[2..])),
+      ("point-on-line", [l], 6, 0) => Geometry::Point(SymbolicPoint::OnLine(*l, n[0]), point_style(&n[1..])),
+      ("point-line-line", [l1, l2], 5, 0) => Geometry::Point(SymbolicPoint::LineLineIntersect(*l1, *l2), point_style(n)),
+      ("point-on-arc", [a], 6, 0) => Geometry::Point(SymbolicPoint::OnArc(*a, n[0]), point_style(&n[1..])),
+      ("point-on-conic", [c], 6, 0) => Geometry::Point(SymbolicPoint::OnConic(*c, n[0]), point_style(&n[1..])),
+      ("point-on-function", [f], 6, 0) => Geometry::Point(SymbolicPoint::OnFunction(*f, n[0]), point_style(&n[1..])),
+      ("point-function-line", [f, l], 6, 0) => Geometry::Point(SymbolicPoint::FunctionLineIntersect(*f, *l, n[0]), point_style(&n[1..])),
+      ("point-on-curve", [c], 6, 0) => Geometry::Point(SymbolicPoint::OnCurve(*c, n[0]), point_style(&n[1..])),
+      ("line-two-points", [p1, p2], 5, 0) => {
+        let (width, color) = stroke(n);
+        Geometry::Line(SymbolicLine::TwoPoints(*p1, *p2), LineStyle { width, color })
+      },
+      ("line-parallel", [l, p], 5, 0) => {
+        let (width, color) = stroke(n);
+        Geometry::Line(SymbolicLine::Parallel(*l, *p), LineStyle { width, color })
+      },
+      ("line-perpendicular", [l, p], 5, 0) => {
+        let (width, color) = stroke(n);
+        Geometry::Line(SymbolicLine::Perpendicular(*l, *p), LineStyle { width, color })
+      },
+      ("line-tangent", [p, a], 6, 0) => {
+        let (width, color) = stroke(&n[1..]);
+        Geometry::Line(SymbolicLine::Tangent(*p, *a, n[0] != 0.0), LineStyle { width, color })
+      },
+      ("line-tangent-at", [p], 5, 0) => {
+        let (width, color) = stroke(n);
+        Geometry::Line(SymbolicLine::TangentAt(*p), LineStyle { width, color })
+      },
+      ("arc-center-two-points", [c, from, to], 10, 0) => Geometry::Arc(SymbolicArc::CenterTwoPoints(*c, *from, *to), arc_style(n)?),
+      ("arc-three-points", [from, through, to], 10, 0) => Geometry::Arc(SymbolicArc::ThreePoints(*from, *through, *to), arc_style(n)?),
+      ("ellipse", [f1, f2, p], 5, 0) => Geometry::Conic(SymbolicConic::Ellipse(*f1, *f2, *p), conic_style(n)),
+      ("hyperbola", [f1, f2, p], 5, 0) => Geometry::Conic(SymbolicConic::Hyperbola(*f1, *f2, *p), conic_style(n)),
+      ("parabola", [f, l], 5, 0) => Geometry::Conic(SymbolicConic::Parabola(*f, *l), conic_style(n)),
+      ("conic-five-points", [p1, p2, p3, p4, p5], 5, 0) => Geometry::Conic(SymbolicConic::FivePoints(*p1, *p2, *p3, *p4, *p5), conic_style(n)),
+      ("function", refs, 5, 1) => {
+        let sym_function = SymbolicFunction { expression: x[0].clone(), references: refs.to_vec() };
+        if !sym_function.is_well_formed() {
+          return Err(format!("malformed {}", self.kind));
+        }
+        Geometry::Function(sym_function, plot_style(n))
+      },
+      ("curve-parametric", [], 7, 2) => Geometry::Curve(SymbolicCurve::Parametric { x: x[0].clone(), y: x[1].clone(), t_min: n[0], t_max: n[1] }, plot_style(&n[2..])),
+      ("curve-polar", [], 7, 1) => Geometry::Curve(SymbolicCurve::Polar { r: x[0].clone(), t_min: n[0], t_max: n[1] }, plot_style(&n[2..])),
+      ("locus", [driver, traced], 5, 0) => Geometry::Locus(SymbolicLocus { driver: *driver, traced: *traced }, plot_style(n)),
+      _ => return Err(format!("malformed {}", self.kind)),
+    };
+    Ok(geom)
+  }
+
+  /// The definition as it is displayed. Its kind and the number of its
+  /// slots, numbers and expressions are checked, but not what the slots
+  /// refer to
+  pub fn parse(line: &str) -> Result<Self, String> {
+    let mut parts = line.split('|').map(str::trim);
+    let mut head = parts.next().unwrap_or("").split_whitespace();
+    let kind = head.next().ok_or_else(|| "missing kind".to_string())?.to_string();
+    let slots = head.map(|s| s.parse::<usize>().map_err(|_| format!("bad slot `{}`", s))).collect::<Result<Vec<_>, _>>()?;
+    let numbers = parts.next().unwrap_or("").split_whitespace()
+      .map(|s| s.parse::<f64>().map_err(|_| format!("bad number `{}`", s)))
+      .collect::<Result<Vec<_>, _>>()?;
+    let expressions = parts
+      .map(|s| if kind == "function" { SymbolicFunction::parse_expression(s) } else { Expression::parse_with_variables(s, &["t"]) })
+      .map(|result| result.map_err(|err| err.to_string()))
+      .collect::<Result<Vec<_>, _>>()?;
+    let definition = Self { kind, slots, numbers, expressions };
+
+    // Any entity does to check the definition can be instantiated
+    let dummy = EntitiesRes::default().create();
+    definition.geometry(|_| Some(dummy))?;
+    Ok(definition)
+  }
+}
+
+impl fmt::Display for Definition {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}", self.kind)?;
+    for slot in &self.slots {
+      write!(f, " {}", slot)?;
+    }
+    write!(f, " |")?;
+    for number in &self.numbers {
+      write!(f, " {}", number)?;
+    }
+    for expression in &self.expressions {
+      write!(f, " | {}", expression)?;
+    }
+    Ok(())
+  }
+}
+
+fn concat(a: &[f64], b: &[f64]) -> Vec<f64> {
+  a.iter().chain(b.iter()).cloned().collect()
+}
+
+fn color_numbers(color: Color) -> [f64; 4] {
+  [color.r as f64, color.g as f64, color.b as f64, color.a as f64]
+}
+
+fn color(n: &[f64]) -> Color {
+  Color::new(n[0] as f32, n[1] as f32, n[2] as f32, n[3] as f32)
+}
+
+fn stroke_numbers(width: f64, color: Color) -> Vec<f64> {
+  concat(&[width], &color_numbers(color))
+}
+
+fn stroke(n: &[f64]) -> (f64, Color) {
+  (n[0], color(&n[1..5]))
+}
+
+fn point_style_numbers(style: &PointStyle) -> Vec<f64> {
+  stroke_numbers(style.radius, style.color)
+}
+
+fn point_style(n: &[f64]) -> PointStyle {
+  let (radius, color) = stroke(n);
+  PointStyle { radius, color }
+}
+
+fn conic_style(n: &[f64]) -> ConicStyle {
+  let (width, color) = stroke(n);
+  ConicStyle { width, color }
+}
+
+fn plot_style(n: &[f64]) -> PlotStyle {
+  let (width, color) = stroke(n);
+  PlotStyle { width, color }
+}
+
+fn arc_style_numbers(style: &ArcStyle) -> Vec<f64> {
+  let (fill, fill_color) = match style.fill {
+    ArcFill::None => (0.0, [0.0; 4]),
+    ArcFill::Sector(color) => (1.0, color_numbers(color)),
+    ArcFill::Segment(color) => (2.0, color_numbers(color)),
+  };
+  concat(&concat(&stroke_numbers(style.width, style.color), &[fill]), &fill_color)
+}
+
+fn arc_style(n: &[f64]) -> Result<ArcStyle, String> {
+  let (width, stroke_color) = stroke(n);
+  let fill = match n[5] as i32 {
+    0 => ArcFill::None,
+    1 => ArcFill::Sector(color(&n[6..10])),
+    2 => ArcFill::Segment(color(&n[6..10])),
+    _ => return Err("bad arc fill".to_string()),
+  };
+  Ok(ArcStyle { width, color: stroke_color, fill })
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum GivenKind {
+  Point,
+  Line,
+}
+
+/// A construction saved to be repeated on other givens. The slots of its
+/// steps are the givens first, then the steps before it
+#[derive(Debug, Clone, PartialEq)]
+pub struct CustomTool {
+  pub name: String,
+  pub givens: Vec<GivenKind>,
+  pub steps: Vec<Definition>,
+}
+
+/// A custom tool being applied, waiting for its givens to be picked
+pub struct Application {
+  pub tool: usize,
+  pub picks: Vec<Entity>,
+}
+
+/// # Custom Tool Library
+///
+/// The custom tools, which are kept in a library file so that they can be
+/// shared. A tool is defined by first marking its givens and then naming it
+/// with its results selected. Applying a tool waits for its givens to be
+/// picked one by one in order. A library file that cannot be loaded is never
+/// saved over, so that the tools in it are not lost.
+#[derive(Default)]
+pub struct CustomToolLibrary {
+  tools: Vec<CustomTool>,
+  load_error: Option<String>,
+  pub pending_givens: Option<Vec<Entity>>,
+  pub application: Option<Application>,
+}
+
+impl CustomToolLibrary {
+  pub fn tools(&self) -> &[CustomTool] {
+    &self.tools
+  }
+
+  pub fn find(&self, name: &str) -> Option<usize> {
+    self.tools.iter().position(|tool| tool.name == name)
+  }
+
+  /// Add the tool, replacing the one with the same name if any
+  pub fn add(&mut self, tool: CustomTool) {
+    match self.find(&tool.name) {
+      Some(index) => self.tools[index] = tool,
+      None => self.tools.push(tool),
+    }
+  }
+
+  /// The kind of the next given to pick, if a tool is being applied
+  pub fn next_given(&self) -> Option<GivenKind> {
+    self.application.as_ref().and_then(|app| self.tools[app.tool].givens.get(app.picks.len()).cloned())
+  }
+
+  /// What the user is expected to do next, if anything
+  pub fn status(&self) -> Option<String> {
+    if let (Some(app), Some(kind)) = (&self.application, self.next_given()) {
+      let tool = &self.tools[app.tool];
+      let kind = match kind { GivenKind::Point => "point", GivenKind::Line => "line" };
+      Some(format!("{}: pick {} {} of {}", tool.name, kind, app.picks.len() + 1, tool.givens.len()))
+    } else if let Some(givens) = &self.pending_givens {
+      Some(format!("{} givens marked, select the results and press Cmd+G", givens.len()))
+    } else {
+      self.load_error.as_ref().map(|err| format!("Custom tools not loaded, {}", err))
+    }
+  }
+
+  /// Read the tools from a library file. A missing file is an empty library,
+  /// anything else that goes wrong is kept as the load error
+  pub fn load(&mut self, path: &Path) {
+    let result = match fs::read_to_string(path) {
+      Ok(text) => parse_library(&text).map(|tools| self.tools = tools),
+      Err(ref err) if err.kind() == ErrorKind::NotFound => Ok(()),
+      Err(err) => Err(err.to_string()),
+    };
+    self.load_error = result.err().map(|err| format!("{}: {}", path.display(), err));
+  }
+
+  pub fn save(&self, path: &Path) -> Result<(), String> {
+    if let Some(err) = &self.load_error {
+      return Err(format!("not saved over {}", err));
+    }
+    fs::write(path, write_library(&self.tools)).map_err(|err| err.to_string())
+  }
+}
+
+/// ```text
+/// tool Midpoint
+/// given point
+/// given point
+/// step line-two-points 0 1 | 2 0 0 1 1
+/// end
+/// ```
+pub fn write_library(tools: &[CustomTool]) -> String {
+  let mut text = String::new();
+  for tool in tools {
+    text.push_str(&format!("tool {}\n", tool.name));
+    for given in &tool.givens {
+      text.push_str(match given { GivenKind::Point => "given point\n", GivenKind::Line => "given line\n" });
+    }
+    for step in &tool.steps {
+      text.push_str(&format!("step {}\n", step));
+    }
+    text.push_str("end\n");
+  }
+  text
+}
+
+pub fn parse_library(text: &str) -> Result<Vec<CustomTool>, String> {
+  let mut tools = vec![];
+  let mut current : Option<CustomTool> = None;
+  for (i, line) in text.lines().enumerate().map(|(i, line)| (i + 1, line.trim())) {
+    let (keyword, rest) = match line.find(' ') {
+      Some(index) => (&line[..index], line[index + 1..].trim()),
+      None => (line, ""),
+    };
+    match (keyword, &mut current) {
+      ("", _) => (),
+      ("tool", None) => current = Some(CustomTool { name: rest.to_string(), givens: vec![], steps: vec![] }),
+      ("given", Some(tool)) if tool.steps.is_empty() => match rest {
+        "point" => tool.givens.push(GivenKind::Point),
+        "line" => tool.givens.push(GivenKind::Line),
+        _ => return Err(format!("line {}: unknown given `{}`", i, rest)),
+      },
+      ("step", Some(tool)) => {
+        let step = Definition::parse(rest).map_err(|err| format!("line {}: {}", i, err))?;
+        if step.slots().iter().any(|slot| *slot >= tool.givens.len() + tool.steps.len()) {
+          return Err(format!("line {}: step refers to a later slot", i));
+        }
+        tool.steps.push(step);
+      },
+      ("end", Some(_)) => tools.extend(current.take()),
+      _ => return Err(format!("line {}: unexpected `{}`", i, line)),
+    }
+  }
+  match current {
+    Some(_) => Err("missing end of the last tool".to_string()),
+    None => Ok(tools),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_definition_round_trip() {
+    let mut world = World::new();
+    let (a, b) = (world.create_entity().build(), world.create_entity().build());
+    let style = LineStyle { width: 2., color: Color::blue() };
+    let geom = Geometry::Line(SymbolicLine::TwoPoints(a, b), style);
+    let def = Definition::new(&geom, |ent| if ent == a { Some(0) } else if ent == b { Some(1) } else { None }).unwrap();
+    assert_eq!(def.to_string(), "line-two-points 0 1 | 2 0 0 1 1");
+    assert_eq!(Definition::parse(&def.to_string()), Ok(def.clone()));
+
+    match def.geometry(|slot| [b, a].get(slot).cloned()) {
+      Ok(Geometry::Line(SymbolicLine::TwoPoints(p1, p2), _)) => assert_eq!((p1, p2), (b, a)),
+      _ => panic!("expected a line through two points"),
+    }
+  }
+
+  #[test]
+  fn test_definition_with_expressions() {
+    let def = Definition::parse("curve-parametric | 0 1 2 0 0 1 1 | cos(t) | sin(t)").unwrap();
+    assert_eq!(Definition::parse(&def.to_string()), Ok(def.clone()));
+    match def.geometry(|_| None) {
+      Ok(Geometry::Curve(curve, _)) => assert_eq!(curve.range(), (0., 1.)),
+      _ => panic!("expected a curve"),
+    }
+  }
+
+  #[test]
+  fn test_function_definition() {
+    let mut world = World::new();
+    let a = world.create_entity().build();
+    let def = Definition::parse("function 0 | 2 0 0 1 1 | p0_y * x").unwrap();
+    match def.geometry(|slot| [a].get(slot).cloned()) {
+      Ok(Geometry::Function(sym_function, _)) => assert_eq!(sym_function.named(|_| "A".to_string()).to_string(), "A_y * x"),
+      _ => panic!("expected a function"),
+    }
+    assert!(Definition::parse("function 0 | 2 0 0 1 1 | p1_y * x").is_err());
+    assert!(Definition::parse("function 0 | 2 0 0 1 1 | A_y * x").is_err());
+  }
+
+  #[test]
+  fn test_translated_definition() {
+    let def = Definition::parse("point-free | 1 2 5 1 0 0 1").unwrap();
+    assert_eq!(def.translated(vec2![1., -1.]).to_string(), "point-free | 2 1 5 1 0 0 1");
+    let def = Definition::parse("point-on-line 0 | 1 5 1 0 0 1").unwrap();
+    assert_eq!(def.translated(vec2![1., -1.]), def);
+  }
+
+  #[test]
+  fn test_malformed_definition() {
+    let def = Definition::parse("line-two-points 0 1 | 2 0 0 1 1").unwrap();
+    assert!(def.geometry(|_| None).is_err());
+    assert!(Definition::parse("line-two-points 0 | 2 0 0 1 1").is_err());
+    assert!(Definition::parse("point-free | 1 2").is_err());
+    assert!(Definition::parse("point-free | 1 x").is_err());
+  }
+
+  #[test]
+  fn test_library_round_trip() {
+    let tools = vec![CustomTool {
+      name: "Line through two points".to_string(),
+      givens: vec![GivenKind::Point, GivenKind::Point],
+      steps: vec![Definition::parse("line-two-points 0 1 | 2 0 0 1 1").unwrap()],
+    }];
+    assert_eq!(parse_library(&write_library(&tools)), Ok(tools));
+  }
+
+  #[test]
+  fn test_library_errors() {
+    assert!(parse_library("tool A\ngiven point\n").is_err());
+    assert!(parse_library("given point\n").is_err());
+    assert!(parse_library("tool A\ngiven point\nstep line-two-points 0 1 | 2 0 0 1 1\nend\n").is_err());
+    assert!(parse_library("tool A\ngiven point\nstep point-free | 1 2\nend\n").is_err());
+    assert!(parse_library("tool A\ngiven point\nstep circle 0 | 2 0 0 1 1\nend\n").is_err());
+  }
+
+  #[test]
+  fn test_library_not_saved_over() {
+    let path = std::env::temp_dir().join("test_library_not_saved_over.txt");
+    fs::write(&path, "tool A\n").unwrap();
+    let mut library = CustomToolLibrary::default();
+    library.load(&path);
+    assert!(library.status().is_some());
+    assert!(library.save(&path).is_err());
+    assert_eq!(fs::read_to_string(&path).unwrap(), "tool A\n");
+    fs::remove_file(&path).unwrap();
+  }
+}
\ No newline at end of file
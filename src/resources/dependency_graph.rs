@@ -1,25 +1,62 @@
 use std::collections::{HashMap, HashSet};
 use specs::prelude::*;
 
-pub struct DependencyGraph(HashMap<Entity, HashSet<Entity>>);
+/// # Dependency Graph
+///
+/// Which geometries are defined on which, in both directions: from a parent
+/// to its children and from a child to its parents.
+pub struct DependencyGraph {
+  children: HashMap<Entity, HashSet<Entity>>,
+  parents: HashMap<Entity, HashSet<Entity>>,
+}
 
 impl Default for DependencyGraph {
   fn default() -> Self {
-    Self(HashMap::new())
+    Self { children: HashMap::new(), parents: HashMap::new() }
   }
 }
 
 impl DependencyGraph {
   pub fn add(&mut self, parent: &Entity, child: &Entity) {
-    self.0.entry(*parent).or_insert(HashSet::new()).insert(*child);
+    self.children.entry(*parent).or_default().insert(*child);
+    self.parents.entry(*child).or_default().insert(*parent);
+  }
+
+  /// Remove the entity along with every edge from or to it
+  pub fn remove(&mut self, entity: &Entity) {
+    if let Some(children) = self.children.remove(entity) {
+      for child in children {
+        if let Some(parents) = self.parents.get_mut(&child) {
+          parents.remove(entity);
+        }
+      }
+    }
+    if let Some(parents) = self.parents.remove(entity) {
+      for parent in parents {
+        if let Some(children) = self.children.get_mut(&parent) {
+          children.remove(entity);
+        }
+      }
+    }
   }
 
-  pub fn remove(&mut self, parent: &Entity) {
-    self.0.remove(parent);
+  /// Remove the edges from the parents of the entity, keeping its children
+  pub fn remove_parents(&mut self, child: &Entity) {
+    if let Some(parents) = self.parents.remove(child) {
+      for parent in parents {
+        if let Some(children) = self.children.get_mut(&parent) {
+          children.remove(child);
+        }
+      }
+    }
   }
 
   pub fn get_direct_dependents(&self, parent: &Entity) -> Option<&HashSet<Entity>> {
-    self.0.get(parent)
+    self.children.get(parent)
+  }
+
+  pub fn get_direct_parents(&self, child: &Entity) -> Option<&HashSet<Entity>> {
+    self.parents.get(child)
   }
 
   /// Get all the dependents of the parent, including parent itself
@@ -28,14 +65,115 @@ impl DependencyGraph {
     let mut stack : Vec<Entity> = vec![*parent];
 
     while let Some(dependent) = stack.pop() {
-      result.insert(dependent);
-      if let Some(children) = self.0.get(&dependent) {
-        for child in children {
-          stack.push(*child);
+      if result.insert(dependent) {
+        if let Some(children) = self.children.get(&dependent) {
+          for child in children {
+            stack.push(*child);
+          }
         }
       }
     }
 
     result
   }
+
+  /// The entities ordered so that each one comes after its parents among
+  /// them. Parents outside of `entities` are not considered. If some of them
+  /// depend on each other in a cycle, the ones that cannot be ordered are
+  /// returned as the error.
+  pub fn topological_order(&self, entities: &HashSet<Entity>) -> Result<Vec<Entity>, Vec<Entity>> {
+    let (order, stuck) = topological_order(entities, |ent| {
+      self.parents.get(ent).map_or(vec![], |parents| parents.iter().cloned().collect())
+    });
+    if stuck.is_empty() { Ok(order) } else { Err(stuck) }
+  }
+}
+
+/// Order the entities so that each one comes after its parents among them,
+/// given where to find the parents of each. Returns the order along with the
+/// entities that cannot be ordered, which are in or after a cycle.
+pub fn topological_order<F: Fn(&Entity) -> Vec<Entity>>(entities: &HashSet<Entity>, parents_of: F) -> (Vec<Entity>, Vec<Entity>) {
+  let mut parent_count : HashMap<Entity, usize> = HashMap::with_capacity(entities.len());
+  let mut children : HashMap<Entity, Vec<Entity>> = HashMap::new();
+  for ent in entities {
+    let mut count = 0;
+    for parent in parents_of(ent).into_iter().filter(|parent| entities.contains(parent)) {
+      children.entry(parent).or_default().push(*ent);
+      count += 1;
+    }
+    parent_count.insert(*ent, count);
+  }
+
+  // Kahn's algorithm, starting from the ones without parents
+  let mut ready : Vec<Entity> = parent_count.iter().filter(|(_, count)| **count == 0).map(|(ent, _)| *ent).collect();
+  let mut order = Vec::with_capacity(entities.len());
+  while let Some(ent) = ready.pop() {
+    order.push(ent);
+    for child in children.get(&ent).into_iter().flatten() {
+      if let Some(count) = parent_count.get_mut(child) {
+        *count -= 1;
+        if *count == 0 {
+          ready.push(*child);
+        }
+      }
+    }
+  }
+
+  let stuck = parent_count.into_iter().filter(|(_, count)| *count > 0).map(|(ent, _)| ent).collect();
+  (order, stuck)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn entities(count: usize) -> (World, Vec<Entity>) {
+    let mut world = World::new();
+    let ents = (0..count).map(|_| world.create_entity().build()).collect();
+    (world, ents)
+  }
+
+  #[test]
+  fn test_remove_both_directions() {
+    let (_world, ents) = entities(3);
+    let mut graph = DependencyGraph::default();
+    graph.add(&ents[0], &ents[1]);
+    graph.add(&ents[1], &ents[2]);
+
+    graph.remove(&ents[1]);
+    assert!(graph.get_direct_dependents(&ents[0]).unwrap().is_empty());
+    assert!(graph.get_direct_parents(&ents[2]).unwrap().is_empty());
+    assert_eq!(graph.get_all_dependents(&ents[0]).len(), 1);
+  }
+
+  #[test]
+  fn test_topological_order() {
+    let (_world, ents) = entities(4);
+    let mut graph = DependencyGraph::default();
+    graph.add(&ents[2], &ents[1]);
+    graph.add(&ents[1], &ents[0]);
+    graph.add(&ents[3], &ents[0]);
+
+    let order = graph.topological_order(&ents.iter().cloned().collect()).unwrap();
+    let position = |ent: Entity| order.iter().position(|e| *e == ent).unwrap();
+    assert!(position(ents[2]) < position(ents[1]));
+    assert!(position(ents[1]) < position(ents[0]));
+    assert!(position(ents[3]) < position(ents[0]));
+
+    // Parents outside of the entities are left out
+    assert_eq!(graph.topological_order(&[ents[0]].iter().cloned().collect()), Ok(vec![ents[0]]));
+  }
+
+  #[test]
+  fn test_topological_order_cycle() {
+    let (_world, ents) = entities(3);
+    let mut graph = DependencyGraph::default();
+    graph.add(&ents[0], &ents[1]);
+    graph.add(&ents[1], &ents[2]);
+    graph.add(&ents[2], &ents[1]);
+
+    let mut stuck = graph.topological_order(&ents.iter().cloned().collect()).unwrap_err();
+    stuck.sort();
+    assert_eq!(stuck, vec![ents[1], ents[2]]);
+  }
 }
\ No newline at end of file
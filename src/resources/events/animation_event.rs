@@ -0,0 +1,15 @@
+use shrev::{EventChannel, ReaderId};
+
+pub enum AnimationEvent {
+  TogglePlay, // Play or pause
+  Stop, // Pause and move everything back to where it started
+  ToggleSelected, // Start or stop animating the selected points
+  Faster,
+  Slower,
+  Reverse,
+  ToggleBounce,
+}
+
+pub type AnimationEventChannel = EventChannel<AnimationEvent>;
+
+pub type AnimationEventReader = ReaderId<AnimationEvent>;
\ No newline at end of file
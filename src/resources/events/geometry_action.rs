@@ -1,11 +1,42 @@
 use specs::prelude::Entity;
 use shrev::{EventChannel, ReaderId};
+use crate::{
+  utilities::Vector2,
+  resources::geometry::SnapPointType,
+};
 
 pub enum GeometryAction {
   SelectAll,
+  SelectAllPoints,
+  SelectAllLines,
+  SelectParents,
+  SelectChildren,
   DeselectAll,
   DeselectAllExcept(Entity),
   RemoveSelected,
+  CreateLocusFromSelected,
+  ToggleTraceSelected,
+  EraseTraces,
+  StepProtocolBackward,
+  StepProtocolForward,
+  SelectProtocolStep(usize),
+  MarkCustomToolGivens,
+  IterateSelected,
+  ChangeIterationDepth(isize),
+  CopySelected,
+  Paste,
+  DuplicateSelected,
+  RedefineSelected,
+  AttachPoint(Entity, SnapPointType), // Dropped free point, what it snaps to
+  HideSelected,
+  ShowAllHidden,
+  RecolorSelected,
+  RefillSelected, // Next fill of the selected arcs
+  ConstructPerpendicular(Entity, Vector2), // Line, virtual position of the new point on it
+  ConstructTangentAt(Entity, Vector2), // Arc, virtual position of the new point on it
+  ConstructTangentsFromSelected, // From the selected point to the circle of the selected arc
+  EditSelected,
+  EditPoint(Entity), // Clicked in the context menu, whatever else is selected
 }
 
 pub type GeometryActionChannel = EventChannel<GeometryAction>;
@@ -14,4 +14,10 @@ mod sketch_event;
 pub use sketch_event::*;
 
 mod mouse_event;
-pub use mouse_event::*;
\ No newline at end of file
+pub use mouse_event::*;
+
+mod prompt_event;
+pub use prompt_event::*;
+
+mod animation_event;
+pub use animation_event::*;
\ No newline at end of file
@@ -6,7 +6,7 @@ pub enum MouseEvent {
   MouseDown(Vector2),
   MouseUp(Vector2),
   Click(Vector2),
-  // RightClick(Vector2),
+  RightClick(Vector2),
   // DoubleClick(Vector2),
   DragBegin(Vector2), // absolute position
   DragMove(Vector2, Vector2), // relative movement, absolute position
@@ -0,0 +1,10 @@
+use shrev::{EventChannel, ReaderId};
+use crate::resources::PromptKind;
+
+/// Text submitted from a prompt. The handler closes the prompt when the text
+/// is accepted, or sets an error on it otherwise
+pub struct PromptEvent(pub PromptKind, pub String);
+
+pub type PromptEventChannel = EventChannel<PromptEvent>;
+
+pub type PromptEventReader = ReaderId<PromptEvent>;
\ No newline at end of file
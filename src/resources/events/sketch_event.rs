@@ -2,27 +2,71 @@ use specs::prelude::*;
 use shrev::{EventChannel, ReaderId};
 use crate::{
   utilities::Vector2,
-  components::{SymbolicLine, SymbolicPoint, LineStyle, PointStyle},
+  components::{SymbolicLine, SymbolicPoint, SymbolicArc, SymbolicConic, SymbolicFunction, SymbolicCurve, SymbolicLocus, LineStyle, PointStyle, ArcStyle, ConicStyle, PlotStyle},
 };
 
+// The removed geometries and the old values are not read by any system yet.
+// They are kept so that every event carries what it takes to revert it
+#[allow(dead_code)]
 pub enum SketchEvent {
   Select(Entity),
   Deselect(Entity),
   Insert(Entity, Geometry),
   Remove(Entity, Geometry),
   MovePoint(Entity, MovePoint),
+  Redefine(Entity, Geometry, Geometry), // entity, old_geometry, new_geometry
 }
 
 pub enum Geometry {
   Point(SymbolicPoint, PointStyle),
   Line(SymbolicLine, LineStyle),
+  Arc(SymbolicArc, ArcStyle),
+  Conic(SymbolicConic, ConicStyle),
+  Function(SymbolicFunction, PlotStyle),
+  Curve(SymbolicCurve, PlotStyle),
+  Locus(SymbolicLocus, PlotStyle),
 }
 
+#[allow(dead_code)]
 pub enum MovePoint {
   Free(Vector2, Vector2), // old_position, new_position
   OnLine(Entity, f64, f64), // line_entity, old_t, new_t
+  OnArc(Entity, f64, f64), // arc_entity, old_t, new_t
+  OnConic(Entity, f64, f64), // conic_entity, old_t, new_t
+  OnFunction(Entity, f64, f64), // function_entity, old_x, new_x
+  OnCurve(Entity, f64, f64), // curve_entity, old_t, new_t
 }
 
 pub type SketchEventChannel = EventChannel<SketchEvent>;
 
-pub type SketchEventReader = ReaderId<SketchEvent>;
\ No newline at end of file
+pub type SketchEventReader = ReaderId<SketchEvent>;
+
+impl Geometry {
+  pub fn parents(&self) -> Vec<Entity> {
+    match self {
+      Geometry::Point(sym_point, _) => sym_point.parents(),
+      Geometry::Line(sym_line, _) => sym_line.parents(),
+      Geometry::Arc(sym_arc, _) => sym_arc.parents(),
+      Geometry::Conic(sym_conic, _) => sym_conic.parents(),
+      Geometry::Function(sym_function, _) => sym_function.references.clone(),
+      Geometry::Curve(_, _) => vec![],
+      Geometry::Locus(sym_locus, _) => sym_locus.parents(),
+    }
+  }
+
+  /// The same geometry defined on the parents given by `f`
+  pub fn map_parents<F: Fn(Entity) -> Entity>(&self, f: F) -> Self {
+    match self {
+      Geometry::Point(sym_point, style) => Geometry::Point(sym_point.map_parents(f), *style),
+      Geometry::Line(sym_line, style) => Geometry::Line(sym_line.map_parents(f), *style),
+      Geometry::Arc(sym_arc, style) => Geometry::Arc(sym_arc.map_parents(f), *style),
+      Geometry::Conic(sym_conic, style) => Geometry::Conic(sym_conic.map_parents(f), *style),
+      Geometry::Function(sym_function, style) => {
+        let references = sym_function.references.iter().map(|ent| f(*ent)).collect();
+        Geometry::Function(SymbolicFunction { expression: sym_function.expression.clone(), references }, *style)
+      },
+      Geometry::Curve(sym_curve, style) => Geometry::Curve(sym_curve.clone(), *style),
+      Geometry::Locus(sym_locus, style) => Geometry::Locus(sym_locus.map_parents(f), *style),
+    }
+  }
+}
\ No newline at end of file
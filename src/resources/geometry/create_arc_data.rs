@@ -0,0 +1,7 @@
+use specs::prelude::*;
+
+#[derive(Default)]
+pub struct CreateArcData {
+  pub points: Vec<Entity>, // Points placed so far, in order (from, through) or (center, from)
+}
+
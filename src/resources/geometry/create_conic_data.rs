@@ -0,0 +1,8 @@
+use specs::prelude::*;
+
+#[derive(Default)]
+pub struct CreateConicData {
+  pub picks: Vec<Entity>, // Points, or the directrix line of a parabola, picked so far in order
+  pub picking_line: bool, // Whether the next pick is a line instead of a point
+}
+
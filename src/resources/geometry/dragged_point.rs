@@ -0,0 +1,20 @@
+use specs::prelude::*;
+
+/// The free point being dragged on its own, if any. Snapping skips it along
+/// with everything depending on it, as it cannot be attached to those.
+#[derive(Default)]
+pub struct DraggedPoint(Option<Entity>);
+
+impl DraggedPoint {
+  pub fn set(&mut self, entity: Entity) {
+    self.0 = Some(entity);
+  }
+
+  pub fn clear(&mut self) {
+    self.0 = None;
+  }
+
+  pub fn get(&self) -> Option<Entity> {
+    self.0
+  }
+}
\ No newline at end of file
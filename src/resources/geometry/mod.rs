@@ -1,6 +1,12 @@
 mod create_line_data;
 pub use create_line_data::*;
 
+mod create_arc_data;
+pub use create_arc_data::*;
+
+mod create_conic_data;
+pub use create_conic_data::*;
+
 mod last_active_point;
 pub use last_active_point::*;
 
@@ -8,4 +14,10 @@ mod snap_point;
 pub use snap_point::*;
 
 mod select_rectangle;
-pub use select_rectangle::*;
\ No newline at end of file
+pub use select_rectangle::*;
+
+mod selection_handles;
+pub use selection_handles::*;
+
+mod dragged_point;
+pub use dragged_point::*;
\ No newline at end of file
@@ -0,0 +1,65 @@
+use crate::utilities::{Vector2, AABB};
+
+static HANDLE_SIZE : f64 = 8.0; // Pixel
+static ROTATE_HANDLE_OFFSET : f64 = 24.0; // Pixel
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Handle {
+  Scale,
+  Rotate,
+}
+
+/// Bounding box of the selection in actual space, together with the virtual
+/// center everything is rotated or scaled around
+#[derive(Debug, Copy, Clone)]
+pub struct SelectionBox {
+  pub aabb: AABB,
+  pub center: Vector2,
+}
+
+impl SelectionBox {
+  pub fn handle_size(&self) -> f64 {
+    HANDLE_SIZE
+  }
+
+  pub fn scale_handles(&self) -> [Vector2; 4] {
+    let AABB { x, y, width, height } = self.aabb;
+    [vec2![x, y], vec2![x + width, y], vec2![x, y + height], vec2![x + width, y + height]]
+  }
+
+  /// The rotate handle sits above the middle of the top border
+  pub fn rotate_handle(&self) -> Vector2 {
+    vec2![self.aabb.x + self.aabb.width / 2.0, self.aabb.y - ROTATE_HANDLE_OFFSET]
+  }
+
+  pub fn hit(&self, p: Vector2) -> Option<Handle> {
+    if (self.rotate_handle() - p).magnitude() <= HANDLE_SIZE {
+      Some(Handle::Rotate)
+    } else if self.scale_handles().iter().any(|h| (*h - p).x.abs() <= HANDLE_SIZE / 2.0 && (*h - p).y.abs() <= HANDLE_SIZE / 2.0) {
+      Some(Handle::Scale)
+    } else {
+      None
+    }
+  }
+}
+
+#[derive(Default)]
+pub struct SelectionHandles(pub Option<SelectionBox>);
+
+impl SelectionHandles {
+  pub fn set(&mut self, selection_box: SelectionBox) {
+    self.0 = Some(selection_box);
+  }
+
+  pub fn clear(&mut self) {
+    self.0 = None;
+  }
+
+  pub fn get(&self) -> Option<SelectionBox> {
+    self.0
+  }
+
+  pub fn hit(&self, p: Vector2) -> Option<Handle> {
+    self.0.and_then(|selection_box| selection_box.hit(p))
+  }
+}
\ No newline at end of file
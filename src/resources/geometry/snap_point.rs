@@ -1,5 +1,8 @@
 use specs::prelude::*;
-use crate::utilities::Vector2;
+use crate::{
+  utilities::Vector2,
+  components::SymbolicPoint,
+};
 
 pub struct MaybeSnapPoint(Option<SnapPoint>);
 
@@ -34,6 +37,31 @@ pub enum SnapPointType {
   SnapOnPoint(Entity),
   SnapOnLine(Entity, f64), // f32 is t
   SnapOnIntersection(Entity, Entity),
+  SnapOnArc(Entity, f64), // f64 is t along the arc
+  SnapOnConic(Entity, f64), // f64 is the conic parameter
+  SnapOnFunction(Entity, f64), // f64 is x
+  SnapOnFunctionLineIntersection(Entity, Entity, f64), // (function, line, x)
+  SnapOnCurve(Entity, f64), // f64 is t
+  SnapOnGrid, // Only while dragging a point, which stays free
   // SnapOnCircle(Entity, f64), // f32 is theta
   NotSnapped,
+}
+
+impl SnapPointType {
+  /// The point defined on what is snapped to. There is none when snapped to
+  /// an existing point, to the grid or not snapped at all
+  pub fn symbolic_point(&self) -> Option<SymbolicPoint> {
+    match *self {
+      SnapPointType::SnapOnLine(line_ent, t) => Some(SymbolicPoint::OnLine(line_ent, t)),
+      SnapPointType::SnapOnIntersection(l1_ent, l2_ent) => Some(SymbolicPoint::LineLineIntersect(l1_ent, l2_ent)),
+      SnapPointType::SnapOnArc(arc_ent, t) => Some(SymbolicPoint::OnArc(arc_ent, t)),
+      SnapPointType::SnapOnConic(conic_ent, t) => Some(SymbolicPoint::OnConic(conic_ent, t)),
+      SnapPointType::SnapOnFunction(function_ent, x) => Some(SymbolicPoint::OnFunction(function_ent, x)),
+      SnapPointType::SnapOnCurve(curve_ent, t) => Some(SymbolicPoint::OnCurve(curve_ent, t)),
+      SnapPointType::SnapOnFunctionLineIntersection(function_ent, line_ent, x) => {
+        Some(SymbolicPoint::FunctionLineIntersect(function_ent, line_ent, x))
+      },
+      SnapPointType::SnapOnPoint(_) | SnapPointType::SnapOnGrid | SnapPointType::NotSnapped => None,
+    }
+  }
 }
\ No newline at end of file
@@ -9,6 +9,7 @@ pub struct InputState {
   pub rel_scroll: Vector2,
   pub in_focus: ActiveState,
   pub keyboard: Keyboard,
+  pub text: String, // Text typed in since last frame
 }
 
 impl Default for InputState {
@@ -21,6 +22,7 @@ impl Default for InputState {
       in_focus: ActiveState::default(),
       rel_scroll: vec2![0., 0.],
       keyboard: Keyboard::default(),
+      text: String::new(),
     }
   }
 }
@@ -33,6 +35,7 @@ impl InputState {
     self.in_focus.reset_relative_data();
     self.rel_scroll = vec2![0., 0.];
     self.keyboard.reset_relative_data();
+    self.text.clear();
   }
 }
 
@@ -121,6 +124,14 @@ impl Keyboard {
     self.is_activated(Key::LShift) || self.is_activated(Key::RShift)
   }
 
+  pub fn is_alt_activated(&self) -> bool {
+    self.is_activated(Key::LAlt) || self.is_activated(Key::RAlt)
+  }
+
+  pub fn is_ctrl_activated(&self) -> bool {
+    self.is_activated(Key::LCtrl) || self.is_activated(Key::RCtrl)
+  }
+
   pub fn reset_relative_data(&mut self) {
     for (_, state) in self.keys.iter_mut() {
       state.reset_relative_data();
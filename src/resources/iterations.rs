@@ -0,0 +1,105 @@
+use specs::prelude::*;
+
+static DEFAULT_DEPTH : usize = 3;
+static MAX_DEPTH : usize = 64;
+static MAX_GENERATED : usize = 10000; // Geometries generated by a single iteration
+
+/// A construction repeated on its own images. Every pre-image point is
+/// mapped to an image point depending on it, and everything depending on
+/// the pre-images is created again on the images, `depth` times.
+pub struct Iteration {
+  pub pre_images: Vec<Entity>,
+  pub images: Vec<Entity>,
+  pub depth: usize,
+  pub generated: Vec<Entity>,
+  pub dirty: bool, // Needs its generated geometries to be created again
+}
+
+impl Iteration {
+  pub fn new(pre_images: Vec<Entity>, images: Vec<Entity>) -> Self {
+    Self { pre_images, images, depth: DEFAULT_DEPTH, generated: vec![], dirty: true }
+  }
+
+  pub fn change_depth(&mut self, delta: isize) {
+    let depth = (self.depth as isize + delta).max(0) as usize;
+    if depth != self.depth && depth <= MAX_DEPTH {
+      self.depth = depth;
+      self.dirty = true;
+    }
+  }
+
+  /// The depth actually generated, so that there are not too many
+  /// geometries
+  pub fn generated_depth(&self, steps_per_generation: usize) -> usize {
+    MAX_GENERATED.checked_div(steps_per_generation).map_or(0, |max_depth| self.depth.min(max_depth))
+  }
+
+  pub fn involves(&self, entity: Entity) -> bool {
+    self.pre_images.contains(&entity) || self.images.contains(&entity) || self.generated.contains(&entity)
+  }
+}
+
+/// The image points being picked for the pre-images
+pub struct ImagePicking {
+  pub pre_images: Vec<Entity>,
+  pub images: Vec<Entity>,
+}
+
+/// # Iterations
+///
+/// Every iteration in the sketch. An iteration is made by selecting the
+/// pre-image points, then picking their images one by one in the same order.
+#[derive(Default)]
+pub struct Iterations {
+  pub iterations: Vec<Iteration>,
+  pub picking: Option<ImagePicking>,
+}
+
+impl Iterations {
+  /// What the user is expected to do next, if anything
+  pub fn status(&self) -> Option<String> {
+    self.picking.as_ref().map(|picking| {
+      format!("Iterate: pick the image of point {} of {}", picking.images.len() + 1, picking.pre_images.len())
+    })
+  }
+
+  /// Every geometry generated by an iteration
+  pub fn all_generated(&self) -> impl Iterator<Item = &Entity> {
+    self.iterations.iter().flat_map(|iteration| iteration.generated.iter())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn iteration() -> (World, Iteration) {
+    let mut world = World::new();
+    let (pre_image, image) = (world.create_entity().build(), world.create_entity().build());
+    (world, Iteration::new(vec![pre_image], vec![image]))
+  }
+
+  #[test]
+  fn test_change_depth() {
+    let (_world, mut iteration) = iteration();
+    iteration.dirty = false;
+    iteration.change_depth(-5);
+    assert_eq!(iteration.depth, 0);
+    assert!(iteration.dirty);
+
+    iteration.dirty = false;
+    iteration.change_depth(-1);
+    assert!(!iteration.dirty);
+    iteration.change_depth(MAX_DEPTH as isize + 1);
+    assert_eq!(iteration.depth, 0);
+  }
+
+  #[test]
+  fn test_generated_depth() {
+    let (_world, mut iteration) = iteration();
+    iteration.depth = 10;
+    assert_eq!(iteration.generated_depth(0), 0);
+    assert_eq!(iteration.generated_depth(3), 10);
+    assert_eq!(iteration.generated_depth(MAX_GENERATED / 4), 4);
+  }
+}
\ No newline at end of file
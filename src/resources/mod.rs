@@ -7,10 +7,28 @@ mod input_state;
 mod tool_state;
 mod spatial_hash_table;
 mod dependency_graph;
+mod prompt_state;
+mod trace_layer;
+mod construction_protocol;
+mod custom_tools;
+mod iterations;
+mod clipboard;
+mod solver_report;
+mod snap_settings;
+mod context_menu;
 
 pub use delta_time::DeltaTime;
 pub use viewport::*;
 pub use input_state::{InputState, ActiveState};
 pub use tool_state::{Tool, ToolState};
 pub use spatial_hash_table::SpatialHashTable;
-pub use dependency_graph::*;
\ No newline at end of file
+pub use dependency_graph::*;
+pub use prompt_state::*;
+pub use trace_layer::*;
+pub use construction_protocol::*;
+pub use custom_tools::*;
+pub use iterations::*;
+pub use clipboard::*;
+pub use solver_report::*;
+pub use snap_settings::*;
+pub use context_menu::*;
\ No newline at end of file
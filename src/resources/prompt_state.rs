@@ -0,0 +1,75 @@
+use specs::prelude::Entity;
+
+/// What a prompt is asking for, which also decides who handles the submitted
+/// text
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum PromptKind {
+  Function, // y = f(x)
+  Parametric, // (x(t), y(t)), optionally followed by the range of t
+  Polar, // r(t), optionally followed by the range of t
+  NewPoint, // x, y of a new free point
+  PointPosition(Entity), // x, y of the free point
+  PointParameter(Entity), // t of the point on line
+  CustomToolName, // Name of the custom tool made of the selection
+  ApplyCustomTool, // Name of the custom tool to apply
+  SnapSettings, // The snap settings, see `SnapSettings`
+}
+
+impl PromptKind {
+  pub fn label(&self) -> &'static str {
+    match self {
+      PromptKind::Function => "y = ",
+      PromptKind::Parametric => "(x(t), y(t), t_min, t_max) = ",
+      PromptKind::Polar => "(r(t), t_min, t_max) = ",
+      PromptKind::NewPoint | PromptKind::PointPosition(_) => "(x, y) = ",
+      PromptKind::PointParameter(_) => "t = ",
+      PromptKind::CustomToolName => "Tool name = ",
+      PromptKind::ApplyCustomTool => "Apply tool = ",
+      PromptKind::SnapSettings => "Snap = ",
+    }
+  }
+}
+
+pub struct Prompt {
+  pub kind: PromptKind,
+  pub text: String,
+  pub error: Option<String>,
+}
+
+/// # Prompt State
+///
+/// A single line of text being typed in by the user. There is no text
+/// rendering, so the prompt is shown in the window title. While a prompt is
+/// active, keyboard shortcuts are disabled.
+#[derive(Default)]
+pub struct PromptState {
+  maybe_prompt: Option<Prompt>,
+}
+
+impl PromptState {
+  pub fn open(&mut self, kind: PromptKind, text: String) {
+    self.maybe_prompt = Some(Prompt { kind, text, error: None });
+  }
+
+  pub fn close(&mut self) {
+    self.maybe_prompt = None;
+  }
+
+  pub fn is_active(&self) -> bool {
+    self.maybe_prompt.is_some()
+  }
+
+  pub fn get(&self) -> Option<&Prompt> {
+    self.maybe_prompt.as_ref()
+  }
+
+  pub fn get_mut(&mut self) -> Option<&mut Prompt> {
+    self.maybe_prompt.as_mut()
+  }
+
+  pub fn set_error(&mut self, error: String) {
+    if let Some(prompt) = &mut self.maybe_prompt {
+      prompt.error = Some(error);
+    }
+  }
+}
\ No newline at end of file
@@ -0,0 +1,128 @@
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// Whether a kind of snapping is on, and how close the mouse needs to be
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Snap {
+  pub enabled: bool,
+  pub threshold: f64, // Pixel
+}
+
+impl Snap {
+  fn new(threshold: f64) -> Self {
+    Self { enabled: true, threshold }
+  }
+}
+
+/// # Snap Settings
+///
+/// How points snap to each kind of thing, written as a single line like
+/// `point 12, line 6, intersection 15, grid 8 off`. The same line is typed
+/// into the snap prompt and saved in the preferences file. Holding Ctrl
+/// turns snapping off for the moment.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SnapSettings {
+  pub point: Snap,
+  pub line: Snap, // Also arcs, conics and plots
+  pub intersection: Snap,
+  pub grid: Snap, // Only while dragging a point
+}
+
+impl Default for SnapSettings {
+  fn default() -> Self {
+    Self {
+      point: Snap::new(12.0),
+      line: Snap::new(6.0),
+      intersection: Snap::new(15.0),
+      grid: Snap::new(8.0),
+    }
+  }
+}
+
+impl fmt::Display for SnapSettings {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let snaps = [("point", self.point), ("line", self.line), ("intersection", self.intersection), ("grid", self.grid)];
+    let entries : Vec<String> = snaps.iter()
+      .map(|(name, snap)| format!("{} {}{}", name, snap.threshold, if snap.enabled { "" } else { " off" }))
+      .collect();
+    write!(f, "{}", entries.join(", "))
+  }
+}
+
+impl SnapSettings {
+  /// Changes the settings named in the text, leaving the others alone. An
+  /// entry is the kind followed by its threshold, `on` or `off`, or both.
+  /// Nothing changes if any entry is wrong.
+  pub fn apply(&mut self, text: &str) -> Result<(), String> {
+    let mut settings = *self;
+    for entry in text.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+      let mut words = entry.split_whitespace();
+      let snap = match words.next() {
+        Some("point") => &mut settings.point,
+        Some("line") => &mut settings.line,
+        Some("intersection") => &mut settings.intersection,
+        Some("grid") => &mut settings.grid,
+        _ => return Err(format!("unknown snap `{}`", entry)),
+      };
+      for word in words {
+        match word {
+          "on" => snap.enabled = true,
+          "off" => snap.enabled = false,
+          _ => match word.parse::<f64>() {
+            Ok(threshold) if threshold > 0.0 && threshold.is_finite() => snap.threshold = threshold,
+            _ => return Err(format!("expected a positive number of pixels, `on` or `off` instead of `{}`", word)),
+          },
+        }
+      }
+    }
+    *self = settings;
+    Ok(())
+  }
+
+  /// The settings in the file, if there are any
+  pub fn load(&mut self, path: &Path) -> Result<(), String> {
+    match fs::read_to_string(path) {
+      Ok(text) => self.apply(&text),
+      Err(_) => Ok(()),
+    }
+  }
+
+  pub fn save(&self, path: &Path) -> Result<(), String> {
+    fs::write(path, format!("{}\n", self)).map_err(|err| err.to_string())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_round_trip() {
+    let mut settings = SnapSettings::default();
+    settings.grid.enabled = false;
+    settings.line.threshold = 4.5;
+    let text = settings.to_string();
+    assert_eq!(text, "point 12, line 4.5, intersection 15, grid 8 off");
+
+    let mut parsed = SnapSettings::default();
+    assert_eq!(parsed.apply(&text), Ok(()));
+    assert_eq!(parsed, settings);
+  }
+
+  #[test]
+  fn test_apply() {
+    let mut settings = SnapSettings::default();
+    assert_eq!(settings.apply("point off, grid 10 off"), Ok(()));
+    assert!(!settings.point.enabled);
+    assert_eq!(settings.point.threshold, 12.0);
+    assert_eq!(settings.grid, Snap { enabled: false, threshold: 10.0 });
+    assert_eq!(settings.line, SnapSettings::default().line);
+
+    // Nothing changes on errors
+    let before = settings;
+    assert!(settings.apply("point on, circle 3").is_err());
+    assert!(settings.apply("line -1").is_err());
+    assert_eq!(settings, before);
+  }
+}
\ No newline at end of file
@@ -0,0 +1,45 @@
+use std::fmt;
+use specs::prelude::*;
+
+/// What kept the solver from solving a geometry
+#[derive(Debug, Clone, PartialEq)]
+pub enum SolveError {
+  Cycle(Vec<Entity>), // Geometries in a cycle, or depending on one
+  Dangling(Entity, Entity), // (geometry, the parent it refers to that does not exist)
+}
+
+impl fmt::Display for SolveError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      SolveError::Cycle(ents) => write!(f, "{} geometries depend on themselves", ents.len()),
+      SolveError::Dangling(ent, parent) => write!(f, "geometry {} refers to missing geometry {}", ent.id(), parent.id()),
+    }
+  }
+}
+
+/// # Solver Report
+///
+/// The errors of the last time the solver solved anything. Geometries with
+/// errors are left unsolved, the same as undefined ones.
+#[derive(Default)]
+pub struct SolverReport {
+  pub errors: Vec<SolveError>,
+}
+
+impl SolverReport {
+
+  /// Drop the errors about a geometry that no longer exists
+  pub fn forget(&mut self, ent: Entity) {
+    self.errors.retain(|err| match err {
+      SolveError::Cycle(ents) => !ents.contains(&ent),
+      SolveError::Dangling(child, _) => *child != ent,
+    });
+  }
+
+  pub fn status(&self) -> Option<String> {
+    self.errors.first().map(|err| match self.errors.len() {
+      1 => format!("Solver: {}", err),
+      count => format!("Solver: {} ({} more errors)", err, count - 1),
+    })
+  }
+}
\ No newline at end of file
@@ -3,9 +3,10 @@ use std::hash::Hash;
 use itertools::Itertools;
 use super::{Viewport, ViewportTransform};
 use crate::utilities::{Vector2, AABB, Intersect};
-use crate::components::{Point, Line};
+use crate::components::{Point, Line, Arc, Conic, Plot};
 
 static TILE_SIZE : f64 = 40.0;
+static CONIC_SAMPLES : usize = 256;
 
 #[derive(Debug)]
 pub struct SpatialHashTable<T: Clone + Eq + Hash> {
@@ -88,6 +89,50 @@ impl<T: Clone + Eq + Hash> SpatialHashTable<T> {
     }
   }
 
+  /// from, to: segment end points in virtual space
+  pub fn insert_segment(&mut self, ent: T, from: Vector2, to: Vector2, vp: &Viewport) {
+    if let Some((p1, p2)) = (from.to_actual(vp), to.to_actual(vp)).intersect(vp.actual_aabb()) {
+
+      // Walk along the segment in steps of half a tile so no tile is skipped
+      let diff = p2 - p1;
+      let steps = (diff.magnitude() / (TILE_SIZE / 2.0)).ceil() as usize;
+      for i in 0..(steps + 1) {
+        let p = if steps == 0 { p1 } else { p1 + diff * (i as f64 / steps as f64) };
+        if let Some(tile) = self.get_cell(p) {
+          self.table[tile].insert(ent.clone());
+        }
+      }
+    }
+  }
+
+  /// points: poly line in virtual space
+  pub fn insert_polyline(&mut self, ent: T, points: &[Vector2], vp: &Viewport) {
+    for segment in points.windows(2) {
+      self.insert_segment(ent.clone(), segment[0], segment[1], vp);
+    }
+  }
+
+  /// arc: arc in virtual space
+  pub fn insert_arc(&mut self, ent: T, arc: Arc, vp: &Viewport) {
+    let actual_length = arc.length() / vp.scale();
+    let samples = ((actual_length / TILE_SIZE).ceil() as usize).max(1).min(512);
+    self.insert_polyline(ent, &arc.sample(samples), vp);
+  }
+
+  /// plot: plot in virtual space
+  pub fn insert_plot(&mut self, ent: T, plot: &Plot, vp: &Viewport) {
+    for polyline in plot.positions() {
+      self.insert_polyline(ent.clone(), &polyline, vp);
+    }
+  }
+
+  /// conic: conic in virtual space
+  pub fn insert_conic(&mut self, ent: T, conic: &Conic, vp: &Viewport) {
+    for polyline in conic.sample(vp.virtual_aabb(), CONIC_SAMPLES) {
+      self.insert_polyline(ent.clone(), &polyline, vp);
+    }
+  }
+
   pub fn remove_from_all(&mut self, ent: T) {
     for cell in &mut self.table {
       cell.remove(&ent);
@@ -305,6 +350,39 @@ mod tests {
     }
   }
 
+  #[test]
+  fn test_insert_segment_1() {
+    let vp = &Viewport::new(vec2![0., 0.], vec2![4., 4.], vec2![160., 160.]); // 田
+    let mut table : SpatialHashTable<i32> = SpatialHashTable::default();
+    table.init_viewport(vp);
+
+    table.insert_segment(0, vec2![-1.5, 1.5], vec2![0.5, 1.5], vp);
+
+    for i in 0..16 {
+      match i {
+        0..=2 => assert!(table.table[i].contains(&0)),
+        _ => assert!(table.table[i].is_empty())
+      }
+    }
+  }
+
+  #[test]
+  fn test_insert_segment_2() {
+    let vp = &Viewport::new(vec2![0., 0.], vec2![4., 4.], vec2![160., 160.]); // 田
+    let mut table : SpatialHashTable<i32> = SpatialHashTable::default();
+    table.init_viewport(vp);
+
+    // Clipped by the viewport
+    table.insert_segment(0, vec2![1.5, -10.0], vec2![1.5, -1.5], vp);
+
+    for i in 0..16 {
+      match i {
+        15 => assert!(table.table[i].contains(&0)),
+        _ => assert!(table.table[i].is_empty())
+      }
+    }
+  }
+
   #[test]
   fn test_insert_line_6() {
     let vp = &Viewport::new(vec2![0., 0.], vec2![2., 2.], vec2![80., 80.]); // 田
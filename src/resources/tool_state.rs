@@ -4,13 +4,19 @@ pub enum Tool {
   Point,
   Line,
   Circle,
+  Arc,
+  CenterArc,
+  Ellipse,
+  Hyperbola,
+  Conic,
+  Parabola,
   ViewportDrag,
 }
 
 impl Tool {
   pub fn depend_on_active_point(&self) -> bool {
     match self {
-      Tool::Point | Tool::Line | Tool::Circle => true,
+      Tool::Point | Tool::Line | Tool::Circle | Tool::Arc | Tool::CenterArc | Tool::Ellipse | Tool::Hyperbola | Tool::Conic | Tool::Parabola => true,
       _ => false,
     }
   }
@@ -0,0 +1,60 @@
+use std::collections::{HashMap, VecDeque};
+use specs::prelude::*;
+use crate::{
+  utilities::{Vector2, Line},
+  components::{PointStyle, LineStyle},
+};
+
+static MAX_TRACE_MARKS : usize = 20000;
+
+/// A single retained trace primitive, in virtual space
+#[derive(Debug, Copy, Clone)]
+pub enum TraceMark {
+  Point(Vector2, Vector2, PointStyle), // from, to
+  Line(Line, LineStyle),
+}
+
+/// # Trace Layer
+///
+/// Past positions of traced geometries. The screen is cleared every frame,
+/// so the marks are retained here and drawn below everything else. The
+/// oldest marks are dropped once there are too many of them.
+#[derive(Default)]
+pub struct TraceLayer {
+  marks: VecDeque<TraceMark>,
+  last_positions: HashMap<Entity, Vector2>,
+}
+
+impl TraceLayer {
+  /// Trace a point moving to `position`. It is connected to the last
+  /// position the point was traced at, if any
+  pub fn trace_point(&mut self, ent: Entity, position: Vector2, style: PointStyle) {
+    let from = self.last_positions.insert(ent, position).unwrap_or(position);
+    self.push(TraceMark::Point(from, position, style));
+  }
+
+  pub fn trace_line(&mut self, line: Line, style: LineStyle) {
+    self.push(TraceMark::Line(line, style));
+  }
+
+  /// Stop connecting the point's trace, e.g. when it becomes undefined
+  pub fn break_point(&mut self, ent: Entity) {
+    self.last_positions.remove(&ent);
+  }
+
+  pub fn clear(&mut self) {
+    self.marks.clear();
+    self.last_positions.clear();
+  }
+
+  pub fn marks(&self) -> impl Iterator<Item = &TraceMark> {
+    self.marks.iter()
+  }
+
+  fn push(&mut self, mark: TraceMark) {
+    if self.marks.len() >= MAX_TRACE_MARKS {
+      self.marks.pop_front();
+    }
+    self.marks.push_back(mark);
+  }
+}
\ No newline at end of file
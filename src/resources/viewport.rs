@@ -1,6 +1,7 @@
 use crate::utilities::{Vector2, Line, AABB};
 
 pub static WINDOW_SIZE : [f64; 2] = [960., 720.];
+static MIN_GRID_SPACING : f64 = 40.0; // Pixel
 
 #[derive(Debug, Clone, Copy)]
 pub struct Viewport {
@@ -75,7 +76,6 @@ impl Viewport {
     self.virtual_center.x - self.half_virtual_size.x
   }
 
-  #[allow(dead_code)]
   pub fn x_max(&self) -> f64 {
     self.virtual_center.x + self.half_virtual_size.x
   }
@@ -84,7 +84,6 @@ impl Viewport {
     self.virtual_center.y - self.half_virtual_size.y
   }
 
-  #[allow(dead_code)]
   pub fn y_max(&self) -> f64 {
     self.virtual_center.y + self.half_virtual_size.y
   }
@@ -97,6 +96,18 @@ impl Viewport {
   pub fn actual_aabb(&self) -> AABB {
     AABB::new(0., 0., self.actual_width(), self.actual_height())
   }
+
+  /// The spacing of the grid in virtual space: the smallest power of ten
+  /// that is still wide enough on the screen
+  pub fn grid_spacing(&self) -> f64 {
+    10f64.powf((MIN_GRID_SPACING * self.scale()).log10().ceil())
+  }
+
+  /// The grid crossing closest to a virtual position
+  pub fn closest_grid_point(&self, position: Vector2) -> Vector2 {
+    let spacing = self.grid_spacing();
+    vec2![(position.x / spacing).round() * spacing, (position.y / spacing).round() * spacing]
+  }
 }
 
 pub trait ViewportTransform {
@@ -152,4 +163,15 @@ mod tests {
     assert!(vec2![0., 5.].to_actual(vp) == vec2![480., 120.]);
     assert!(vec2![5., 5.].to_actual(vp) == vec2![720., 120.]);
   }
+
+  #[test]
+  fn test_grid() {
+    let vp = &Viewport::default();
+    assert_eq!(vp.grid_spacing(), 1.);
+    assert!(vp.closest_grid_point(vec2![1.4, -2.6]) == vec2![1., -3.]);
+
+    let vp = &Viewport::new(vec2![0., 0.], vec2![200., 150.], WINDOW_SIZE.into());
+    assert_eq!(vp.grid_spacing(), 10.);
+    assert!(vp.closest_grid_point(vec2![14., 6.]) == vec2![10., 10.]);
+  }
 }
\ No newline at end of file
@@ -2,9 +2,9 @@ use specs::prelude::*;
 use crate::{
   resources::{
     DependencyGraph,
-    events::{Geometry, SketchEvent, SketchEventChannel, SketchEventReader},
+    events::{SketchEvent, SketchEventChannel, SketchEventReader},
   },
-  components::{SymbolicLine, SymbolicPoint},
+  components::{SymbolicLine, SymbolicPoint, SymbolicArc, SymbolicConic, SymbolicFunction, SymbolicLocus},
 };
 
 pub struct DependencyGraphCache {
@@ -21,29 +21,9 @@ impl Default for DependencyGraphCache {
   }
 }
 
-fn add_point(dependency_graph: &mut DependencyGraph, ent: &Entity, sym_point: &SymbolicPoint) {
-  match sym_point {
-    SymbolicPoint::Free(_) => (),
-    SymbolicPoint::OnLine(line_ent, _) => {
-      dependency_graph.add(line_ent, ent);
-    },
-    SymbolicPoint::LineLineIntersect(l1_ent, l2_ent) => {
-      dependency_graph.add(l1_ent, ent);
-      dependency_graph.add(l2_ent, ent);
-    },
-  }
-}
-
-fn add_line(dependency_graph: &mut DependencyGraph, ent: &Entity, sym_line: &SymbolicLine) {
-  match sym_line {
-    SymbolicLine::TwoPoints(p1_ent, p2_ent) => {
-      dependency_graph.add(p1_ent, ent);
-      dependency_graph.add(p2_ent, ent);
-    },
-    SymbolicLine::Parallel(line_ent, point_ent) => {
-      dependency_graph.add(line_ent, ent);
-      dependency_graph.add(point_ent, ent);
-    },
+fn add_parents(dependency_graph: &mut DependencyGraph, ent: &Entity, parents: Vec<Entity>) {
+  for parent in parents {
+    dependency_graph.add(&parent, ent);
   }
 }
 
@@ -54,6 +34,10 @@ impl<'a> System<'a> for DependencyGraphCache {
     Write<'a, DependencyGraph>,
     ReadStorage<'a, SymbolicPoint>,
     ReadStorage<'a, SymbolicLine>,
+    ReadStorage<'a, SymbolicArc>,
+    ReadStorage<'a, SymbolicConic>,
+    ReadStorage<'a, SymbolicFunction>,
+    ReadStorage<'a, SymbolicLocus>,
   );
 
   fn setup(&mut self, world: &mut World) {
@@ -67,16 +51,23 @@ impl<'a> System<'a> for DependencyGraphCache {
     mut dependency_graph,
     sym_points,
     sym_lines,
+    sym_arcs,
+    sym_conics,
+    sym_functions,
+    sym_loci,
   ): Self::SystemData) {
     if self.initialized {
       if let Some(reader_id) = &mut self.sketch_events_reader_id {
         for event in sketch_events.read(reader_id) {
           match event {
-            SketchEvent::Insert(entity, geom) => match geom {
-              Geometry::Point(sym_point, _) => add_point(&mut dependency_graph, entity, sym_point),
-              Geometry::Line(sym_line, _) => add_line(&mut dependency_graph, entity, sym_line),
-            },
+            SketchEvent::Insert(entity, geom) => add_parents(&mut dependency_graph, entity, geom.parents()),
             SketchEvent::Remove(entity, _) => dependency_graph.remove(entity),
+
+            // The children stay, only the parents change
+            SketchEvent::Redefine(entity, _, new_geom) => {
+              dependency_graph.remove_parents(entity);
+              add_parents(&mut dependency_graph, entity, new_geom.parents());
+            },
             SketchEvent::Select(_) | SketchEvent::Deselect(_) | SketchEvent::MovePoint(_, _) => (),
           }
         }
@@ -85,11 +76,26 @@ impl<'a> System<'a> for DependencyGraphCache {
       }
     } else {
       for (entity, sym_point) in (&entities, &sym_points).join() {
-        add_point(&mut dependency_graph, &entity, sym_point);
+        add_parents(&mut dependency_graph, &entity, sym_point.parents());
       }
       for (entity, sym_line) in (&entities, &sym_lines).join() {
-        add_line(&mut dependency_graph, &entity, sym_line);
+        add_parents(&mut dependency_graph, &entity, sym_line.parents());
       }
+      for (entity, sym_arc) in (&entities, &sym_arcs).join() {
+        add_parents(&mut dependency_graph, &entity, sym_arc.parents());
+      }
+      for (entity, sym_conic) in (&entities, &sym_conics).join() {
+        add_parents(&mut dependency_graph, &entity, sym_conic.parents());
+      }
+      for (entity, sym_function) in (&entities, &sym_functions).join() {
+        add_parents(&mut dependency_graph, &entity, sym_function.references.clone());
+      }
+      for (entity, sym_locus) in (&entities, &sym_loci).join() {
+        add_parents(&mut dependency_graph, &entity, sym_locus.parents());
+      }
+
+      // From now on the graph follows the sketch events
+      self.initialized = true;
     }
   }
 }
\ No newline at end of file
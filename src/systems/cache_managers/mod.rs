@@ -2,4 +2,13 @@ mod dependency_graph_cache;
 pub use dependency_graph_cache::DependencyGraphCache;
 
 mod spatial_hash_cache;
-pub use spatial_hash_cache::SpatialHashCache;
\ No newline at end of file
+pub use spatial_hash_cache::SpatialHashCache;
+
+mod plot_cache;
+pub use plot_cache::PlotCache;
+
+mod trace_cache;
+pub use trace_cache::TraceCache;
+
+mod protocol_cache;
+pub use protocol_cache::ProtocolCache;
\ No newline at end of file
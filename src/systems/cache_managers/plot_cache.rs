@@ -0,0 +1,132 @@
+use specs::prelude::*;
+use crate::{
+  resources::{
+    Viewport,
+    DependencyGraph,
+    events::{
+      ViewportEventChannel, ViewportEventReader,
+      Geometry, SketchEvent, SketchEventChannel, SketchEventReader,
+    },
+  },
+  components::{Point, SymbolicFunction, SymbolicCurve, Plot},
+};
+
+static PLOT_TOLERANCE : f64 = 0.5; // In actual space
+
+/// There is no plot while a point the function references is undefined
+fn sample_function(sym_function: &SymbolicFunction, points: &ReadStorage<Point>, vp: &Viewport) -> Option<Plot> {
+  let sym_function = sym_function.bind(|ent| points.get(ent).cloned()).ok()?;
+
+  // Sample a little bit beyond the screen so that the plot does not end
+  // right at the border
+  let margin = vp.virtual_width() * 0.05;
+  Some(Plot::sample(|x| sym_function.point_at(x), vp.x_min() - margin, vp.x_max() + margin, PLOT_TOLERANCE * vp.scale()))
+}
+
+fn sample_curve(sym_curve: &SymbolicCurve, vp: &Viewport) -> Plot {
+  let (t_min, t_max) = sym_curve.range();
+  Plot::sample(|t| sym_curve.point_at(t), t_min, t_max, PLOT_TOLERANCE * vp.scale())
+}
+
+fn insert_plot<'a>(plots: &mut WriteStorage<'a, Plot>, ent: Entity, plot: Plot) {
+  if let Err(err) = plots.insert(ent, plot) {
+    panic!("[plot_cache] Error when inserting plot: {:?}", err);
+  }
+}
+
+fn update_function_plot<'a>(plots: &mut WriteStorage<'a, Plot>, ent: Entity, maybe_plot: Option<Plot>) {
+  match maybe_plot {
+    Some(plot) => insert_plot(plots, ent, plot),
+    None => { plots.remove(ent); },
+  }
+}
+
+/// # Plot Cache
+///
+/// Function plots are sampled over the visible part of the viewport, so they
+/// are re-sampled whenever the viewport changes, and whenever a point they
+/// reference moves. The sketch events are read once the solver has handled
+/// them, so the points are already at their new positions. Curves have their
+/// own range but their precision still depends on the zoom level.
+#[derive(Default)]
+pub struct PlotCache {
+  viewport_events_reader_id: Option<ViewportEventReader>,
+  sketch_events_reader_id: Option<SketchEventReader>,
+}
+
+impl PlotCache {
+  fn need_refresh(&mut self, vp_events: &ViewportEventChannel) -> bool {
+    if let Some(vp_event_reader_id) = &mut self.viewport_events_reader_id {
+      vp_events.read(vp_event_reader_id).count() > 0
+    } else {
+      panic!("[plot_cache] No viewport event reader id");
+    }
+  }
+}
+
+impl<'a> System<'a> for PlotCache {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, Viewport>,
+    Read<'a, ViewportEventChannel>,
+    Read<'a, SketchEventChannel>,
+    Read<'a, DependencyGraph>,
+    ReadStorage<'a, Point>,
+    ReadStorage<'a, SymbolicFunction>,
+    ReadStorage<'a, SymbolicCurve>,
+    WriteStorage<'a, Plot>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.viewport_events_reader_id = Some(world.fetch_mut::<ViewportEventChannel>().register_reader());
+    self.sketch_events_reader_id = Some(world.fetch_mut::<SketchEventChannel>().register_reader());
+  }
+
+  fn run(&mut self, (
+    entities,
+    vp,
+    viewport_event_channel,
+    sketch_events,
+    dependency_graph,
+    points,
+    sym_functions,
+    sym_curves,
+    mut plots,
+  ): Self::SystemData) {
+    let refresh = self.need_refresh(&viewport_event_channel);
+
+    // Sketch events are always read so that they do not pile up
+    if let Some(sketch_events_reader_id) = &mut self.sketch_events_reader_id {
+      for event in sketch_events.read(sketch_events_reader_id) {
+        match event {
+          SketchEvent::Insert(entity, Geometry::Function(sym_function, _)) if !refresh => {
+            update_function_plot(&mut plots, *entity, sample_function(sym_function, &points, &vp));
+          },
+          SketchEvent::Insert(entity, Geometry::Curve(sym_curve, _)) if !refresh => {
+            insert_plot(&mut plots, *entity, sample_curve(sym_curve, &vp));
+          },
+          SketchEvent::MovePoint(entity, _) | SketchEvent::Redefine(entity, _, _) if !refresh => {
+            for dependent in dependency_graph.get_all_dependents(entity) {
+              if let Some(sym_function) = sym_functions.get(dependent) {
+                update_function_plot(&mut plots, dependent, sample_function(sym_function, &points, &vp));
+              }
+            }
+          },
+          _ => (),
+        }
+      }
+    } else {
+      panic!("[plot_cache] No sketch event reader id");
+    }
+
+    if refresh {
+      for (ent, sym_function) in (&*entities, &sym_functions).join() {
+        update_function_plot(&mut plots, ent, sample_function(sym_function, &points, &vp));
+      }
+      for (ent, sym_curve) in (&*entities, &sym_curves).join() {
+        insert_plot(&mut plots, ent, sample_curve(sym_curve, &vp));
+      }
+    }
+  }
+}
\ No newline at end of file
@@ -0,0 +1,111 @@
+use specs::prelude::*;
+use crate::{
+  resources::{
+    ConstructionProtocol,
+    events::{Geometry, SketchEvent, SketchEventChannel, SketchEventReader, MovePoint},
+  },
+  components::{SymbolicPoint, SymbolicLine, SymbolicArc, SymbolicConic, SymbolicCurve},
+};
+
+/// # Protocol Cache
+///
+/// Records every inserted geometry as a construction step. Creating
+/// something while stepping through the protocol shows every step again.
+#[derive(Default)]
+pub struct ProtocolCache {
+  sketch_events_reader_id: Option<SketchEventReader>,
+}
+
+impl<'a> System<'a> for ProtocolCache {
+  type SystemData = (
+    Read<'a, SketchEventChannel>,
+    Write<'a, ConstructionProtocol>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.sketch_events_reader_id = Some(world.fetch_mut::<SketchEventChannel>().register_reader());
+  }
+
+  fn run(&mut self, (sketch_events, mut protocol): Self::SystemData) {
+    if let Some(reader_id) = &mut self.sketch_events_reader_id {
+      for event in sketch_events.read(reader_id) {
+        match event {
+          SketchEvent::Insert(entity, geom) => {
+            let name = match geom {
+              Geometry::Point(_, _) => protocol.name_point(*entity),
+              _ => protocol.name_other(*entity),
+            };
+            let description = describe(&name, geom, &protocol);
+            protocol.push(*entity, description);
+            protocol.show_all();
+          },
+          SketchEvent::Remove(entity, _) => protocol.remove(*entity),
+          SketchEvent::Redefine(entity, _, new_geom) => {
+            let name = protocol.name_of(*entity);
+            let description = describe(&name, new_geom, &protocol);
+            protocol.describe(*entity, description);
+          },
+          SketchEvent::MovePoint(entity, MovePoint::Free(_, new_position)) => {
+            let name = protocol.name_of(*entity);
+            let description = describe_point(&name, &SymbolicPoint::Free(*new_position), &protocol);
+            protocol.describe(*entity, description);
+          },
+          _ => (),
+        }
+      }
+    } else {
+      panic!("[protocol_cache] No sketch events reader id");
+    }
+  }
+}
+
+fn describe(name: &str, geom: &Geometry, protocol: &ConstructionProtocol) -> String {
+  let n = |entity: &Entity| protocol.name_of(*entity);
+  match geom {
+    Geometry::Point(sym_point, _) => describe_point(name, sym_point, protocol),
+    Geometry::Line(sym_line, _) => match sym_line {
+      SymbolicLine::TwoPoints(p1, p2) => format!("Line {} through {}, {}", name, n(p1), n(p2)),
+      SymbolicLine::Parallel(l, p) => format!("Line {} parallel to {} through {}", name, n(l), n(p)),
+      SymbolicLine::Perpendicular(l, p) => format!("Line {} perpendicular to {} through {}", name, n(l), n(p)),
+      SymbolicLine::Tangent(p, a, _) => format!("Line {} tangent to {} through {}", name, n(a), n(p)),
+      SymbolicLine::TangentAt(p) => format!("Line {} tangent at {}", name, n(p)),
+    },
+    Geometry::Arc(sym_arc, _) => match sym_arc {
+      SymbolicArc::CenterTwoPoints(c, from, to) if from == to => format!("Circle {} centered at {} through {}", name, n(c), n(from)),
+      SymbolicArc::CenterTwoPoints(c, from, to) => format!("Arc {} centered at {} from {} to {}", name, n(c), n(from), n(to)),
+      SymbolicArc::ThreePoints(from, through, to) => format!("Arc {} from {} through {} to {}", name, n(from), n(through), n(to)),
+    },
+    Geometry::Conic(sym_conic, _) => match sym_conic {
+      SymbolicConic::Ellipse(f1, f2, p) => format!("Ellipse {} with foci {}, {} through {}", name, n(f1), n(f2), n(p)),
+      SymbolicConic::Hyperbola(f1, f2, p) => format!("Hyperbola {} with foci {}, {} through {}", name, n(f1), n(f2), n(p)),
+      SymbolicConic::Parabola(f, l) => format!("Parabola {} with focus {} and directrix {}", name, n(f), n(l)),
+      SymbolicConic::FivePoints(p1, p2, p3, p4, p5) => format!("Conic {} through {}, {}, {}, {}, {}", name, n(p1), n(p2), n(p3), n(p4), n(p5)),
+    },
+    Geometry::Function(sym_function, _) => format!("Function {}(x) = {}", name, sym_function.named(|ent| protocol.name_of(ent))),
+    Geometry::Curve(sym_curve, _) => match sym_curve {
+      SymbolicCurve::Parametric { x, y, t_min, t_max } => format!("Curve {} (x, y) = ({}, {}) for t in [{}, {}]", name, x, y, t_min, t_max),
+      SymbolicCurve::Polar { r, t_min, t_max } => format!("Curve {} r = {} for t in [{}, {}]", name, r, t_min, t_max),
+    },
+    Geometry::Locus(sym_locus, _) => format!("Locus {} of {} driven by {}", name, n(&sym_locus.traced), n(&sym_locus.driver)),
+  }
+}
+
+fn describe_point(name: &str, sym_point: &SymbolicPoint, protocol: &ConstructionProtocol) -> String {
+  let n = |entity: &Entity| protocol.name_of(*entity);
+  match sym_point {
+    SymbolicPoint::Free(p) => format!("Point {} free at ({}, {})", name, round(p.x), round(p.y)),
+    SymbolicPoint::OnLine(l, _) => format!("Point {} on {}", name, n(l)),
+    SymbolicPoint::LineLineIntersect(l1, l2) => format!("Point {} at intersection of {}, {}", name, n(l1), n(l2)),
+    SymbolicPoint::OnArc(a, _) => format!("Point {} on {}", name, n(a)),
+    SymbolicPoint::OnConic(c, _) => format!("Point {} on {}", name, n(c)),
+    SymbolicPoint::OnFunction(f, _) => format!("Point {} on {}", name, n(f)),
+    SymbolicPoint::FunctionLineIntersect(f, l, _) => format!("Point {} at intersection of {}, {}", name, n(f), n(l)),
+    SymbolicPoint::OnCurve(c, _) => format!("Point {} on {}", name, n(c)),
+  }
+}
+
+/// Coordinates are shown with at most two decimals
+fn round(x: f64) -> f64 {
+  (x * 100.0).round() / 100.0
+}
\ No newline at end of file
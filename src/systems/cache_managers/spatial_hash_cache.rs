@@ -9,7 +9,7 @@ use crate::{
       Geometry, SketchEvent, SketchEventChannel, SketchEventReader
     },
   },
-  components::{SymbolicLine, Line, SymbolicPoint, Point},
+  components::{SymbolicLine, Line, SymbolicPoint, Point, SymbolicArc, Arc, SymbolicConic, Conic, Plot, Hidden},
 };
 
 pub struct SpatialHashCache {
@@ -51,6 +51,12 @@ impl<'a> System<'a> for SpatialHashCache {
     ReadStorage<'a, Line>,
     ReadStorage<'a, SymbolicPoint>,
     ReadStorage<'a, Point>,
+    ReadStorage<'a, SymbolicArc>,
+    ReadStorage<'a, Arc>,
+    ReadStorage<'a, SymbolicConic>,
+    ReadStorage<'a, Conic>,
+    ReadStorage<'a, Plot>,
+    ReadStorage<'a, Hidden>,
   );
 
   fn setup(&mut self, world: &mut World) {
@@ -71,7 +77,13 @@ impl<'a> System<'a> for SpatialHashCache {
     sym_lines,
     lines,
     sym_points,
-    points
+    points,
+    sym_arcs,
+    arcs,
+    sym_conics,
+    conics,
+    plots,
+    hidden,
   ): Self::SystemData) {
 
     // First check if needs full refresh
@@ -79,12 +91,21 @@ impl<'a> System<'a> for SpatialHashCache {
 
       // If is then reconstruct the whole table
       table.init_viewport(&*vp);
-      for (ent, _, point) in (&*entities, &sym_points, &points).join() {
+      for (ent, _, point, _) in (&*entities, &sym_points, &points, !&hidden).join() {
         table.insert_point(ent, *point, &*vp);
       }
-      for (ent, _, line) in (&*entities, &sym_lines, &lines).join() {
+      for (ent, _, line, _) in (&*entities, &sym_lines, &lines, !&hidden).join() {
         table.insert_line(ent, *line, &*vp);
       }
+      for (ent, _, arc, _) in (&*entities, &sym_arcs, &arcs, !&hidden).join() {
+        table.insert_arc(ent, *arc, &*vp);
+      }
+      for (ent, _, conic, _) in (&*entities, &sym_conics, &conics, !&hidden).join() {
+        table.insert_conic(ent, conic, &*vp);
+      }
+      for (ent, plot, _) in (&*entities, &plots, !&hidden).join() {
+        table.insert_plot(ent, plot, &*vp);
+      }
     } else {
 
       // Else, loop through all the events
@@ -92,25 +113,50 @@ impl<'a> System<'a> for SpatialHashCache {
         for event in sketch_events.read(sketch_event_reader_id) {
           match event {
             SketchEvent::Insert(entity, geom) => match geom {
-              Geometry::Point(_, _) => match points.get(*entity) {
-                Some(position) => table.insert_point(*entity, *position, &*vp),
-                None => panic!("[spatial_hash_cache] Cannot find given point"),
+
+              // Geometries created on other ones (e.g. by a custom tool) can be
+              // undefined, and degenerated conics are not solved either, so
+              // there can be nothing to insert. The same goes for functions
+              // referencing undefined points
+              Geometry::Point(_, _) => if let Some(position) = points.get(*entity) {
+                table.insert_point(*entity, *position, &*vp);
               },
-              Geometry::Line(_, _) => match lines.get(*entity) {
-                Some(line) => table.insert_line(*entity, *line, &*vp),
-                None => panic!("[spatial_hash_cache] Cannot find given line"),
-              }
+              Geometry::Line(_, _) => if let Some(line) = lines.get(*entity) {
+                table.insert_line(*entity, *line, &*vp);
+              },
+              Geometry::Arc(_, _) => if let Some(arc) = arcs.get(*entity) {
+                table.insert_arc(*entity, *arc, &*vp);
+              },
+              Geometry::Conic(_, _) => if let Some(conic) = conics.get(*entity) {
+                table.insert_conic(*entity, conic, &*vp);
+              },
+              Geometry::Function(_, _) => if let Some(plot) = plots.get(*entity) {
+                table.insert_plot(*entity, plot, &*vp);
+              },
+              Geometry::Curve(_, _) => if let Some(plot) = plots.get(*entity) {
+                table.insert_plot(*entity, plot, &*vp);
+              },
+              Geometry::Locus(_, _) => (), // The locus system inserts the locus once it is sampled
             },
             SketchEvent::Remove(entity, _) => table.remove_from_all(*entity),
             SketchEvent::Select(_) | SketchEvent::Deselect(_) => (),
-            SketchEvent::MovePoint(entity, _) => {
+            SketchEvent::MovePoint(entity, _) | SketchEvent::Redefine(entity, _, _) => {
               let dependents = dependency_graph.get_all_dependents(entity);
               for dependent in dependents {
                 table.remove_from_all(dependent);
+                if hidden.get(dependent).is_some() {
+                  continue;
+                }
                 if let Some(point) = points.get(dependent) {
                   table.insert_point(dependent, *point, &*vp);
                 } else if let Some(line) = lines.get(dependent) {
                   table.insert_line(dependent, *line, &*vp);
+                } else if let Some(arc) = arcs.get(dependent) {
+                  table.insert_arc(dependent, *arc, &*vp);
+                } else if let Some(conic) = conics.get(dependent) {
+                  table.insert_conic(dependent, conic, &*vp);
+                } else if let Some(plot) = plots.get(dependent) {
+                  table.insert_plot(dependent, plot, &*vp);
                 }
               }
             }
@@ -0,0 +1,85 @@
+use specs::prelude::*;
+use crate::{
+  resources::{
+    DependencyGraph,
+    TraceLayer,
+    events::{SketchEvent, SketchEventChannel, SketchEventReader},
+  },
+  components::{SymbolicPoint, Point, PointStyle, Line, LineStyle, Traced},
+};
+
+/// # Trace Cache
+///
+/// Records the traced points and lines into the trace layer whenever they
+/// move. This has to run after the solver so that the new positions are
+/// recorded.
+#[derive(Default)]
+pub struct TraceCache {
+  sketch_events_reader_id: Option<SketchEventReader>,
+}
+
+impl<'a> System<'a> for TraceCache {
+  type SystemData = (
+    Read<'a, SketchEventChannel>,
+    Read<'a, DependencyGraph>,
+    Write<'a, TraceLayer>,
+    ReadStorage<'a, SymbolicPoint>,
+    ReadStorage<'a, Point>,
+    ReadStorage<'a, PointStyle>,
+    ReadStorage<'a, Line>,
+    ReadStorage<'a, LineStyle>,
+    ReadStorage<'a, Traced>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.sketch_events_reader_id = Some(world.fetch_mut::<SketchEventChannel>().register_reader());
+  }
+
+  fn run(&mut self, (
+    sketch_events,
+    dependency_graph,
+    mut trace_layer,
+    sym_points,
+    points,
+    point_styles,
+    lines,
+    line_styles,
+    traced,
+  ): Self::SystemData) {
+    if let Some(reader_id) = &mut self.sketch_events_reader_id {
+      for event in sketch_events.read(reader_id) {
+        match event {
+          SketchEvent::MovePoint(entity, _) => {
+            for dependent in dependency_graph.get_all_dependents(entity) {
+              if traced.get(dependent).is_none() {
+                continue;
+              }
+              if sym_points.get(dependent).is_some() {
+
+                // Undefined points leave a gap in the trace
+                match (points.get(dependent), point_styles.get(dependent)) {
+                  (Some(point), Some(style)) => trace_layer.trace_point(dependent, *point, *style),
+                  _ => trace_layer.break_point(dependent),
+                }
+              } else if let (Some(line), Some(style)) = (lines.get(dependent), line_styles.get(dependent)) {
+                trace_layer.trace_line(*line, *style);
+              }
+            }
+          },
+          SketchEvent::Remove(entity, _) => trace_layer.break_point(*entity),
+
+          // A redefined geometry jumps, which is not traced
+          SketchEvent::Redefine(entity, _, _) => {
+            for dependent in dependency_graph.get_all_dependents(entity) {
+              trace_layer.break_point(dependent);
+            }
+          },
+          _ => (),
+        }
+      }
+    } else {
+      panic!("[trace_cache] No sketch events reader id");
+    }
+  }
+}
\ No newline at end of file
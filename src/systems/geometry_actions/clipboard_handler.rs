@@ -0,0 +1,115 @@
+use std::collections::HashSet;
+use specs::prelude::*;
+use crate::{
+  utilities::Vector2,
+  resources::{
+    Clipboard,
+    ConstructionProtocol,
+    Definition,
+    Viewport,
+    events::{GeometryAction, GeometryActionReader, GeometryActionChannel, SketchEvent, SketchEventChannel},
+  },
+  components::*,
+};
+use super::helpers::GeometryStorages;
+
+static PASTE_OFFSET : f64 = 20.0; // In actual pixels
+
+#[derive(Default)]
+pub struct ClipboardHandler {
+  geometry_action_reader: Option<GeometryActionReader>,
+}
+
+/// # Clipboard Handler
+///
+/// Copies the selection along with everything it is defined on, so that a
+/// paste does not depend on the copied geometries still being there. Pasting
+/// creates all of them again, moved a bit from the last paste, and selects
+/// them instead of the current selection.
+impl<'a> System<'a> for ClipboardHandler {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, GeometryActionChannel>,
+    Read<'a, ConstructionProtocol>,
+    Read<'a, Viewport>,
+    Write<'a, Clipboard>,
+    Write<'a, SketchEventChannel>,
+    WriteStorage<'a, Selected>,
+    GeometryStorages<'a>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.geometry_action_reader = Some(world.fetch_mut::<GeometryActionChannel>().register_reader());
+  }
+
+  fn run(&mut self, (
+    entities,
+    geometry_action_channel,
+    protocol,
+    viewport,
+    mut clipboard,
+    mut sketch_events,
+    mut selected,
+    mut storages,
+  ): Self::SystemData) {
+
+    if let Some(reader_id) = &mut self.geometry_action_reader {
+      for event in geometry_action_channel.read(reader_id) {
+        match event {
+          GeometryAction::CopySelected => {
+
+            // Walk up from the selection to everything it needs
+            let mut needed = HashSet::new();
+            let mut stack : Vec<Entity> = (&entities, &selected).join().map(|(ent, _)| ent).collect();
+            while let Some(ent) = stack.pop() {
+              if needed.insert(ent) {
+                if let Some(geom) = storages.get(ent) {
+                  stack.extend(geom.parents());
+                }
+              }
+            }
+            if needed.is_empty() {
+              continue;
+            }
+
+            // Every slot is then a definition that comes before
+            let copied = protocol.creation_order(&needed);
+            let definitions = copied.iter()
+              .map(|ent| storages.get(*ent).and_then(|geom| Definition::new(&geom, |parent| copied.iter().position(|e| *e == parent))))
+              .collect::<Option<Vec<_>>>();
+            if let Some(definitions) = definitions {
+              clipboard.set(definitions);
+            }
+          },
+          GeometryAction::Paste => {
+            if clipboard.definitions.is_empty() {
+              continue;
+            }
+            for (ent, _) in (&entities, &selected).join() {
+              sketch_events.single_write(SketchEvent::Deselect(ent));
+            }
+
+            clipboard.pastes += 1;
+            let distance = PASTE_OFFSET * viewport.scale() * clipboard.pastes as f64;
+            let offset = vec2![distance, -distance];
+            let mut slots = vec![];
+            for definition in &clipboard.definitions {
+              match definition.translated(offset).geometry(|slot| slots.get(slot).cloned()) {
+                Ok(geom) => {
+                  let entity = entities.create();
+                  storages.insert(entity, &geom);
+                  if let Err(err) = selected.insert(entity, Selected) { panic!("[clipboard_handler] {:?}", err) }
+                  sketch_events.single_write(SketchEvent::Insert(entity, geom));
+                  slots.push(entity);
+                },
+                Err(err) => panic!("[clipboard_handler] {}", err),
+              }
+            }
+          },
+          _ => (),
+        }
+      }
+    }
+  }
+}
\ No newline at end of file
@@ -0,0 +1,88 @@
+use specs::prelude::*;
+use crate::{
+  utilities::Color,
+  resources::{
+    DependencyGraph,
+    events::{
+      GeometryAction, GeometryActionReader, GeometryActionChannel,
+      SketchEvent, SketchEventChannel, Geometry
+    },
+  },
+  components::{SymbolicPoint, SymbolicLocus, PlotStyle, Selected},
+};
+
+#[derive(Default)]
+pub struct CreateLocusHandler {
+  geometry_action_reader: Option<GeometryActionReader>,
+}
+
+/// # Create Locus Handler
+///
+/// Creates the locus from exactly two selected points. The driver is the one
+/// sliding along something, and the other one has to depend on it; which is
+/// which is found out from the dependency graph, so the selection order does
+/// not matter.
+impl<'a> System<'a> for CreateLocusHandler {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, GeometryActionChannel>,
+    Read<'a, DependencyGraph>,
+    Write<'a, SketchEventChannel>,
+    ReadStorage<'a, SymbolicPoint>,
+    WriteStorage<'a, SymbolicLocus>,
+    WriteStorage<'a, PlotStyle>,
+    ReadStorage<'a, Selected>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.geometry_action_reader = Some(world.fetch_mut::<GeometryActionChannel>().register_reader());
+  }
+
+  fn run(&mut self, (
+    entities,
+    geometry_action_channel,
+    dep_graph,
+    mut sketch_events,
+    sym_points,
+    mut sym_loci,
+    mut styles,
+    selected,
+  ): Self::SystemData) {
+    if let Some(reader_id) = &mut self.geometry_action_reader {
+      for event in geometry_action_channel.read(reader_id) {
+        if let GeometryAction::CreateLocusFromSelected = event {
+          let selected_points : Vec<_> = (&entities, &sym_points, &selected).join().map(|(ent, sym, _)| (ent, *sym)).collect();
+          if selected_points.len() != 2 {
+            continue;
+          }
+
+          // Find the driver among the two
+          let (a, b) = (selected_points[0], selected_points[1]);
+          let is_driving = |(driver, sym): (Entity, SymbolicPoint), traced: Entity| {
+            sym.with_parameter(0.0).is_some() && dep_graph.get_all_dependents(&driver).contains(&traced)
+          };
+          let maybe_locus = if is_driving(a, b.0) {
+            Some(SymbolicLocus { driver: a.0, traced: b.0 })
+          } else if is_driving(b, a.0) {
+            Some(SymbolicLocus { driver: b.0, traced: a.0 })
+          } else {
+            None
+          };
+
+          if let Some(sym_locus) = maybe_locus {
+            let plot_style = PlotStyle { color: Color::magenta(), width: 2. };
+
+            // Create the locus
+            let entity = entities.create();
+            if let Err(err) = sym_loci.insert(entity, sym_locus) { panic!("[create_locus_handler] {:?}", err) }
+            if let Err(err) = styles.insert(entity, plot_style) { panic!("[create_locus_handler] {:?}", err) }
+
+            // Push event to created locus
+            sketch_events.single_write(SketchEvent::Insert(entity, Geometry::Locus(sym_locus, plot_style)));
+          }
+        }
+      }
+    }
+  }
+}
\ No newline at end of file
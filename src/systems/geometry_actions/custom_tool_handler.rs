@@ -0,0 +1,196 @@
+use std::collections::HashSet;
+use specs::prelude::*;
+use crate::{
+  utilities::preferences_file,
+  resources::{
+    ConstructionProtocol,
+    CustomTool,
+    CustomToolLibrary,
+    Application,
+    Definition,
+    GivenKind,
+    PromptState,
+    PromptKind,
+    events::{
+      GeometryAction, GeometryActionReader, GeometryActionChannel,
+      PromptEvent, PromptEventChannel, PromptEventReader,
+      SketchEvent, SketchEventChannel,
+    },
+  },
+  components::*,
+};
+use super::helpers::GeometryStorages;
+
+static LIBRARY_FILE : &str = "custom_tools.txt";
+
+#[derive(Default)]
+pub struct CustomToolHandler {
+  geometry_action_reader: Option<GeometryActionReader>,
+  prompt_event_reader: Option<PromptEventReader>,
+}
+
+/// # Custom Tool Handler
+///
+/// Makes a custom tool out of the marked givens and the selected results.
+/// Its steps are everything the results depend on that is not a given, in
+/// the order they were created. Applying a tool creates its steps again on
+/// the picked givens once they are all picked.
+impl<'a> System<'a> for CustomToolHandler {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, GeometryActionChannel>,
+    Read<'a, PromptEventChannel>,
+    Read<'a, ConstructionProtocol>,
+    Write<'a, CustomToolLibrary>,
+    Write<'a, PromptState>,
+    Write<'a, SketchEventChannel>,
+    WriteStorage<'a, Selected>,
+    GeometryStorages<'a>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.geometry_action_reader = Some(world.fetch_mut::<GeometryActionChannel>().register_reader());
+    self.prompt_event_reader = Some(world.fetch_mut::<PromptEventChannel>().register_reader());
+    world.fetch_mut::<CustomToolLibrary>().load(&preferences_file(LIBRARY_FILE));
+  }
+
+  fn run(&mut self, (
+    entities,
+    geometry_action_channel,
+    prompt_events,
+    protocol,
+    mut library,
+    mut prompt_state,
+    mut sketch_events,
+    mut selected,
+    mut storages,
+  ): Self::SystemData) {
+
+    if let Some(reader_id) = &mut self.geometry_action_reader {
+      for event in geometry_action_channel.read(reader_id) {
+        if let GeometryAction::MarkCustomToolGivens = event {
+          let givens : HashSet<Entity> = (&entities, &selected).join()
+            .filter(|(ent, _)| storages.sym_points.get(*ent).is_some() || storages.sym_lines.get(*ent).is_some())
+            .map(|(ent, _)| ent)
+            .collect();
+          if !givens.is_empty() {
+            library.pending_givens = Some(protocol.creation_order(&givens));
+          }
+        }
+      }
+    }
+
+    if let Some(reader_id) = &mut self.prompt_event_reader {
+      for PromptEvent(kind, text) in prompt_events.read(reader_id) {
+        let name = text.trim();
+        let result = match kind {
+          PromptKind::CustomToolName => {
+            let givens = library.pending_givens.clone().unwrap_or_default();
+            let results : Vec<Entity> = (&entities, &selected).join().map(|(ent, _)| ent).filter(|ent| !givens.contains(ent)).collect();
+            make_tool(name, &givens, results, &storages, &protocol).and_then(|tool| {
+              library.add(tool);
+              library.pending_givens = None;
+              library.save(&preferences_file(LIBRARY_FILE))
+            })
+          },
+          PromptKind::ApplyCustomTool => match library.find(name) {
+            Some(tool) => {
+              library.application = Some(Application { tool, picks: vec![] });
+              for (ent, _) in (&entities, &selected).join() {
+                sketch_events.single_write(SketchEvent::Deselect(ent));
+              }
+              Ok(())
+            },
+            None => Err(format!("no tool named `{}`", name)),
+          },
+          _ => continue,
+        };
+        match result {
+          Ok(()) => prompt_state.close(),
+          Err(err) => prompt_state.set_error(err),
+        }
+      }
+    }
+
+    // Create the steps once every given is picked
+    let is_complete = match &library.application {
+      Some(application) => application.picks.len() >= library.tools()[application.tool].givens.len(),
+      None => false,
+    };
+    if is_complete {
+      if let Some(application) = library.application.take() {
+        for ent in &application.picks {
+          sketch_events.single_write(SketchEvent::Deselect(*ent));
+        }
+        // Nothing is inserted unless every step can be instantiated
+        let tool = &library.tools()[application.tool];
+        let mut slots = application.picks;
+        let mut created = vec![];
+        for step in &tool.steps {
+          match step.geometry(|slot| slots.get(slot).cloned()) {
+            Ok(geom) => {
+              let entity = entities.create();
+              created.push((entity, geom));
+              slots.push(entity);
+            },
+            Err(err) => {
+              for (entity, _) in created.drain(..) {
+                if let Err(err) = entities.delete(entity) { panic!("[custom_tool_handler] {:?}", err) }
+              }
+              prompt_state.open(PromptKind::ApplyCustomTool, tool.name.clone());
+              prompt_state.set_error(err);
+              break;
+            },
+          }
+        }
+        for (entity, geom) in created {
+          storages.insert(entity, &geom);
+          if let Err(err) = selected.insert(entity, Selected) { panic!("[custom_tool_handler] {:?}", err) }
+          sketch_events.single_write(SketchEvent::Insert(entity, geom));
+        }
+      }
+    }
+  }
+}
+
+/// The tool made of everything the results depend on down to the givens
+fn make_tool(
+  name: &str,
+  givens: &[Entity],
+  results: Vec<Entity>,
+  storages: &GeometryStorages,
+  protocol: &ConstructionProtocol,
+) -> Result<CustomTool, String> {
+  if name.is_empty() {
+    return Err("the tool needs a name".to_string());
+  } else if results.is_empty() {
+    return Err("select the results".to_string());
+  }
+
+  // Walk up from the results, stopping at the givens
+  let mut visited = HashSet::new();
+  let mut stack = results;
+  while let Some(ent) = stack.pop() {
+    if givens.contains(&ent) || !visited.insert(ent) {
+      continue;
+    }
+    if let Some(geom) = storages.get(ent) {
+      stack.extend(geom.parents());
+    }
+  }
+  let steps = protocol.creation_order(&visited);
+
+  // Then write every step with slots instead of entities
+  let slot_of = |ent: Entity| -> Option<usize> {
+    givens.iter().chain(steps.iter()).position(|e| *e == ent)
+  };
+  let definitions = steps.iter()
+    .map(|ent| storages.get(*ent).and_then(|geom| Definition::new(&geom, slot_of)))
+    .collect::<Option<Vec<_>>>()
+    .ok_or_else(|| "the results cannot be made from the givens".to_string())?;
+  let given_kinds = givens.iter()
+    .map(|ent| if storages.sym_points.get(*ent).is_some() { GivenKind::Point } else { GivenKind::Line })
+    .collect();
+  Ok(CustomTool { name: name.to_string(), givens: given_kinds, steps: definitions })
+}
\ No newline at end of file
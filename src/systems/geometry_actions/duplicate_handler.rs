@@ -0,0 +1,112 @@
+use std::collections::HashSet;
+use specs::prelude::*;
+use crate::{
+  utilities::Vector2,
+  resources::{
+    DependencyGraph,
+    Definition,
+    Viewport,
+    events::{GeometryAction, GeometryActionReader, GeometryActionChannel, SketchEvent, SketchEventChannel},
+  },
+  components::*,
+};
+use super::helpers::GeometryStorages;
+
+static DUPLICATE_OFFSET : f64 = 20.0; // In actual pixels
+
+#[derive(Default)]
+pub struct DuplicateHandler {
+  geometry_action_reader: Option<GeometryActionReader>,
+}
+
+/// # Duplicate Handler
+///
+/// Clones the selected geometries. A clone is defined on the clones of its
+/// selected parents, and on the very same parents otherwise: a line through
+/// A and B duplicated alone is still through A and B, while duplicated with
+/// A it goes through the new A. The clones replace the selection.
+impl<'a> System<'a> for DuplicateHandler {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, GeometryActionChannel>,
+    Read<'a, DependencyGraph>,
+    Read<'a, Viewport>,
+    Write<'a, SketchEventChannel>,
+    WriteStorage<'a, Selected>,
+    GeometryStorages<'a>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.geometry_action_reader = Some(world.fetch_mut::<GeometryActionChannel>().register_reader());
+  }
+
+  fn run(&mut self, (
+    entities,
+    geometry_action_channel,
+    dependency_graph,
+    viewport,
+    mut sketch_events,
+    mut selected,
+    mut storages,
+  ): Self::SystemData) {
+
+    if let Some(reader_id) = &mut self.geometry_action_reader {
+      for event in geometry_action_channel.read(reader_id) {
+        if let GeometryAction::DuplicateSelected = event {
+          let originals : HashSet<Entity> = (&entities, &selected).join()
+            .map(|(ent, _)| ent)
+            .filter(|ent| storages.get(*ent).is_some())
+            .collect();
+          let order = match dependency_graph.topological_order(&originals) {
+            Ok(order) => order,
+            Err(_) => continue,
+          };
+
+          // Slots are the originals in order, then the parents shared with
+          // the originals
+          let mut shared = vec![];
+          for ent in &order {
+            if let Some(geom) = storages.get(*ent) {
+              for parent in geom.parents() {
+                if !originals.contains(&parent) && !shared.contains(&parent) {
+                  shared.push(parent);
+                }
+              }
+            }
+          }
+          let slots : Vec<Entity> = order.iter().chain(shared.iter()).cloned().collect();
+          let definitions = order.iter()
+            .map(|ent| storages.get(*ent).and_then(|geom| Definition::new(&geom, |parent| slots.iter().position(|e| *e == parent))))
+            .collect::<Option<Vec<_>>>();
+          let definitions = match definitions {
+            Some(definitions) => definitions,
+            None => continue,
+          };
+
+          for ent in &order {
+            sketch_events.single_write(SketchEvent::Deselect(*ent));
+          }
+
+          let distance = DUPLICATE_OFFSET * viewport.scale();
+          let mut clones : Vec<Entity> = vec![];
+          for definition in &definitions {
+            let entity_of = |slot: usize| -> Option<Entity> {
+              if slot < order.len() { clones.get(slot).cloned() } else { shared.get(slot - order.len()).cloned() }
+            };
+            match definition.translated(vec2![distance, -distance]).geometry(entity_of) {
+              Ok(geom) => {
+                let entity = entities.create();
+                storages.insert(entity, &geom);
+                if let Err(err) = selected.insert(entity, Selected) { panic!("[duplicate_handler] {:?}", err) }
+                sketch_events.single_write(SketchEvent::Insert(entity, geom));
+                clones.push(entity);
+              },
+              Err(err) => panic!("[duplicate_handler] {}", err),
+            }
+          }
+        }
+      }
+    }
+  }
+}
\ No newline at end of file
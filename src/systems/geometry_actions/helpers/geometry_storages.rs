@@ -0,0 +1,122 @@
+use specs::{prelude::*, shred::ResourceId};
+use crate::{
+  resources::events::Geometry,
+  components::*,
+};
+
+type Storages<'a> = (
+  WriteStorage<'a, SymbolicPoint>,
+  WriteStorage<'a, PointStyle>,
+  WriteStorage<'a, SymbolicLine>,
+  WriteStorage<'a, LineStyle>,
+  WriteStorage<'a, SymbolicArc>,
+  WriteStorage<'a, ArcStyle>,
+  WriteStorage<'a, SymbolicConic>,
+  WriteStorage<'a, ConicStyle>,
+  WriteStorage<'a, SymbolicFunction>,
+  WriteStorage<'a, SymbolicCurve>,
+  WriteStorage<'a, SymbolicLocus>,
+  WriteStorage<'a, PlotStyle>,
+);
+
+/// The symbolic and style storages of every kind of geometry, to read the
+/// geometry of an entity or to insert a new one. Fetched as a single part
+/// of the system data.
+pub struct GeometryStorages<'a> {
+  pub sym_points: WriteStorage<'a, SymbolicPoint>,
+  pub point_styles: WriteStorage<'a, PointStyle>,
+  pub sym_lines: WriteStorage<'a, SymbolicLine>,
+  pub line_styles: WriteStorage<'a, LineStyle>,
+  pub sym_arcs: WriteStorage<'a, SymbolicArc>,
+  pub arc_styles: WriteStorage<'a, ArcStyle>,
+  pub sym_conics: WriteStorage<'a, SymbolicConic>,
+  pub conic_styles: WriteStorage<'a, ConicStyle>,
+  pub sym_functions: WriteStorage<'a, SymbolicFunction>,
+  pub sym_curves: WriteStorage<'a, SymbolicCurve>,
+  pub sym_loci: WriteStorage<'a, SymbolicLocus>,
+  pub plot_styles: WriteStorage<'a, PlotStyle>,
+}
+
+impl<'a> SystemData<'a> for GeometryStorages<'a> {
+  fn setup(world: &mut World) {
+    Storages::setup(world);
+  }
+
+  fn fetch(world: &'a World) -> Self {
+    let (
+      sym_points,
+      point_styles,
+      sym_lines,
+      line_styles,
+      sym_arcs,
+      arc_styles,
+      sym_conics,
+      conic_styles,
+      sym_functions,
+      sym_curves,
+      sym_loci,
+      plot_styles,
+    ) = Storages::fetch(world);
+    Self {
+      sym_points,
+      point_styles,
+      sym_lines,
+      line_styles,
+      sym_arcs,
+      arc_styles,
+      sym_conics,
+      conic_styles,
+      sym_functions,
+      sym_curves,
+      sym_loci,
+      plot_styles,
+    }
+  }
+
+  fn reads() -> Vec<ResourceId> {
+    Storages::reads()
+  }
+
+  fn writes() -> Vec<ResourceId> {
+    Storages::writes()
+  }
+}
+
+impl<'a> GeometryStorages<'a> {
+  pub fn get(&self, ent: Entity) -> Option<Geometry> {
+    if let (Some(sym_point), Some(style)) = (self.sym_points.get(ent), self.point_styles.get(ent)) {
+      Some(Geometry::Point(*sym_point, *style))
+    } else if let (Some(sym_line), Some(style)) = (self.sym_lines.get(ent), self.line_styles.get(ent)) {
+      Some(Geometry::Line(*sym_line, *style))
+    } else if let (Some(sym_arc), Some(style)) = (self.sym_arcs.get(ent), self.arc_styles.get(ent)) {
+      Some(Geometry::Arc(*sym_arc, *style))
+    } else if let (Some(sym_conic), Some(style)) = (self.sym_conics.get(ent), self.conic_styles.get(ent)) {
+      Some(Geometry::Conic(*sym_conic, *style))
+    } else if let Some(style) = self.plot_styles.get(ent) {
+      if let Some(sym_function) = self.sym_functions.get(ent) {
+        Some(Geometry::Function(sym_function.clone(), *style))
+      } else if let Some(sym_curve) = self.sym_curves.get(ent) {
+        Some(Geometry::Curve(sym_curve.clone(), *style))
+      } else {
+        self.sym_loci.get(ent).map(|sym_locus| Geometry::Locus(*sym_locus, *style))
+      }
+    } else {
+      None
+    }
+  }
+
+  /// Insert the components of the geometry. The solved ones are left to the
+  /// solver, the plot cache and the locus system
+  pub fn insert(&mut self, ent: Entity, geom: &Geometry) {
+    let result = match geom {
+      Geometry::Point(sym_point, style) => self.sym_points.insert(ent, *sym_point).and(self.point_styles.insert(ent, *style)).map(|_| ()),
+      Geometry::Line(sym_line, style) => self.sym_lines.insert(ent, *sym_line).and(self.line_styles.insert(ent, *style)).map(|_| ()),
+      Geometry::Arc(sym_arc, style) => self.sym_arcs.insert(ent, *sym_arc).and(self.arc_styles.insert(ent, *style)).map(|_| ()),
+      Geometry::Conic(sym_conic, style) => self.sym_conics.insert(ent, *sym_conic).and(self.conic_styles.insert(ent, *style)).map(|_| ()),
+      Geometry::Function(sym_function, style) => self.sym_functions.insert(ent, sym_function.clone()).and(self.plot_styles.insert(ent, *style)).map(|_| ()),
+      Geometry::Curve(sym_curve, style) => self.sym_curves.insert(ent, sym_curve.clone()).and(self.plot_styles.insert(ent, *style)).map(|_| ()),
+      Geometry::Locus(sym_locus, style) => self.sym_loci.insert(ent, *sym_locus).and(self.plot_styles.insert(ent, *style)).map(|_| ()),
+    };
+    if let Err(err) = result { panic!("[geometry_storages] {:?}", err) }
+  }
+}
\ No newline at end of file
@@ -0,0 +1,2 @@
+mod geometry_storages;
+pub use geometry_storages::*;
\ No newline at end of file
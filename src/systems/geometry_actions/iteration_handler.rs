@@ -0,0 +1,238 @@
+use std::collections::HashSet;
+use specs::prelude::*;
+use crate::{
+  resources::{
+    ConstructionProtocol,
+    DependencyGraph,
+    Definition,
+    Iteration,
+    Iterations,
+    ImagePicking,
+    events::{GeometryAction, GeometryActionReader, GeometryActionChannel, SketchEvent, SketchEventChannel},
+  },
+  components::*,
+};
+use super::helpers::GeometryStorages;
+
+#[derive(Default)]
+pub struct IterationHandler {
+  geometry_action_reader: Option<GeometryActionReader>,
+}
+
+/// # Iteration Handler
+///
+/// Starts picking the images of the selected points, changes the depth of
+/// iterations, and creates the generated geometries of an iteration again
+/// whenever its depth changes. Everything depending on the pre-images is
+/// repeated, while what it depends on otherwise is shared by every
+/// generation.
+impl<'a> System<'a> for IterationHandler {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, GeometryActionChannel>,
+    Read<'a, DependencyGraph>,
+    Read<'a, ConstructionProtocol>,
+    Write<'a, Iterations>,
+    Write<'a, SketchEventChannel>,
+    ReadStorage<'a, Selected>,
+    GeometryStorages<'a>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.geometry_action_reader = Some(world.fetch_mut::<GeometryActionChannel>().register_reader());
+  }
+
+  fn run(&mut self, (
+    entities,
+    geometry_action_channel,
+    dependency_graph,
+    protocol,
+    mut iterations,
+    mut sketch_events,
+    selected,
+    mut storages,
+  ): Self::SystemData) {
+
+    // Iterations whose points are removed are gone, along with what they
+    // generated as it depends on the images
+    iterations.iterations.retain(|iteration| {
+      iteration.pre_images.iter().chain(iteration.images.iter()).all(|ent| storages.sym_points.get(*ent).is_some())
+    });
+
+    if let Some(reader_id) = &mut self.geometry_action_reader {
+      for event in geometry_action_channel.read(reader_id) {
+        match event {
+          GeometryAction::IterateSelected => {
+            let pre_images : HashSet<Entity> = (&entities, &storages.sym_points, &selected).join().map(|(ent, _, _)| ent).collect();
+            if !pre_images.is_empty() {
+              for ent in &pre_images {
+                sketch_events.single_write(SketchEvent::Deselect(*ent));
+              }
+              iterations.picking = Some(ImagePicking { pre_images: protocol.creation_order(&pre_images), images: vec![] });
+            }
+          },
+          GeometryAction::ChangeIterationDepth(delta) => {
+
+            // The iteration of the selection, or else the last one
+            let index = iterations.iterations.iter()
+              .position(|iteration| (&entities, &selected).join().any(|(ent, _)| iteration.involves(ent)))
+              .or_else(|| iterations.iterations.len().checked_sub(1));
+            if let Some(index) = index {
+              iterations.iterations[index].change_depth(*delta);
+            }
+          },
+          _ => (),
+        }
+      }
+    }
+
+    // The iteration is made once every image is picked
+    let is_complete = match &iterations.picking {
+      Some(picking) => picking.images.len() >= picking.pre_images.len(),
+      None => false,
+    };
+    if is_complete {
+      if let Some(ImagePicking { pre_images, images }) = iterations.picking.take() {
+        for ent in &images {
+          sketch_events.single_write(SketchEvent::Deselect(*ent));
+        }
+        iterations.iterations.push(Iteration::new(pre_images, images));
+      }
+    }
+
+    // Generate the dirty iterations again
+    let all_generated : HashSet<Entity> = iterations.all_generated().cloned().collect();
+    for iteration in iterations.iterations.iter_mut().filter(|iteration| iteration.dirty) {
+      iteration.dirty = false;
+
+      // Remove what was generated before, along with anything built on it
+      let mut to_remove = HashSet::new();
+      for ent in iteration.generated.drain(..) {
+        to_remove.extend(dependency_graph.get_all_dependents(&ent));
+      }
+      for ent in to_remove.iter().cloned() {
+        if let Some(geom) = storages.get(ent) {
+          sketch_events.single_write(SketchEvent::Remove(ent, geom));
+        }
+      }
+
+      // The steps are everything depending on the pre-images, except what
+      // is being removed since the storages still have it. Their other
+      // parents are external and shared by every generation
+      let mut descendants = HashSet::new();
+      for pre_image in &iteration.pre_images {
+        descendants.extend(dependency_graph.get_all_dependents(pre_image));
+      }
+      descendants.retain(|ent| {
+        !iteration.pre_images.contains(ent) && !all_generated.contains(ent) && !to_remove.contains(ent) && storages.get(*ent).is_some()
+      });
+      let steps = protocol.creation_order(&descendants);
+      let mut externals = vec![];
+      for step in &steps {
+        if let Some(geom) = storages.get(*step) {
+          for parent in geom.parents() {
+            if !iteration.pre_images.contains(&parent) && !descendants.contains(&parent) && !externals.contains(&parent) {
+              externals.push(parent);
+            }
+          }
+        }
+      }
+
+      // Write the steps with slots: the pre-images, the externals, then the
+      // steps themselves
+      let first_step_slot = iteration.pre_images.len() + externals.len();
+      let slots : Vec<Entity> = iteration.pre_images.iter().chain(externals.iter()).chain(steps.iter()).cloned().collect();
+      let definitions = steps.iter()
+        .map(|ent| storages.get(*ent).and_then(|geom| Definition::new(&geom, |parent| slots.iter().position(|e| *e == parent))))
+        .collect::<Option<Vec<_>>>();
+      let image_steps = iteration.images.iter()
+        .map(|image| steps.iter().position(|step| step == image))
+        .collect::<Option<Vec<_>>>();
+      let (definitions, image_steps) = match (definitions, image_steps) {
+        (Some(definitions), Some(image_steps)) => (definitions, image_steps),
+        _ => continue,
+      };
+
+      // Then create every generation on the images of the last one
+      let mut givens = iteration.images.clone();
+      for _ in 0..iteration.generated_depth(definitions.len()) {
+        let mut slots : Vec<Entity> = givens.iter().chain(externals.iter()).cloned().collect();
+        for definition in &definitions {
+          match definition.geometry(|slot| slots.get(slot).cloned()) {
+            Ok(geom) => {
+              let entity = entities.create();
+              storages.insert(entity, &geom);
+              sketch_events.single_write(SketchEvent::Insert(entity, geom));
+              slots.push(entity);
+              iteration.generated.push(entity);
+            },
+            Err(err) => panic!("[iteration_handler] {}", err),
+          }
+        }
+        givens = image_steps.iter().map(|step| slots[first_step_slot + step]).collect();
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    utilities::{Vector2, Color},
+    resources::events::Geometry,
+    systems::cache_managers::DependencyGraphCache,
+  };
+  use super::*;
+
+  fn create(world: &mut World, geom: Geometry) -> Entity {
+    let entity = world.create_entity().build();
+    world.system_data::<GeometryStorages>().insert(entity, &geom);
+    world.write_resource::<ConstructionProtocol>().push(entity, String::new());
+    entity
+  }
+
+  #[test]
+  fn test_generation() {
+    let mut world = World::new();
+    let (mut graph_cache, mut handler) = (DependencyGraphCache::default(), IterationHandler::default());
+    System::setup(&mut graph_cache, &mut world);
+    System::setup(&mut handler, &mut world);
+    world.register::<Selected>();
+    let (point_style, line_style) = (PointStyle { radius: 5., color: Color::black() }, LineStyle { width: 2., color: Color::black() });
+
+    // The image is on the line from the pre-image through an external point
+    let pre_image = create(&mut world, Geometry::Point(SymbolicPoint::Free(vec2![0., 0.]), point_style));
+    let external = create(&mut world, Geometry::Point(SymbolicPoint::Free(vec2![1., 0.]), point_style));
+    let line = create(&mut world, Geometry::Line(SymbolicLine::TwoPoints(pre_image, external), line_style));
+    let image = create(&mut world, Geometry::Point(SymbolicPoint::OnLine(line, 0.5), point_style));
+    let mut iteration = Iteration::new(vec![pre_image], vec![image]);
+    iteration.depth = 1;
+    world.write_resource::<Iterations>().iterations.push(iteration);
+    graph_cache.run_now(&world);
+    handler.run_now(&world);
+    graph_cache.run_now(&world);
+    world.maintain();
+
+    let generated = world.read_resource::<Iterations>().iterations[0].generated.clone();
+    assert_eq!(generated.len(), 2);
+    let storages = world.system_data::<GeometryStorages>();
+    match storages.get(generated[0]) {
+      Some(Geometry::Line(SymbolicLine::TwoPoints(p, e), _)) => assert!(p == image && e == external),
+      _ => panic!("The first generated geometry should be a line"),
+    }
+    match storages.get(generated[1]) {
+      Some(Geometry::Point(SymbolicPoint::OnLine(l, _), _)) => assert_eq!(l, generated[0]),
+      _ => panic!("The second generated geometry should be a point on it"),
+    }
+    drop(storages);
+
+    // What is built on a generated geometry is removed with it, and is not
+    // generated again along with the steps
+    let built_on = create(&mut world, Geometry::Point(SymbolicPoint::OnLine(generated[0], 2.), point_style));
+    world.write_resource::<DependencyGraph>().add(&generated[0], &built_on);
+    world.write_resource::<Iterations>().iterations[0].change_depth(1);
+    handler.run_now(&world);
+    assert_eq!(world.read_resource::<Iterations>().iterations[0].generated.len(), 4);
+  }
+}
\ No newline at end of file
@@ -1,5 +1,40 @@
+mod helpers;
+
 mod remove_selected_handler;
 pub use remove_selected_handler::*;
 
 mod selde_all_handler;
-pub use selde_all_handler::*;
\ No newline at end of file
+pub use selde_all_handler::*;
+
+mod create_locus_handler;
+pub use create_locus_handler::*;
+
+mod trace_handler;
+pub use trace_handler::*;
+
+mod protocol_handler;
+pub use protocol_handler::*;
+
+mod custom_tool_handler;
+pub use custom_tool_handler::*;
+
+mod iteration_handler;
+pub use iteration_handler::*;
+
+mod clipboard_handler;
+pub use clipboard_handler::*;
+
+mod duplicate_handler;
+pub use duplicate_handler::*;
+
+mod redefine_handler;
+pub use redefine_handler::*;
+
+mod recolor_handler;
+pub use recolor_handler::*;
+
+mod perpendicular_handler;
+pub use perpendicular_handler::*;
+
+mod tangent_handler;
+pub use tangent_handler::*;
\ No newline at end of file
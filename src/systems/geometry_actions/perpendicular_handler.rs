@@ -0,0 +1,81 @@
+use specs::prelude::*;
+use crate::{
+  utilities::Color,
+  resources::events::{GeometryAction, GeometryActionReader, GeometryActionChannel, SketchEvent, SketchEventChannel, Geometry},
+  components::{SymbolicPoint, PointStyle, SymbolicLine, Line, LineStyle, Selected},
+};
+
+#[derive(Default)]
+pub struct PerpendicularHandler {
+  geometry_action_reader: Option<GeometryActionReader>,
+}
+
+/// # Perpendicular Handler
+///
+/// Constructs the line perpendicular to a line through a new point on it,
+/// where the line was clicked. The new line is selected alone.
+impl<'a> System<'a> for PerpendicularHandler {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, GeometryActionChannel>,
+    Write<'a, SketchEventChannel>,
+    ReadStorage<'a, Line>,
+    WriteStorage<'a, SymbolicPoint>,
+    WriteStorage<'a, PointStyle>,
+    WriteStorage<'a, SymbolicLine>,
+    WriteStorage<'a, LineStyle>,
+    WriteStorage<'a, Selected>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.geometry_action_reader = Some(world.fetch_mut::<GeometryActionChannel>().register_reader());
+  }
+
+  fn run(&mut self, (
+    entities,
+    geometry_action_channel,
+    mut sketch_events,
+    lines,
+    mut sym_points,
+    mut point_styles,
+    mut sym_lines,
+    mut line_styles,
+    mut selected,
+  ): Self::SystemData) {
+    if let Some(reader_id) = &mut self.geometry_action_reader {
+      for event in geometry_action_channel.read(reader_id) {
+        if let GeometryAction::ConstructPerpendicular(line_ent, position) = event {
+          let line = match lines.get(*line_ent) {
+            Some(line) => *line,
+            None => continue,
+          };
+
+          // The foot point, on the line where it was clicked
+          let t = (*position - line.origin).dot(line.direction);
+          let sym_point = SymbolicPoint::OnLine(*line_ent, t);
+          let point_style = PointStyle { color: Color::red(), radius: 5. };
+          let point_ent = entities.create();
+          if let Err(err) = sym_points.insert(point_ent, sym_point) { panic!("[perpendicular_handler] {:?}", err) }
+          if let Err(err) = point_styles.insert(point_ent, point_style) { panic!("[perpendicular_handler] {:?}", err) }
+          sketch_events.single_write(SketchEvent::Insert(point_ent, Geometry::Point(sym_point, point_style)));
+
+          // Then the perpendicular line through it
+          let sym_line = SymbolicLine::Perpendicular(*line_ent, point_ent);
+          let line_style = LineStyle { color: Color::blue(), width: 2. };
+          let perpendicular_ent = entities.create();
+          if let Err(err) = sym_lines.insert(perpendicular_ent, sym_line) { panic!("[perpendicular_handler] {:?}", err) }
+          if let Err(err) = line_styles.insert(perpendicular_ent, line_style) { panic!("[perpendicular_handler] {:?}", err) }
+          sketch_events.single_write(SketchEvent::Insert(perpendicular_ent, Geometry::Line(sym_line, line_style)));
+
+          for (ent, _) in (&entities, &selected).join() {
+            sketch_events.single_write(SketchEvent::Deselect(ent));
+          }
+          if let Err(err) = selected.insert(perpendicular_ent, Selected) { panic!("[perpendicular_handler] {:?}", err) }
+        }
+      }
+    } else {
+      panic!("[perpendicular_handler] No reader id");
+    }
+  }
+}
\ No newline at end of file
@@ -0,0 +1,121 @@
+use specs::prelude::*;
+use crate::{
+  resources::{
+    ConstructionProtocol,
+    SpatialHashTable,
+    Viewport,
+    events::{GeometryAction, GeometryActionReader, GeometryActionChannel, SketchEvent, SketchEventChannel},
+  },
+  components::{Point, Line, Arc, Conic, Plot, Selected, Hidden, HiddenByUser},
+};
+
+#[derive(Default)]
+pub struct ProtocolHandler {
+  geometry_action_reader: Option<GeometryActionReader>,
+}
+
+/// # Protocol Handler
+///
+/// Steps through the construction protocol and selects the geometry of a
+/// step. Then hides everything created after the current step, along with
+/// what the user hid, taking it out of the spatial hash table so that it
+/// cannot be hit either.
+impl<'a> System<'a> for ProtocolHandler {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, GeometryActionChannel>,
+    Read<'a, Viewport>,
+    Write<'a, ConstructionProtocol>,
+    Write<'a, SpatialHashTable<Entity>>,
+    Write<'a, SketchEventChannel>,
+    ReadStorage<'a, Point>,
+    ReadStorage<'a, Line>,
+    ReadStorage<'a, Arc>,
+    ReadStorage<'a, Conic>,
+    ReadStorage<'a, Plot>,
+    ReadStorage<'a, Selected>,
+    WriteStorage<'a, Hidden>,
+    WriteStorage<'a, HiddenByUser>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.geometry_action_reader = Some(world.fetch_mut::<GeometryActionChannel>().register_reader());
+  }
+
+  fn run(&mut self, (
+    entities,
+    geometry_action_channel,
+    viewport,
+    mut protocol,
+    mut table,
+    mut sketch_event_channel,
+    points,
+    lines,
+    arcs,
+    conics,
+    plots,
+    selected,
+    mut hidden,
+    mut hidden_by_user,
+  ): Self::SystemData) {
+    if let Some(reader_id) = &mut self.geometry_action_reader {
+      for event in geometry_action_channel.read(reader_id) {
+        let to_select = match event {
+          GeometryAction::StepProtocolBackward => if protocol.step_backward() { protocol.current() } else { None },
+          GeometryAction::StepProtocolForward => if protocol.step_forward() { protocol.current() } else { None },
+          GeometryAction::SelectProtocolStep(index) if protocol.is_shown(*index) => Some(*index),
+          GeometryAction::HideSelected => {
+            for (entity, _) in (&entities, &selected).join() {
+              if let Err(err) = hidden_by_user.insert(entity, HiddenByUser) { panic!("[protocol_handler] {:?}", err) }
+            }
+            None
+          },
+          GeometryAction::ShowAllHidden => {
+            hidden_by_user.clear();
+            None
+          },
+          _ => None,
+        };
+
+        // The geometry of the step is selected alone
+        if let Some(step) = to_select.and_then(|index| protocol.steps().get(index)) {
+          for (entity, _) in (&entities, &selected).join() {
+            if entity != step.entity {
+              sketch_event_channel.single_write(SketchEvent::Deselect(entity));
+            }
+          }
+          if selected.get(step.entity).is_none() {
+            sketch_event_channel.single_write(SketchEvent::Select(step.entity));
+          }
+        }
+      }
+    }
+
+    // Hide or show the geometries according to the current step
+    for (index, step) in protocol.steps().iter().enumerate() {
+      let entity = step.entity;
+      if protocol.is_shown(index) && hidden_by_user.get(entity).is_none() {
+        if hidden.remove(entity).is_some() {
+          if let Some(point) = points.get(entity) {
+            table.insert_point(entity, *point, &viewport);
+          } else if let Some(line) = lines.get(entity) {
+            table.insert_line(entity, *line, &viewport);
+          } else if let Some(arc) = arcs.get(entity) {
+            table.insert_arc(entity, *arc, &viewport);
+          } else if let Some(conic) = conics.get(entity) {
+            table.insert_conic(entity, conic, &viewport);
+          } else if let Some(plot) = plots.get(entity) {
+            table.insert_plot(entity, plot, &viewport);
+          }
+        }
+      } else if hidden.get(entity).is_none() {
+        if let Err(err) = hidden.insert(entity, Hidden) { panic!("[protocol_handler] {:?}", err) }
+        table.remove_from_all(entity);
+        if selected.get(entity).is_some() {
+          sketch_event_channel.single_write(SketchEvent::Deselect(entity));
+        }
+      }
+    }
+  }
+}
\ No newline at end of file
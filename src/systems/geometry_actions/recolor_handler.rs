@@ -0,0 +1,101 @@
+use specs::prelude::*;
+use crate::{
+  utilities::Color,
+  resources::events::{GeometryAction, GeometryActionReader, GeometryActionChannel},
+  components::{PointStyle, LineStyle, ArcStyle, ArcFill, ConicStyle, PlotStyle, Selected},
+};
+
+static PALETTE : [Color; 5] = [
+  Color { r: 1.0, g: 0.0, b: 0.0, a: 1.0 },
+  Color { r: 0.0, g: 0.0, b: 1.0, a: 1.0 },
+  Color { r: 0.0, g: 0.6, b: 0.0, a: 1.0 },
+  Color { r: 1.0, g: 0.5, b: 0.0, a: 1.0 },
+  Color { r: 0.0, g: 0.0, b: 0.0, a: 1.0 },
+];
+
+/// The color after this one in the palette, or the first one for colors
+/// not in the palette
+fn next_color(color: Color) -> Color {
+  match PALETTE.iter().position(|c| *c == color) {
+    Some(index) => PALETTE[(index + 1) % PALETTE.len()],
+    None => PALETTE[0],
+  }
+}
+
+/// No fill, then the sector, then the segment, in a lighter shade of the arc
+fn next_fill(style: &ArcStyle) -> ArcFill {
+  match style.fill {
+    ArcFill::None => ArcFill::Sector(Color { a: 0.3, ..style.color }),
+    ArcFill::Sector(color) => ArcFill::Segment(color),
+    ArcFill::Segment(_) => ArcFill::None,
+  }
+}
+
+#[derive(Default)]
+pub struct RecolorHandler {
+  geometry_action_reader: Option<GeometryActionReader>,
+}
+
+/// # Recolor Handler
+///
+/// Gives every selected geometry the next color of the palette, or every
+/// selected arc its next fill.
+impl<'a> System<'a> for RecolorHandler {
+  type SystemData = (
+    Read<'a, GeometryActionChannel>,
+    ReadStorage<'a, Selected>,
+    WriteStorage<'a, PointStyle>,
+    WriteStorage<'a, LineStyle>,
+    WriteStorage<'a, ArcStyle>,
+    WriteStorage<'a, ConicStyle>,
+    WriteStorage<'a, PlotStyle>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.geometry_action_reader = Some(world.fetch_mut::<GeometryActionChannel>().register_reader());
+  }
+
+  fn run(&mut self, (
+    geometry_action_channel,
+    selected,
+    mut point_styles,
+    mut line_styles,
+    mut arc_styles,
+    mut conic_styles,
+    mut plot_styles,
+  ): Self::SystemData) {
+    if let Some(reader_id) = &mut self.geometry_action_reader {
+      for event in geometry_action_channel.read(reader_id) {
+        if let GeometryAction::RefillSelected = event {
+          for (style, _) in (&mut arc_styles, &selected).join() {
+            style.fill = next_fill(style);
+          }
+        } else if let GeometryAction::RecolorSelected = event {
+          for (style, _) in (&mut point_styles, &selected).join() {
+            style.color = next_color(style.color);
+          }
+          for (style, _) in (&mut line_styles, &selected).join() {
+            style.color = next_color(style.color);
+          }
+          for (style, _) in (&mut arc_styles, &selected).join() {
+            style.color = next_color(style.color);
+            style.fill = match style.fill {
+              ArcFill::None => ArcFill::None,
+              ArcFill::Sector(color) => ArcFill::Sector(Color { a: color.a, ..style.color }),
+              ArcFill::Segment(color) => ArcFill::Segment(Color { a: color.a, ..style.color }),
+            };
+          }
+          for (style, _) in (&mut conic_styles, &selected).join() {
+            style.color = next_color(style.color);
+          }
+          for (style, _) in (&mut plot_styles, &selected).join() {
+            style.color = next_color(style.color);
+          }
+        }
+      }
+    } else {
+      panic!("[recolor_handler] No reader id");
+    }
+  }
+}
\ No newline at end of file
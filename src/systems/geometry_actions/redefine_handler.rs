@@ -0,0 +1,180 @@
+use std::collections::HashSet;
+use specs::prelude::*;
+use crate::{
+  components::*,
+  resources::{
+    ConstructionProtocol,
+    DependencyGraph,
+    geometry::SnapPointType,
+    events::{GeometryAction, GeometryActionReader, GeometryActionChannel, Geometry, SketchEvent, SketchEventChannel},
+  },
+};
+use super::helpers::GeometryStorages;
+
+#[derive(Default)]
+pub struct RedefineHandler {
+  geometry_action_reader: Option<GeometryActionReader>,
+}
+
+/// # Redefine Handler
+///
+/// Changes how the selected geometry is defined while keeping everything
+/// defined on it. With the selection being
+///
+/// - a point and a line, the point is put on the line where it is closest
+/// - a line and two points, the line goes through the two points instead
+/// - a single point that is not free, the point is freed where it is
+///
+/// A free point dropped onto something is attached to it the same way, and a
+/// free point dropped onto another point is merged into it: everything on
+/// the dropped point is then defined on the other one instead.
+///
+/// A definition on something that depends on the geometry itself would make
+/// a cycle and is rejected. The protocol is reordered if the geometry now
+/// depends on something created after it.
+impl<'a> System<'a> for RedefineHandler {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, GeometryActionChannel>,
+    Read<'a, DependencyGraph>,
+    Write<'a, ConstructionProtocol>,
+    Write<'a, SketchEventChannel>,
+    ReadStorage<'a, Selected>,
+    ReadStorage<'a, Point>,
+    ReadStorage<'a, Line>,
+    GeometryStorages<'a>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.geometry_action_reader = Some(world.fetch_mut::<GeometryActionChannel>().register_reader());
+  }
+
+  fn run(&mut self, (
+    entities,
+    geometry_action_channel,
+    dependency_graph,
+    mut protocol,
+    mut sketch_events,
+    selected,
+    points,
+    lines,
+    mut storages,
+  ): Self::SystemData) {
+
+    if let Some(reader_id) = &mut self.geometry_action_reader {
+      for event in geometry_action_channel.read(reader_id) {
+        let redefinition = match event {
+          GeometryAction::RedefineSelected => {
+            let selection : Vec<Entity> = (&entities, &selected).join().map(|(ent, _)| ent).collect();
+            redefine_selection(selection, &protocol, &storages, &points, &lines)
+          },
+          GeometryAction::AttachPoint(entity, SnapPointType::SnapOnPoint(target)) => {
+            if !dependency_graph.get_all_dependents(entity).contains(target) && !share_child(*entity, *target, &dependency_graph) {
+              merge_point(*entity, *target, &dependency_graph, &mut storages, &mut sketch_events);
+              protocol.reorder(|ent| storages.get(ent).map_or(vec![], |geom| geom.parents()));
+            }
+            None
+          },
+          GeometryAction::AttachPoint(entity, symbo) => match (symbo.symbolic_point(), storages.get(*entity)) {
+            (Some(sym_point), Some(Geometry::Point(_, style))) => Some((*entity, Geometry::Point(sym_point, style))),
+            _ => None,
+          },
+          _ => None,
+        };
+        let (entity, new_geom) = match redefinition {
+          Some(redefinition) => redefinition,
+          None => continue,
+        };
+
+        // Reject cycles
+        let dependents : HashSet<Entity> = dependency_graph.get_all_dependents(&entity);
+        if new_geom.parents().iter().any(|parent| dependents.contains(parent)) {
+          continue;
+        }
+
+        if let Some(old_geom) = storages.get(entity) {
+          storages.insert(entity, &new_geom);
+          protocol.reorder(|ent| storages.get(ent).map_or(vec![], |geom| geom.parents()));
+          sketch_events.single_write(SketchEvent::Redefine(entity, old_geom, new_geom));
+        }
+      }
+    }
+  }
+}
+
+/// The selected geometry along with its new definition on the rest of the
+/// selection
+fn redefine_selection<'a>(
+  selection: Vec<Entity>,
+  protocol: &ConstructionProtocol,
+  storages: &GeometryStorages,
+  points: &ReadStorage<'a, Point>,
+  lines: &ReadStorage<'a, Line>,
+) -> Option<(Entity, Geometry)> {
+  let sel_points : Vec<Entity> = selection.iter().cloned().filter(|ent| storages.sym_points.get(*ent).is_some()).collect();
+  let sel_lines : Vec<Entity> = selection.iter().cloned().filter(|ent| storages.sym_lines.get(*ent).is_some()).collect();
+  if sel_points.len() + sel_lines.len() != selection.len() {
+    return None;
+  }
+  match (sel_points.as_slice(), sel_lines.as_slice()) {
+    ([point], [line]) => match (points.get(*point), lines.get(*line), storages.get(*point)) {
+      (Some(position), Some(Line { origin, direction }), Some(Geometry::Point(_, style))) => {
+        let t = (*position - *origin).dot(*direction);
+        Some((*point, Geometry::Point(SymbolicPoint::OnLine(*line, t), style)))
+      },
+      _ => None,
+    },
+    ([p1, p2], [line]) => match storages.get(*line) {
+      Some(Geometry::Line(_, style)) => {
+        let (p1, p2) = if index_of(protocol, *p1) <= index_of(protocol, *p2) { (*p1, *p2) } else { (*p2, *p1) };
+        Some((*line, Geometry::Line(SymbolicLine::TwoPoints(p1, p2), style)))
+      },
+      _ => None,
+    },
+    ([point], []) => match (points.get(*point), storages.get(*point)) {
+      (Some(position), Some(Geometry::Point(sym_point, style))) => match sym_point {
+        SymbolicPoint::Free(_) => None,
+        _ => Some((*point, Geometry::Point(SymbolicPoint::Free(*position), style))),
+      },
+      _ => None,
+    },
+    _ => None,
+  }
+}
+
+/// Define everything on the point on the target instead, then remove the
+/// point
+fn merge_point(
+  entity: Entity,
+  target: Entity,
+  dependency_graph: &DependencyGraph,
+  storages: &mut GeometryStorages,
+  sketch_events: &mut SketchEventChannel,
+) {
+  let children : Vec<Entity> = dependency_graph.get_direct_dependents(&entity).into_iter().flatten().cloned().collect();
+  for child in children {
+    if let Some(old_geom) = storages.get(child) {
+      let new_geom = old_geom.map_parents(|parent| if parent == entity { target } else { parent });
+      storages.insert(child, &new_geom);
+      sketch_events.single_write(SketchEvent::Redefine(child, old_geom, new_geom));
+    }
+  }
+  if let Some(geom) = storages.get(entity) {
+    sketch_events.single_write(SketchEvent::Remove(entity, geom));
+  }
+  sketch_events.single_write(SketchEvent::Select(target));
+}
+
+/// Merging points with a common child would define it twice on the same
+/// point, e.g. a line through a single point
+fn share_child(p1: Entity, p2: Entity, dependency_graph: &DependencyGraph) -> bool {
+  match (dependency_graph.get_direct_dependents(&p1), dependency_graph.get_direct_dependents(&p2)) {
+    (Some(children_1), Some(children_2)) => !children_1.is_disjoint(children_2),
+    _ => false,
+  }
+}
+
+fn index_of(protocol: &ConstructionProtocol, entity: Entity) -> Option<usize> {
+  protocol.steps().iter().position(|step| step.entity == entity)
+}
\ No newline at end of file
@@ -5,11 +5,12 @@ use crate::{
     DependencyGraph,
     events::{
       GeometryAction, GeometryActionReader, GeometryActionChannel,
-      SketchEvent, SketchEventChannel, Geometry
+      SketchEvent, SketchEventChannel,
     },
   },
-  components::{SymbolicLine, SymbolicPoint, PointStyle, LineStyle, Selected},
+  components::Selected,
 };
+use super::helpers::GeometryStorages;
 
 pub struct RemoveSelectedHandler {
   geometry_action_reader: Option<GeometryActionReader>,
@@ -27,10 +28,7 @@ impl<'a> System<'a> for RemoveSelectedHandler {
     Read<'a, GeometryActionChannel>,
     Read<'a, DependencyGraph>,
     Write<'a, SketchEventChannel>,
-    ReadStorage<'a, SymbolicPoint>,
-    ReadStorage<'a, PointStyle>,
-    ReadStorage<'a, SymbolicLine>,
-    ReadStorage<'a, LineStyle>,
+    GeometryStorages<'a>,
     ReadStorage<'a, Selected>,
   );
 
@@ -44,10 +42,7 @@ impl<'a> System<'a> for RemoveSelectedHandler {
     geometry_action_channel,
     dep_graph,
     mut sketch_events,
-    sym_points,
-    point_styles,
-    sym_lines,
-    line_styles,
+    storages,
     selected,
   ): Self::SystemData) {
     if let Some(reader_id) = &mut self.geometry_action_reader {
@@ -65,20 +60,8 @@ impl<'a> System<'a> for RemoveSelectedHandler {
 
             // Remove everything
             for entity in to_remove {
-
-              // Push the event
-              if let Some(sym_pt) = sym_points.get(entity) {
-                if let Some(pt_sty) = point_styles.get(entity) {
-                  sketch_events.single_write(SketchEvent::Remove(entity, Geometry::Point(*sym_pt, *pt_sty)));
-                } else {
-                  panic!("[remove_selected_handler] Cannot find point style for point entity {:?}", entity);
-                }
-              } else if let Some(sym_ln) = sym_lines.get(entity) {
-                if let Some(ln_sty) = line_styles.get(entity) {
-                  sketch_events.single_write(SketchEvent::Remove(entity, Geometry::Line(*sym_ln, *ln_sty)));
-                } else {
-                  panic!("[remove_selected_handler] Cannot find line style for line entity {:?}", entity);
-                }
+              if let Some(geom) = storages.get(entity) {
+                sketch_events.single_write(SketchEvent::Remove(entity, geom));
               }
             }
 
@@ -1,7 +1,9 @@
+use std::collections::HashSet;
 use specs::prelude::*;
 use crate::{
   components::*,
   resources::{
+    DependencyGraph,
     events::{
       GeometryAction, GeometryActionReader, GeometryActionChannel,
       SketchEvent, SketchEventChannel
@@ -23,12 +25,22 @@ impl<'a> System<'a> for SeldeAllHandler {
   type SystemData = (
     Entities<'a>,
     Read<'a, GeometryActionChannel>,
+    Read<'a, DependencyGraph>,
     Write<'a, SketchEventChannel>,
     ReadStorage<'a, SymbolicPoint>,
     ReadStorage<'a, Point>,
     ReadStorage<'a, SymbolicLine>,
     ReadStorage<'a, Line>,
+    ReadStorage<'a, SymbolicArc>,
+    ReadStorage<'a, ArcStyle>,
+    ReadStorage<'a, SymbolicConic>,
+    ReadStorage<'a, ConicStyle>,
+    ReadStorage<'a, SymbolicFunction>,
+    ReadStorage<'a, SymbolicCurve>,
+    ReadStorage<'a, SymbolicLocus>,
+    ReadStorage<'a, PlotStyle>,
     ReadStorage<'a, Selected>,
+    ReadStorage<'a, Hidden>,
   );
 
   fn setup(&mut self, world: &mut World) {
@@ -39,24 +51,111 @@ impl<'a> System<'a> for SeldeAllHandler {
   fn run(&mut self, (
     entities,
     geometry_action_channel,
+    dependency_graph,
     mut sketch_event_channel,
     sym_points,
     point_styles,
     sym_lines,
     line_styles,
+    sym_arcs,
+    arc_styles,
+    sym_conics,
+    conic_styles,
+    sym_functions,
+    sym_curves,
+    sym_loci,
+    plot_styles,
     selected,
+    hidden,
   ): Self::SystemData) {
+
+    // Whether the entity is a geometry that can be selected
+    let is_selectable = |ent: Entity| -> bool {
+      entities.is_alive(ent) && hidden.get(ent).is_none() && (
+        sym_points.get(ent).is_some() ||
+        sym_lines.get(ent).is_some() ||
+        sym_arcs.get(ent).is_some() ||
+        sym_conics.get(ent).is_some() ||
+        sym_functions.get(ent).is_some() ||
+        sym_curves.get(ent).is_some() ||
+        sym_loci.get(ent).is_some()
+      )
+    };
+
     if let Some(reader_id) = &mut self.geometry_action_reader {
       for event in geometry_action_channel.read(reader_id) {
         match event {
           GeometryAction::SelectAll => {
-            for (entity, _, _, _) in (&entities, &sym_points, &point_styles, !&selected).join() {
+            for (entity, _, _, _, _) in (&entities, &sym_points, &point_styles, !&selected, !&hidden).join() {
+              sketch_event_channel.single_write(SketchEvent::Select(entity));
+            }
+            for (entity, _, _, _, _) in (&entities, &sym_lines, &line_styles, !&selected, !&hidden).join() {
+              sketch_event_channel.single_write(SketchEvent::Select(entity));
+            }
+            for (entity, _, _, _, _) in (&entities, &sym_arcs, &arc_styles, !&selected, !&hidden).join() {
+              sketch_event_channel.single_write(SketchEvent::Select(entity));
+            }
+            for (entity, _, _, _, _) in (&entities, &sym_conics, &conic_styles, !&selected, !&hidden).join() {
+              sketch_event_channel.single_write(SketchEvent::Select(entity));
+            }
+            for (entity, _, _, _, _) in (&entities, &sym_functions, &plot_styles, !&selected, !&hidden).join() {
+              sketch_event_channel.single_write(SketchEvent::Select(entity));
+            }
+            for (entity, _, _, _, _) in (&entities, &sym_curves, &plot_styles, !&selected, !&hidden).join() {
+              sketch_event_channel.single_write(SketchEvent::Select(entity));
+            }
+            for (entity, _, _, _, _) in (&entities, &sym_loci, &plot_styles, !&selected, !&hidden).join() {
+              sketch_event_channel.single_write(SketchEvent::Select(entity));
+            }
+          },
+          // The selection is replaced by every point, or every line
+          GeometryAction::SelectAllPoints => {
+            for (entity, _, _) in (&entities, &selected, !&sym_points).join() {
+              sketch_event_channel.single_write(SketchEvent::Deselect(entity));
+            }
+            for (entity, _, _, _, _) in (&entities, &sym_points, &point_styles, !&selected, !&hidden).join() {
               sketch_event_channel.single_write(SketchEvent::Select(entity));
             }
-            for (entity, _, _, _) in (&entities, &sym_lines, &line_styles, !&selected).join() {
+          },
+          GeometryAction::SelectAllLines => {
+            for (entity, _, _) in (&entities, &selected, !&sym_lines).join() {
+              sketch_event_channel.single_write(SketchEvent::Deselect(entity));
+            }
+            for (entity, _, _, _, _) in (&entities, &sym_lines, &line_styles, !&selected, !&hidden).join() {
               sketch_event_channel.single_write(SketchEvent::Select(entity));
             }
           },
+          GeometryAction::SelectParents => {
+
+            // Walk up the dependency graph to everything the selection is
+            // defined on
+            let mut visited = HashSet::new();
+            let mut stack : Vec<Entity> = (&entities, &selected).join().map(|(ent, _)| ent).collect();
+            while let Some(ent) = stack.pop() {
+              if visited.insert(ent) {
+                stack.extend(dependency_graph.get_direct_parents(&ent).into_iter().flatten());
+              }
+            }
+            for ent in visited {
+              if selected.get(ent).is_none() && is_selectable(ent) {
+                sketch_event_channel.single_write(SketchEvent::Select(ent));
+              }
+            }
+          },
+          GeometryAction::SelectChildren => {
+
+            // Walk down the dependency graph to everything defined on the
+            // selection
+            let mut dependents = HashSet::new();
+            for (ent, _) in (&entities, &selected).join() {
+              dependents.extend(dependency_graph.get_all_dependents(&ent));
+            }
+            for ent in dependents {
+              if selected.get(ent).is_none() && is_selectable(ent) {
+                sketch_event_channel.single_write(SketchEvent::Select(ent));
+              }
+            }
+          },
           GeometryAction::DeselectAll => {
             for (entity, _, _, _) in (&entities, &sym_points, &point_styles, &selected).join() {
               sketch_event_channel.single_write(SketchEvent::Deselect(entity));
@@ -64,6 +163,21 @@ impl<'a> System<'a> for SeldeAllHandler {
             for (entity, _, _, _) in (&entities, &sym_lines, &line_styles, &selected).join() {
               sketch_event_channel.single_write(SketchEvent::Deselect(entity));
             }
+            for (entity, _, _, _) in (&entities, &sym_arcs, &arc_styles, &selected).join() {
+              sketch_event_channel.single_write(SketchEvent::Deselect(entity));
+            }
+            for (entity, _, _, _) in (&entities, &sym_conics, &conic_styles, &selected).join() {
+              sketch_event_channel.single_write(SketchEvent::Deselect(entity));
+            }
+            for (entity, _, _, _) in (&entities, &sym_functions, &plot_styles, &selected).join() {
+              sketch_event_channel.single_write(SketchEvent::Deselect(entity));
+            }
+            for (entity, _, _, _) in (&entities, &sym_curves, &plot_styles, &selected).join() {
+              sketch_event_channel.single_write(SketchEvent::Deselect(entity));
+            }
+            for (entity, _, _, _) in (&entities, &sym_loci, &plot_styles, &selected).join() {
+              sketch_event_channel.single_write(SketchEvent::Deselect(entity));
+            }
           },
           GeometryAction::DeselectAllExcept(except_this) => {
             for (entity, _, _, _) in (&entities, &sym_points, &point_styles, &selected).join() {
@@ -76,6 +190,31 @@ impl<'a> System<'a> for SeldeAllHandler {
                 sketch_event_channel.single_write(SketchEvent::Deselect(entity));
               }
             }
+            for (entity, _, _, _) in (&entities, &sym_arcs, &arc_styles, &selected).join() {
+              if entity != *except_this {
+                sketch_event_channel.single_write(SketchEvent::Deselect(entity));
+              }
+            }
+            for (entity, _, _, _) in (&entities, &sym_conics, &conic_styles, &selected).join() {
+              if entity != *except_this {
+                sketch_event_channel.single_write(SketchEvent::Deselect(entity));
+              }
+            }
+            for (entity, _, _, _) in (&entities, &sym_functions, &plot_styles, &selected).join() {
+              if entity != *except_this {
+                sketch_event_channel.single_write(SketchEvent::Deselect(entity));
+              }
+            }
+            for (entity, _, _, _) in (&entities, &sym_curves, &plot_styles, &selected).join() {
+              if entity != *except_this {
+                sketch_event_channel.single_write(SketchEvent::Deselect(entity));
+              }
+            }
+            for (entity, _, _, _) in (&entities, &sym_loci, &plot_styles, &selected).join() {
+              if entity != *except_this {
+                sketch_event_channel.single_write(SketchEvent::Deselect(entity));
+              }
+            }
           },
           _ => (),
         }
@@ -0,0 +1,94 @@
+use specs::prelude::*;
+use crate::{
+  utilities::Color,
+  resources::events::{GeometryAction, GeometryActionReader, GeometryActionChannel, SketchEvent, SketchEventChannel, Geometry},
+  components::{SymbolicPoint, PointStyle, SymbolicLine, LineStyle, SymbolicArc, Arc, Selected},
+};
+
+#[derive(Default)]
+pub struct TangentHandler {
+  geometry_action_reader: Option<GeometryActionReader>,
+}
+
+/// # Tangent Handler
+///
+/// Constructs the tangent at a new point on an arc, where the arc was
+/// clicked, or both tangents from the selected point to the circle of the
+/// selected arc. The new lines are selected alone.
+impl<'a> System<'a> for TangentHandler {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, GeometryActionChannel>,
+    Write<'a, SketchEventChannel>,
+    ReadStorage<'a, Arc>,
+    ReadStorage<'a, SymbolicArc>,
+    WriteStorage<'a, SymbolicPoint>,
+    WriteStorage<'a, PointStyle>,
+    WriteStorage<'a, SymbolicLine>,
+    WriteStorage<'a, LineStyle>,
+    WriteStorage<'a, Selected>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.geometry_action_reader = Some(world.fetch_mut::<GeometryActionChannel>().register_reader());
+  }
+
+  fn run(&mut self, (
+    entities,
+    geometry_action_channel,
+    mut sketch_events,
+    arcs,
+    sym_arcs,
+    mut sym_points,
+    mut point_styles,
+    mut sym_lines,
+    mut line_styles,
+    mut selected,
+  ): Self::SystemData) {
+    if let Some(reader_id) = &mut self.geometry_action_reader {
+      for event in geometry_action_channel.read(reader_id) {
+        let tangents = match event {
+          GeometryAction::ConstructTangentAt(arc_ent, position) => {
+            let arc = match arcs.get(*arc_ent) {
+              Some(arc) => *arc,
+              None => continue,
+            };
+
+            // The point of tangency, on the arc where it was clicked
+            let sym_point = SymbolicPoint::OnArc(*arc_ent, arc.param_of(*position));
+            let point_style = PointStyle { color: Color::red(), radius: 5. };
+            let point_ent = entities.create();
+            if let Err(err) = sym_points.insert(point_ent, sym_point) { panic!("[tangent_handler] {:?}", err) }
+            if let Err(err) = point_styles.insert(point_ent, point_style) { panic!("[tangent_handler] {:?}", err) }
+            sketch_events.single_write(SketchEvent::Insert(point_ent, Geometry::Point(sym_point, point_style)));
+            vec![SymbolicLine::TangentAt(point_ent)]
+          },
+          GeometryAction::ConstructTangentsFromSelected => {
+            let sel_points : Vec<Entity> = (&entities, &sym_points, &selected).join().map(|(ent, _, _)| ent).collect();
+            let sel_arcs : Vec<Entity> = (&entities, &sym_arcs, &selected).join().map(|(ent, _, _)| ent).collect();
+            match (&sel_points[..], &sel_arcs[..]) {
+              ([point_ent], [arc_ent]) => vec![SymbolicLine::Tangent(*point_ent, *arc_ent, true), SymbolicLine::Tangent(*point_ent, *arc_ent, false)],
+              _ => continue,
+            }
+          },
+          _ => continue,
+        };
+
+        for (ent, _) in (&entities, &selected).join() {
+          sketch_events.single_write(SketchEvent::Deselect(ent));
+        }
+        for sym_line in tangents {
+          let line_style = LineStyle { color: Color::blue(), width: 2. };
+          let tangent_ent = entities.create();
+          if let Err(err) = sym_lines.insert(tangent_ent, sym_line) { panic!("[tangent_handler] {:?}", err) }
+          if let Err(err) = line_styles.insert(tangent_ent, line_style) { panic!("[tangent_handler] {:?}", err) }
+          if let Err(err) = selected.insert(tangent_ent, Selected) { panic!("[tangent_handler] {:?}", err) }
+          sketch_events.single_write(SketchEvent::Insert(tangent_ent, Geometry::Line(sym_line, line_style)));
+        }
+      }
+    } else {
+      panic!("[tangent_handler] No reader id");
+    }
+  }
+}
\ No newline at end of file
@@ -0,0 +1,67 @@
+use specs::prelude::*;
+use crate::{
+  resources::{
+    TraceLayer,
+    events::{GeometryAction, GeometryActionReader, GeometryActionChannel},
+  },
+  components::{SymbolicPoint, SymbolicLine, Selected, Traced},
+};
+
+#[derive(Default)]
+pub struct TraceHandler {
+  geometry_action_reader: Option<GeometryActionReader>,
+}
+
+/// # Trace Handler
+///
+/// Toggles tracing of the selected points and lines, and erases the traces.
+/// Anything else in the selection is left alone.
+impl<'a> System<'a> for TraceHandler {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, GeometryActionChannel>,
+    Write<'a, TraceLayer>,
+    ReadStorage<'a, SymbolicPoint>,
+    ReadStorage<'a, SymbolicLine>,
+    ReadStorage<'a, Selected>,
+    WriteStorage<'a, Traced>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.geometry_action_reader = Some(world.fetch_mut::<GeometryActionChannel>().register_reader());
+  }
+
+  fn run(&mut self, (
+    entities,
+    geometry_action_channel,
+    mut trace_layer,
+    sym_points,
+    sym_lines,
+    selected,
+    mut traced,
+  ): Self::SystemData) {
+    if let Some(reader_id) = &mut self.geometry_action_reader {
+      for event in geometry_action_channel.read(reader_id) {
+        match event {
+          GeometryAction::ToggleTraceSelected => {
+            let to_toggle : Vec<_> = (&entities, &selected).join()
+              .filter(|(ent, _)| sym_points.get(*ent).is_some() || sym_lines.get(*ent).is_some())
+              .map(|(ent, _)| ent)
+              .collect();
+            for ent in to_toggle {
+              if traced.get(ent).is_some() {
+                traced.remove(ent);
+                trace_layer.break_point(ent);
+              } else if let Err(err) = traced.insert(ent, Traced) {
+                panic!("[trace_handler] {:?}", err);
+              }
+            }
+          },
+          GeometryAction::EraseTraces => trace_layer.clear(),
+          _ => (),
+        }
+      }
+    }
+  }
+}
\ No newline at end of file
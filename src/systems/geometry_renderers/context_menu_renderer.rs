@@ -0,0 +1,79 @@
+use specs::prelude::*;
+use crate::{
+  utilities::Color,
+  resources::{InputState, ContextMenu, MenuEntry},
+  components::{Rectangle, RectangleStyle, LineStyle},
+};
+
+static ROW_BORDER : LineStyle = LineStyle {
+  color: Color { r: 0.6, g: 0.6, b: 0.6, a: 1.0 },
+  width: 0.5,
+};
+
+static HOVERED_ROW_BORDER : LineStyle = LineStyle {
+  color: Color { r: 0.2, g: 0.2, b: 0.2, a: 1.0 },
+  width: 1.5,
+};
+
+/// The rows have no text, so every entry has a tint of its own
+fn row_fill(entry: MenuEntry) -> Color {
+  match entry {
+    MenuEntry::Delete => Color::new(0.95, 0.75, 0.75, 1.0),
+    MenuEntry::Hide => Color::new(0.85, 0.85, 0.85, 1.0),
+    MenuEntry::Style => Color::new(0.75, 0.8, 0.95, 1.0),
+    MenuEntry::Fill => Color::new(0.95, 0.9, 0.7, 1.0),
+    MenuEntry::ConstructPerpendicular => Color::new(0.75, 0.92, 0.75, 1.0),
+    MenuEntry::ConstructTangent => Color::new(0.7, 0.9, 0.9, 1.0),
+    MenuEntry::Properties => Color::new(0.88, 0.78, 0.95, 1.0),
+  }
+}
+
+/// # Context Menu Renderer
+///
+/// Draws a row for every entry of the context menu while it is shown, each
+/// kind of entry in its own tint. The row under the mouse is outlined, and
+/// its label is in the window title.
+#[derive(Default)]
+pub struct ContextMenuRenderer {
+  row_entities: Vec<Entity>,
+}
+
+impl<'a> System<'a> for ContextMenuRenderer {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, InputState>,
+    Read<'a, ContextMenu>,
+    WriteStorage<'a, Rectangle>,
+    WriteStorage<'a, RectangleStyle>,
+  );
+
+  fn run(&mut self, (
+    entities,
+    input_state,
+    context_menu,
+    mut rects,
+    mut rect_styles,
+  ): Self::SystemData) {
+    let row_entries = context_menu.get().map_or(vec![], |menu| menu.entries.clone());
+    let row_count = row_entries.len();
+
+    // Make sure we have as many entities as rows
+    while self.row_entities.len() < row_count {
+      self.row_entities.push(entities.create());
+    }
+
+    for (index, ent) in self.row_entities.iter().enumerate() {
+      match (context_menu.row_aabb(index), row_entries.get(index)) {
+        (Some(aabb), Some(entry)) => {
+          let border = if aabb.contains(input_state.mouse_abs_pos) { HOVERED_ROW_BORDER } else { ROW_BORDER };
+          let style = RectangleStyle { border, fill: row_fill(*entry) };
+          if let Err(err) = rects.insert(*ent, aabb) { panic!("[context_menu_renderer] {:?}", err) }
+          if let Err(err) = rect_styles.insert(*ent, style) { panic!("[context_menu_renderer] {:?}", err) }
+        },
+        _ => {
+          rects.remove(*ent);
+        },
+      }
+    }
+  }
+}
\ No newline at end of file
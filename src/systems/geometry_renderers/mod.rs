@@ -5,4 +5,13 @@ mod snap_point_renderer;
 pub use snap_point_renderer::*;
 
 mod select_rectangle_renderer;
-pub use select_rectangle_renderer::*;
\ No newline at end of file
+pub use select_rectangle_renderer::*;
+
+mod selection_handles_renderer;
+pub use selection_handles_renderer::*;
+
+mod protocol_panel_renderer;
+pub use protocol_panel_renderer::*;
+
+mod context_menu_renderer;
+pub use context_menu_renderer::*;
\ No newline at end of file
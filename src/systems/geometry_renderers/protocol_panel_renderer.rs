@@ -0,0 +1,92 @@
+use specs::prelude::*;
+use crate::{
+  utilities::Color,
+  resources::{Viewport, ConstructionProtocol},
+  components::{Rectangle, RectangleStyle, LineStyle, Selected},
+};
+
+static ROW_STYLE : RectangleStyle = RectangleStyle {
+  border: LineStyle {
+    color: Color { r: 0.6, g: 0.6, b: 0.6, a: 1.0 },
+    width: 0.5,
+  },
+  fill: Color { r: 0.9, g: 0.9, b: 0.9, a: 1.0 },
+};
+
+static CURRENT_ROW_STYLE : RectangleStyle = RectangleStyle {
+  border: LineStyle {
+    color: Color { r: 0.3, g: 0.3, b: 0.3, a: 1.0 },
+    width: 0.5,
+  },
+  fill: Color { r: 0.6, g: 0.6, b: 0.6, a: 1.0 },
+};
+
+static HIDDEN_ROW_STYLE : RectangleStyle = RectangleStyle {
+  border: LineStyle {
+    color: Color { r: 0.6, g: 0.6, b: 0.6, a: 0.5 },
+    width: 0.5,
+  },
+  fill: Color { r: 0.9, g: 0.9, b: 0.9, a: 0.3 },
+};
+
+static SELECTED_BORDER : LineStyle = LineStyle {
+  color: Color { r: 1.0, g: 0.0, b: 1.0, a: 1.0 },
+  width: 1.,
+};
+
+/// # Protocol Panel Renderer
+///
+/// Draws a row for every construction step while the panel is visible. The
+/// current step is darker, the steps after it are faded out, and the rows of
+/// selected geometries have a magenta border.
+#[derive(Default)]
+pub struct ProtocolPanelRenderer {
+  row_entities: Vec<Entity>,
+}
+
+impl<'a> System<'a> for ProtocolPanelRenderer {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, Viewport>,
+    Read<'a, ConstructionProtocol>,
+    ReadStorage<'a, Selected>,
+    WriteStorage<'a, Rectangle>,
+    WriteStorage<'a, RectangleStyle>,
+  );
+
+  fn run(&mut self, (
+    entities,
+    viewport,
+    protocol,
+    selected,
+    mut rects,
+    mut rect_styles,
+  ): Self::SystemData) {
+    let row_count = if protocol.panel_visible { protocol.steps().len() } else { 0 };
+
+    // Make sure we have as many entities as rows
+    while self.row_entities.len() < row_count {
+      self.row_entities.push(entities.create());
+    }
+
+    for (index, ent) in self.row_entities.iter().enumerate() {
+      if index < row_count {
+        let step = &protocol.steps()[index];
+        let mut style = if !protocol.is_shown(index) {
+          HIDDEN_ROW_STYLE
+        } else if protocol.is_stepping() && protocol.current() == Some(index) {
+          CURRENT_ROW_STYLE
+        } else {
+          ROW_STYLE
+        };
+        if selected.get(step.entity).is_some() {
+          style.border = SELECTED_BORDER;
+        }
+        if let Err(err) = rects.insert(*ent, protocol.row_aabb(index, &viewport)) { panic!("[protocol_panel_renderer] {:?}", err) }
+        if let Err(err) = rect_styles.insert(*ent, style) { panic!("[protocol_panel_renderer] {:?}", err) }
+      } else {
+        rects.remove(*ent);
+      }
+    }
+  }
+}
\ No newline at end of file
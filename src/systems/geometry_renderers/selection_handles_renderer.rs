@@ -0,0 +1,93 @@
+use specs::prelude::*;
+use crate::{
+  utilities::{Color, AABB},
+  resources::{
+    Viewport, ViewportTransform,
+    geometry::SelectionHandles,
+  },
+  components::{Rectangle, RectangleStyle, LineStyle, Point, PointStyle},
+};
+
+static BOX_STYLE : RectangleStyle = RectangleStyle {
+  border: LineStyle {
+    color: Color { r: 1.0, g: 0.0, b: 1.0, a: 0.5 },
+    width: 1.,
+  },
+  fill: Color { r: 0.0, g: 0.0, b: 0.0, a: 0.0 },
+};
+
+static HANDLE_STYLE : RectangleStyle = RectangleStyle {
+  border: LineStyle {
+    color: Color { r: 1.0, g: 0.0, b: 1.0, a: 1.0 },
+    width: 1.,
+  },
+  fill: Color { r: 1.0, g: 1.0, b: 1.0, a: 1.0 },
+};
+
+static ROTATE_HANDLE_STYLE : PointStyle = PointStyle {
+  color: Color { r: 1.0, g: 1.0, b: 1.0, a: 1.0 },
+  radius: 5.,
+};
+
+#[derive(Default)]
+pub struct SelectionHandlesRenderer {
+  box_entity: Option<Entity>,
+  scale_handle_entities: Vec<Entity>,
+  rotate_handle_entity: Option<Entity>,
+}
+
+impl<'a> System<'a> for SelectionHandlesRenderer {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, Viewport>,
+    Read<'a, SelectionHandles>,
+    WriteStorage<'a, Rectangle>,
+    WriteStorage<'a, RectangleStyle>,
+    WriteStorage<'a, Point>,
+    WriteStorage<'a, PointStyle>,
+  );
+
+  fn run(&mut self, (
+    entities,
+    viewport,
+    selection_handles,
+    mut rects,
+    mut rect_styles,
+    mut points,
+    mut point_styles,
+  ): Self::SystemData) {
+
+    // Make sure we have all the entities
+    if self.box_entity.is_none() {
+      let ent = entities.create();
+      if let Err(err) = rect_styles.insert(ent, BOX_STYLE) { panic!("[selection_handles_renderer] {:?}", err) }
+      self.box_entity = Some(ent);
+      for _ in 0..4 {
+        let ent = entities.create();
+        if let Err(err) = rect_styles.insert(ent, HANDLE_STYLE) { panic!("[selection_handles_renderer] {:?}", err) }
+        self.scale_handle_entities.push(ent);
+      }
+      let ent = entities.create();
+      if let Err(err) = point_styles.insert(ent, ROTATE_HANDLE_STYLE) { panic!("[selection_handles_renderer] {:?}", err) }
+      self.rotate_handle_entity = Some(ent);
+    }
+    let box_ent = self.box_entity.unwrap();
+    let rotate_ent = self.rotate_handle_entity.unwrap();
+
+    if let Some(selection_box) = selection_handles.get() {
+      if let Err(err) = rects.insert(box_ent, selection_box.aabb) { panic!("[selection_handles_renderer] {:?}", err) }
+      let half = selection_box.handle_size() / 2.0;
+      for (ent, handle) in self.scale_handle_entities.iter().zip(selection_box.scale_handles().iter()) {
+        let rect = AABB::new(handle.x - half, handle.y - half, half * 2.0, half * 2.0);
+        if let Err(err) = rects.insert(*ent, rect) { panic!("[selection_handles_renderer] {:?}", err) }
+      }
+      if let Err(err) = points.insert(rotate_ent, selection_box.rotate_handle().to_virtual(&viewport)) { panic!("[selection_handles_renderer] {:?}", err) }
+    } else {
+      rects.remove(box_ent);
+      for ent in &self.scale_handle_entities {
+        rects.remove(*ent);
+      }
+      points.remove(rotate_ent);
+    }
+  }
+}
\ No newline at end of file
@@ -0,0 +1,188 @@
+use specs::prelude::*;
+use crate::{
+  resources::{
+    DeltaTime,
+    Viewport,
+    events::{
+      AnimationEvent, AnimationEventChannel, AnimationEventReader,
+      SketchEvent, SketchEventChannel, MovePoint,
+    },
+  },
+  components::{SymbolicPoint, Point, Line, Arc, Conic, SymbolicCurve, Animated, AnimationMode, Selected},
+};
+use super::locus_system::{LiveSolved, parameter_range};
+
+static MIN_SPEED : f64 = 1.0 / 256.0;
+static MAX_SPEED : f64 = 4.0;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Playback {
+  Playing,
+  Paused,
+  Stopped,
+}
+
+/// Move the point along what it is constrained to, the same way dragging does
+fn move_along(ent: Entity, sym_point: &SymbolicPoint, new_t: f64) -> Option<SketchEvent> {
+  let move_point = match *sym_point {
+    SymbolicPoint::OnLine(line_ent, old_t) => MovePoint::OnLine(line_ent, old_t, new_t),
+    SymbolicPoint::OnArc(arc_ent, old_t) => MovePoint::OnArc(arc_ent, old_t, new_t),
+    SymbolicPoint::OnConic(conic_ent, old_t) => MovePoint::OnConic(conic_ent, old_t, new_t),
+    SymbolicPoint::OnFunction(function_ent, old_x) => MovePoint::OnFunction(function_ent, old_x, new_t),
+    SymbolicPoint::OnCurve(curve_ent, old_t) => MovePoint::OnCurve(curve_ent, old_t, new_t),
+    SymbolicPoint::Free(_) | SymbolicPoint::LineLineIntersect(_, _) | SymbolicPoint::FunctionLineIntersect(_, _, _) => return None,
+  };
+  Some(SketchEvent::MovePoint(ent, move_point))
+}
+
+/// Advance `t` within `[t_0, t_1]`, wrapping around or turning around at
+/// the ends
+fn advance(animated: &mut Animated, t: f64, (t_0, t_1): (f64, f64), dt: f64) -> f64 {
+  let span = t_1 - t_0;
+  if span <= 0.0 {
+    return t;
+  }
+  let t = t + animated.direction * animated.speed * span * dt;
+  match animated.mode {
+    AnimationMode::Loop => t_0 + ((t - t_0) % span + span) % span,
+    AnimationMode::Bounce => if t > t_1 {
+      animated.direction = -1.0;
+      (2.0 * t_1 - t).max(t_0)
+    } else if t < t_0 {
+      animated.direction = 1.0;
+      (2.0 * t_0 - t).min(t_1)
+    } else {
+      t
+    },
+  }
+}
+
+/// # Animation System
+///
+/// Slides the animated points along whatever they are constrained to. Every
+/// step is emitted as a `MovePoint`, so that everything else updates just
+/// like when the point is dragged. Unbounded ranges, e.g. lines, are limited
+/// to around the viewport.
+pub struct AnimationSystem {
+  playback: Playback,
+  animation_events_reader_id: Option<AnimationEventReader>,
+}
+
+impl Default for AnimationSystem {
+  fn default() -> Self {
+    Self {
+      playback: Playback::Stopped,
+      animation_events_reader_id: None,
+    }
+  }
+}
+
+impl<'a> System<'a> for AnimationSystem {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, DeltaTime>,
+    Read<'a, Viewport>,
+    Read<'a, AnimationEventChannel>,
+    Write<'a, SketchEventChannel>,
+    ReadStorage<'a, SymbolicPoint>,
+    ReadStorage<'a, SymbolicCurve>,
+    ReadStorage<'a, Point>,
+    ReadStorage<'a, Line>,
+    ReadStorage<'a, Arc>,
+    ReadStorage<'a, Conic>,
+    ReadStorage<'a, Selected>,
+    WriteStorage<'a, Animated>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.animation_events_reader_id = Some(world.fetch_mut::<AnimationEventChannel>().register_reader());
+  }
+
+  fn run(&mut self, (
+    entities,
+    delta_time,
+    vp,
+    animation_events,
+    mut sketch_events,
+    sym_points,
+    sym_curves,
+    points,
+    lines,
+    arcs,
+    conics,
+    selected,
+    mut animated,
+  ): Self::SystemData) {
+    if let Some(reader_id) = &mut self.animation_events_reader_id {
+      for event in animation_events.read(reader_id) {
+        match event {
+          AnimationEvent::TogglePlay => {
+            if self.playback == Playback::Stopped {
+
+              // Remember where everything starts
+              for (sym_point, anim) in (&sym_points, &mut animated).join() {
+                if let Some(t) = sym_point.parameter() {
+                  anim.start = t;
+                }
+              }
+            }
+            self.playback = if self.playback == Playback::Playing { Playback::Paused } else { Playback::Playing };
+          },
+          AnimationEvent::Stop => {
+            self.playback = Playback::Stopped;
+            for (ent, sym_point, anim) in (&entities, &sym_points, &animated).join() {
+              if let Some(event) = move_along(ent, sym_point, anim.start) {
+                sketch_events.single_write(event);
+              }
+            }
+          },
+          AnimationEvent::ToggleSelected => {
+            let to_toggle : Vec<_> = (&entities, &sym_points, &selected).join()
+              .filter_map(|(ent, sym_point, _)| sym_point.parameter().map(|t| (ent, t)))
+              .collect();
+            for (ent, t) in to_toggle {
+              if animated.get(ent).is_some() {
+                animated.remove(ent);
+              } else if let Err(err) = animated.insert(ent, Animated::new(t)) {
+                panic!("[animation_system] {:?}", err);
+              }
+            }
+
+            // Animating something starts the playback
+            if self.playback == Playback::Stopped && animated.join().next().is_some() {
+              self.playback = Playback::Playing;
+            }
+          },
+          AnimationEvent::Faster | AnimationEvent::Slower | AnimationEvent::Reverse | AnimationEvent::ToggleBounce => {
+            for (anim, _) in (&mut animated, &selected).join() {
+              match event {
+                AnimationEvent::Faster => anim.speed = (anim.speed * 2.0).min(MAX_SPEED),
+                AnimationEvent::Slower => anim.speed = (anim.speed / 2.0).max(MIN_SPEED),
+                AnimationEvent::Reverse => anim.direction = -anim.direction,
+                _ => anim.mode = if anim.mode == AnimationMode::Loop { AnimationMode::Bounce } else { AnimationMode::Loop },
+              }
+            }
+          },
+        }
+      }
+    } else {
+      panic!("[animation_system] No animation events reader id");
+    }
+
+    if self.playback == Playback::Playing {
+      let live = LiveSolved { points: &points, lines: &lines, arcs: &arcs, conics: &conics };
+      let radius = vp.virtual_width().hypot(vp.virtual_height()) / 2.0;
+      for (ent, sym_point, anim) in (&entities, &sym_points, &mut animated).join() {
+        if let Some(t) = sym_point.parameter() {
+          if let Some(range) = parameter_range(sym_point, &sym_curves, &live, &vp, radius) {
+            let new_t = advance(anim, t, range, delta_time.get());
+            if let Some(event) = move_along(ent, sym_point, new_t) {
+              sketch_events.single_write(event);
+            }
+          }
+        }
+      }
+    }
+  }
+}
\ No newline at end of file
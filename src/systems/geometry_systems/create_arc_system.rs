@@ -0,0 +1,121 @@
+use specs::prelude::*;
+use shrev::{EventChannel, ReaderId};
+use crate::{
+  utilities::Color,
+  resources::{
+    ToolState, Tool,
+    geometry::{LastActivePoint, CreateArcData},
+    events::{SketchEvent, Geometry, SketchEventChannel},
+  },
+  components::{SymbolicArc, ArcStyle, ArcFill, Selected},
+};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum ArcTool {
+  ThreePoints,
+  CenterTwoPoints,
+  Circle,
+}
+
+impl ArcTool {
+  fn from_tool(tool: Tool) -> Option<Self> {
+    match tool {
+      Tool::Arc => Some(ArcTool::ThreePoints),
+      Tool::CenterArc => Some(ArcTool::CenterTwoPoints),
+      Tool::Circle => Some(ArcTool::Circle),
+      _ => None,
+    }
+  }
+
+  fn num_points(&self) -> usize {
+    match self {
+      ArcTool::ThreePoints | ArcTool::CenterTwoPoints => 3,
+      ArcTool::Circle => 2,
+    }
+  }
+
+  /// A circle is an arc going all the way around, back to where it starts
+  fn symbolic(&self, pts: &[Entity]) -> SymbolicArc {
+    match self {
+      ArcTool::ThreePoints => SymbolicArc::ThreePoints(pts[0], pts[1], pts[2]),
+      ArcTool::CenterTwoPoints => SymbolicArc::CenterTwoPoints(pts[0], pts[1], pts[2]),
+      ArcTool::Circle => SymbolicArc::CenterTwoPoints(pts[0], pts[1], pts[1]),
+    }
+  }
+}
+
+#[derive(Default)]
+pub struct CreateArcSystem {
+  arc_tool: Option<ArcTool>,
+  last_active_point_event_reader_id: Option<ReaderId<LastActivePoint>>,
+}
+
+/// # Create Arc System
+///
+/// Creates an arc passing through three points. The first and the last point
+/// are the two ends of the arc, and the second one is a point on the arc in
+/// between them. With the center arc tool, the first point is the center
+/// instead, the second one is where the arc starts, and the arc goes
+/// counter-clockwise until it reaches the direction of the third one. The
+/// circle tool makes a full circle from its center and a point on it.
+impl<'a> System<'a> for CreateArcSystem {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, ToolState>,
+    Write<'a, CreateArcData>,
+    Write<'a, EventChannel<LastActivePoint>>,
+    Write<'a, SketchEventChannel>,
+    WriteStorage<'a, SymbolicArc>,
+    WriteStorage<'a, ArcStyle>,
+    WriteStorage<'a, Selected>,
+  );
+
+  fn run(&mut self, (
+    entities,
+    tool_state,
+    mut create_arc_data,
+    mut last_active_point_event,
+    mut sketch_events,
+    mut sym_arcs,
+    mut styles,
+    mut selected,
+  ): Self::SystemData) {
+
+    // First deal with tooling states. Switching between two arc tools
+    // also starts over since the points mean different things
+    let curr_arc_tool = ArcTool::from_tool(tool_state.get());
+    if curr_arc_tool != self.arc_tool {
+      self.arc_tool = curr_arc_tool;
+      create_arc_data.points.clear();
+      self.last_active_point_event_reader_id = curr_arc_tool.map(|_| last_active_point_event.register_reader());
+    }
+
+    // Same as line creation, we only listen to the last active points when using an arc tool
+    if let (Some(arc_tool), Some(reader_id)) = (self.arc_tool, &mut self.last_active_point_event_reader_id) {
+      for event in last_active_point_event.read(reader_id) {
+        let curr_point_entity = event.get();
+
+        // All the points need to be different
+        if !create_arc_data.points.contains(&curr_point_entity) {
+          create_arc_data.points.push(curr_point_entity);
+          if create_arc_data.points.len() == arc_tool.num_points() {
+            let sym_arc = arc_tool.symbolic(&create_arc_data.points);
+            let arc_style = ArcStyle { color: Color::blue(), width: 2., fill: ArcFill::None };
+
+            // Create the arc
+            let entity = entities.create();
+            if let Err(err) = sym_arcs.insert(entity, sym_arc) { panic!("[create_arc_system] {:?}", err) }
+            if let Err(err) = styles.insert(entity, arc_style) { panic!("[create_arc_system] {:?}", err) }
+            if let Err(err) = selected.insert(entity, Selected) { panic!("[create_arc_system] {:?}", err) }
+
+            // Push event to created arcs
+            sketch_events.single_write(SketchEvent::Insert(entity, Geometry::Arc(sym_arc, arc_style)));
+
+            // Start over
+            create_arc_data.points.clear();
+          }
+        }
+      }
+    }
+  }
+}
\ No newline at end of file
@@ -0,0 +1,172 @@
+use specs::prelude::*;
+use shrev::{EventChannel, ReaderId};
+use crate::{
+  utilities::Color,
+  resources::{
+    ToolState, Tool,
+    Viewport,
+    SpatialHashTable,
+    geometry::{LastActivePoint, CreateConicData},
+    events::{SketchEvent, Geometry, SketchEventChannel, MouseEvent, MouseEventChannel, MouseEventReader},
+  },
+  components::{SymbolicConic, ConicStyle, Selected, Point, Line, Arc, Conic, Plot},
+  systems::interactions::helpers::hitting_object,
+};
+
+static SELECT_DIST_THRES : f64 = 5.0; // Pixel
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum ConicTool {
+  Ellipse,
+  Hyperbola,
+  FivePoints,
+  Parabola,
+}
+
+impl ConicTool {
+  fn from_tool(tool: Tool) -> Option<Self> {
+    match tool {
+      Tool::Ellipse => Some(ConicTool::Ellipse),
+      Tool::Hyperbola => Some(ConicTool::Hyperbola),
+      Tool::Conic => Some(ConicTool::FivePoints),
+      Tool::Parabola => Some(ConicTool::Parabola),
+      _ => None,
+    }
+  }
+
+  fn num_picks(&self) -> usize {
+    match self {
+      ConicTool::Ellipse | ConicTool::Hyperbola => 3,
+      ConicTool::FivePoints => 5,
+      ConicTool::Parabola => 2,
+    }
+  }
+
+  /// Whether the pick after the given number of picks is a line
+  fn picks_line(&self, num_picks: usize) -> bool {
+    *self == ConicTool::Parabola && num_picks == 1
+  }
+
+  fn symbolic(&self, picks: &[Entity]) -> SymbolicConic {
+    match self {
+      ConicTool::Ellipse => SymbolicConic::Ellipse(picks[0], picks[1], picks[2]),
+      ConicTool::Hyperbola => SymbolicConic::Hyperbola(picks[0], picks[1], picks[2]),
+      ConicTool::FivePoints => SymbolicConic::FivePoints(picks[0], picks[1], picks[2], picks[3], picks[4]),
+      ConicTool::Parabola => SymbolicConic::Parabola(picks[0], picks[1]),
+    }
+  }
+}
+
+#[derive(Default)]
+pub struct CreateConicSystem {
+  conic_tool: Option<ConicTool>,
+  last_active_point_event_reader_id: Option<ReaderId<LastActivePoint>>,
+  mouse_event_reader: Option<MouseEventReader>,
+}
+
+/// # Create Conic System
+///
+/// Creates ellipses and hyperbolas from their two foci and a point on them,
+/// a general conic passing through five points, or a parabola from its
+/// focus and then its directrix. The directrix is picked by clicking a line,
+/// which creates no point.
+impl<'a> System<'a> for CreateConicSystem {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, ToolState>,
+    Read<'a, Viewport>,
+    Read<'a, SpatialHashTable<Entity>>,
+    Read<'a, MouseEventChannel>,
+    Write<'a, CreateConicData>,
+    Write<'a, EventChannel<LastActivePoint>>,
+    Write<'a, SketchEventChannel>,
+    WriteStorage<'a, SymbolicConic>,
+    WriteStorage<'a, ConicStyle>,
+    WriteStorage<'a, Selected>,
+    ReadStorage<'a, Point>,
+    ReadStorage<'a, Line>,
+    ReadStorage<'a, Arc>,
+    ReadStorage<'a, Conic>,
+    ReadStorage<'a, Plot>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.mouse_event_reader = Some(world.fetch_mut::<MouseEventChannel>().register_reader());
+  }
+
+  fn run(&mut self, (
+    entities,
+    tool_state,
+    viewport,
+    spatial_table,
+    mouse_event_channel,
+    mut create_conic_data,
+    mut last_active_point_event,
+    mut sketch_events,
+    mut sym_conics,
+    mut styles,
+    mut selected,
+    points,
+    lines,
+    arcs,
+    conics,
+    plots,
+  ): Self::SystemData) {
+
+    // First deal with tooling states. Switching between two conic tools also
+    // starts over since they demand different points
+    let curr_conic_tool = ConicTool::from_tool(tool_state.get());
+    if curr_conic_tool != self.conic_tool {
+      self.conic_tool = curr_conic_tool;
+      create_conic_data.picks.clear();
+      create_conic_data.picking_line = false;
+      self.last_active_point_event_reader_id = curr_conic_tool.map(|_| last_active_point_event.register_reader());
+    }
+
+    // The mouse is read before the points, so that the press placing a point
+    // is not also taken as picking a line
+    let mut picks = vec![];
+    if let Some(reader_id) = &mut self.mouse_event_reader {
+      for event in mouse_event_channel.read(reader_id) {
+        if let (MouseEvent::MouseDown(mouse_pos), true) = (event, create_conic_data.picking_line) {
+          let hit = hitting_object(*mouse_pos, &viewport, &spatial_table, &points, &lines, &arcs, &conics, &plots, SELECT_DIST_THRES);
+          picks.extend(hit.filter(|ent| lines.get(*ent).is_some()));
+        }
+      }
+    } else {
+      panic!("[create_conic_system] No mouse event reader id");
+    }
+
+    if let (Some(conic_tool), Some(reader_id)) = (self.conic_tool, &mut self.last_active_point_event_reader_id) {
+      let active_points : Vec<Entity> = last_active_point_event.read(reader_id).map(|event| event.get()).collect();
+      if !create_conic_data.picking_line {
+        picks.extend(active_points);
+      }
+
+      // We only deal with one pick, and all the picks need to be different
+      if let Some(curr_pick) = picks.first() {
+        if !create_conic_data.picks.contains(curr_pick) {
+          create_conic_data.picks.push(*curr_pick);
+          if create_conic_data.picks.len() == conic_tool.num_picks() {
+            let sym_conic = conic_tool.symbolic(&create_conic_data.picks);
+            let conic_style = ConicStyle { color: Color::blue(), width: 2. };
+
+            // Create the conic
+            let entity = entities.create();
+            if let Err(err) = sym_conics.insert(entity, sym_conic) { panic!("[create_conic_system] {:?}", err) }
+            if let Err(err) = styles.insert(entity, conic_style) { panic!("[create_conic_system] {:?}", err) }
+            if let Err(err) = selected.insert(entity, Selected) { panic!("[create_conic_system] {:?}", err) }
+
+            // Push event to created conics
+            sketch_events.single_write(SketchEvent::Insert(entity, Geometry::Conic(sym_conic, conic_style)));
+
+            // Start over
+            create_conic_data.picks.clear();
+          }
+          create_conic_data.picking_line = conic_tool.picks_line(create_conic_data.picks.len());
+        }
+      }
+    }
+  }
+}
\ No newline at end of file
@@ -0,0 +1,109 @@
+use std::f64::consts::PI;
+use specs::prelude::*;
+use crate::{
+  utilities::{Color, Expression},
+  resources::{
+    PromptState, PromptKind,
+    events::{PromptEvent, PromptEventChannel, PromptEventReader, SketchEvent, Geometry, SketchEventChannel},
+  },
+  components::{SymbolicCurve, PlotStyle, Selected},
+};
+
+/// Parse the optional range of t, which defaults to [0, 2π]
+fn parse_range(parts: &[&str]) -> Result<(f64, f64), String> {
+  match parts {
+    [] => Ok((0.0, 2.0 * PI)),
+    [min, max] => {
+      let t_min = Expression::parse_with_variables(min, &[]).map_err(|err| err.to_string())?.evaluate_at("t", 0.0);
+      let t_max = Expression::parse_with_variables(max, &[]).map_err(|err| err.to_string())?.evaluate_at("t", 0.0);
+      if t_min.is_finite() && t_max.is_finite() && t_min < t_max {
+        Ok((t_min, t_max))
+      } else {
+        Err("invalid range of t".to_string())
+      }
+    },
+    _ => Err("expected both t_min and t_max".to_string()),
+  }
+}
+
+/// `x(t), y(t)` or `x(t), y(t), t_min, t_max`
+fn parse_parametric(text: &str) -> Result<SymbolicCurve, String> {
+  let parts : Vec<&str> = text.split(',').collect();
+  if parts.len() < 2 {
+    return Err("expected x(t), y(t)".to_string());
+  }
+  let x = Expression::parse_with_variables(parts[0], &["t"]).map_err(|err| err.to_string())?;
+  let y = Expression::parse_with_variables(parts[1], &["t"]).map_err(|err| err.to_string())?;
+  let (t_min, t_max) = parse_range(&parts[2..])?;
+  Ok(SymbolicCurve::Parametric { x, y, t_min, t_max })
+}
+
+/// `r(t)` or `r(t), t_min, t_max`
+fn parse_polar(text: &str) -> Result<SymbolicCurve, String> {
+  let parts : Vec<&str> = text.split(',').collect();
+  let r = Expression::parse_with_variables(parts[0], &["t"]).map_err(|err| err.to_string())?;
+  let (t_min, t_max) = parse_range(&parts[1..])?;
+  Ok(SymbolicCurve::Polar { r, t_min, t_max })
+}
+
+#[derive(Default)]
+pub struct CreateCurveSystem {
+  prompt_event_reader: Option<PromptEventReader>,
+}
+
+/// # Create Curve System
+///
+/// Creates parametric and polar curves from the text typed into their
+/// prompts. Same as functions, the prompt stays open when there is an error.
+impl<'a> System<'a> for CreateCurveSystem {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, PromptEventChannel>,
+    Write<'a, PromptState>,
+    Write<'a, SketchEventChannel>,
+    WriteStorage<'a, SymbolicCurve>,
+    WriteStorage<'a, PlotStyle>,
+    WriteStorage<'a, Selected>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.prompt_event_reader = Some(world.fetch_mut::<PromptEventChannel>().register_reader());
+  }
+
+  fn run(&mut self, (
+    entities,
+    prompt_events,
+    mut prompt_state,
+    mut sketch_events,
+    mut sym_curves,
+    mut styles,
+    mut selected,
+  ): Self::SystemData) {
+    if let Some(reader_id) = &mut self.prompt_event_reader {
+      for PromptEvent(kind, text) in prompt_events.read(reader_id) {
+        let result = match kind {
+          PromptKind::Parametric => parse_parametric(text),
+          PromptKind::Polar => parse_polar(text),
+          _ => continue,
+        };
+        match result {
+          Ok(sym_curve) => {
+            let plot_style = PlotStyle { color: Color::blue(), width: 2. };
+
+            // Create the curve
+            let entity = entities.create();
+            if let Err(err) = sym_curves.insert(entity, sym_curve.clone()) { panic!("[create_curve_system] {:?}", err) }
+            if let Err(err) = styles.insert(entity, plot_style) { panic!("[create_curve_system] {:?}", err) }
+            if let Err(err) = selected.insert(entity, Selected) { panic!("[create_curve_system] {:?}", err) }
+
+            // Push event to created curves
+            sketch_events.single_write(SketchEvent::Insert(entity, Geometry::Curve(sym_curve, plot_style)));
+            prompt_state.close();
+          },
+          Err(err) => prompt_state.set_error(err),
+        }
+      }
+    }
+  }
+}
\ No newline at end of file
@@ -0,0 +1,82 @@
+use specs::prelude::*;
+use crate::{
+  utilities::Color,
+  resources::{
+    ConstructionProtocol, PromptState, PromptKind,
+    events::{PromptEvent, PromptEventChannel, PromptEventReader, SketchEvent, Geometry, SketchEventChannel},
+  },
+  components::{SymbolicPoint, SymbolicFunction, PlotStyle, Selected},
+};
+
+/// The referenced points are looked up by their name in the protocol
+fn parse_function(text: &str, protocol: &ConstructionProtocol, sym_points: &ReadStorage<SymbolicPoint>) -> Result<SymbolicFunction, String> {
+  let expression = SymbolicFunction::parse_expression(text).map_err(|err| err.to_string())?;
+  SymbolicFunction::with_names(&expression, |name| protocol.entity_named(name).filter(|ent| sym_points.get(*ent).is_some()))
+    .map_err(|name| format!("no point named {}", name))
+}
+
+#[derive(Default)]
+pub struct CreateFunctionSystem {
+  prompt_event_reader: Option<PromptEventReader>,
+}
+
+/// # Create Function System
+///
+/// Creates the graph of `y = f(x)` from the expression typed into the
+/// function prompt, where `A_x` and `A_y` are the coordinates of the point
+/// named `A`. The prompt stays open with an error message when the
+/// expression cannot be parsed or names no point.
+impl<'a> System<'a> for CreateFunctionSystem {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, PromptEventChannel>,
+    Read<'a, ConstructionProtocol>,
+    Write<'a, PromptState>,
+    Write<'a, SketchEventChannel>,
+    ReadStorage<'a, SymbolicPoint>,
+    WriteStorage<'a, SymbolicFunction>,
+    WriteStorage<'a, PlotStyle>,
+    WriteStorage<'a, Selected>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.prompt_event_reader = Some(world.fetch_mut::<PromptEventChannel>().register_reader());
+  }
+
+  fn run(&mut self, (
+    entities,
+    prompt_events,
+    protocol,
+    mut prompt_state,
+    mut sketch_events,
+    sym_points,
+    mut sym_functions,
+    mut styles,
+    mut selected,
+  ): Self::SystemData) {
+    if let Some(reader_id) = &mut self.prompt_event_reader {
+      for PromptEvent(kind, text) in prompt_events.read(reader_id) {
+        if *kind != PromptKind::Function {
+          continue;
+        }
+        match parse_function(text, &protocol, &sym_points) {
+          Ok(sym_function) => {
+            let plot_style = PlotStyle { color: Color::blue(), width: 2. };
+
+            // Create the function
+            let entity = entities.create();
+            if let Err(err) = sym_functions.insert(entity, sym_function.clone()) { panic!("[create_function_system] {:?}", err) }
+            if let Err(err) = styles.insert(entity, plot_style) { panic!("[create_function_system] {:?}", err) }
+            if let Err(err) = selected.insert(entity, Selected) { panic!("[create_function_system] {:?}", err) }
+
+            // Push event to created functions
+            sketch_events.single_write(SketchEvent::Insert(entity, Geometry::Function(sym_function, plot_style)));
+            prompt_state.close();
+          },
+          Err(err) => prompt_state.set_error(err),
+        }
+      }
+    }
+  }
+}
\ No newline at end of file
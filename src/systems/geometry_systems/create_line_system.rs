@@ -9,7 +9,7 @@ use crate::{
     geometry::{LastActivePoint, CreateLineData},
     events::{SketchEvent, Geometry, SketchEventChannel},
   },
-  components::{SymbolicPoint, SymbolicLine, LineStyle, Selected},
+  components::{SymbolicLine, LineStyle, Selected},
 };
 
 pub struct CreateLineSystem {
@@ -30,7 +30,6 @@ impl<'a> System<'a> for CreateLineSystem {
     Write<'a, CreateLineData>,
     Write<'a, EventChannel<LastActivePoint>>,
     Write<'a, SketchEventChannel>,
-    ReadStorage<'a, SymbolicPoint>,
     WriteStorage<'a, SymbolicLine>,
     WriteStorage<'a, LineStyle>,
     WriteStorage<'a, Selected>,
@@ -43,7 +42,6 @@ impl<'a> System<'a> for CreateLineSystem {
     mut create_line_data,
     mut last_active_point_event,
     mut sketch_events,
-    sym_points,
     mut sym_lines,
     mut styles,
     mut selected,
@@ -77,7 +75,7 @@ impl<'a> System<'a> for CreateLineSystem {
 
           // Need to check first point is not second point
           if first_point_entity != curr_point_entity &&
-            !on_same_line(first_point_entity, curr_point_entity, &dependency_graph, &sym_points, &sym_lines) {
+            !on_same_line(first_point_entity, curr_point_entity, &dependency_graph, &sym_lines) {
 
             let sym_line = SymbolicLine::TwoPoints(first_point_entity, curr_point_entity);
             let line_style = LineStyle { color: Color::blue(), width: 2. };
@@ -107,53 +105,18 @@ impl<'a> System<'a> for CreateLineSystem {
   }
 }
 
+/// Whether there is already a line through both points: one they are on,
+/// or one defined on them
 fn on_same_line<'a>(
   p1: Entity,
   p2: Entity,
   dependency_graph: &DependencyGraph,
-  sym_points: &ReadStorage<'a, SymbolicPoint>,
   sym_lines: &WriteStorage<'a, SymbolicLine>,
 ) -> bool {
-  if let Some(sp1) = sym_points.get(p1) {
-    if let Some(sp2) = sym_points.get(p2) {
-      let direct_check_on_same_line = sp1.is_on_same_line_with(sp2);
-      if direct_check_on_same_line {
-        return true;
-      } else {
-        if let Some(p1_children) = dependency_graph.get_direct_dependents(&p1) {
-          if let Some(p2_children) = dependency_graph.get_direct_dependents(&p2) {
-            for itsct in p1_children.intersection(p2_children) {
-              if sym_lines.get(*itsct).is_some() {
-                return true;
-              }
-            }
-            if check_parent_line_contained_by(sp1, p2_children) {
-              return true;
-            }
-            if check_parent_line_contained_by(sp2, p1_children) {
-              return true;
-            }
-            return false;
-          } else {
-            return check_parent_line_contained_by(sp2, p1_children);
-          }
-        } else {
-          if let Some(p2_children) = dependency_graph.get_direct_dependents(&p2) {
-            return check_parent_line_contained_by(sp1, p2_children);
-          } else {
-            return false;
-          }
-        }
-      }
-    }
-  }
-  panic!("[create_line_system] Point entities does not have symbolic point");
-}
-
-fn check_parent_line_contained_by(sp: &SymbolicPoint, set: &HashSet<Entity>) -> bool {
-  match sp {
-    SymbolicPoint::Free(_) => false,
-    SymbolicPoint::OnLine(line_ent, _) => set.contains(&line_ent),
-    SymbolicPoint::LineLineIntersect(l1_ent, l2_ent) => set.contains(&l1_ent) || set.contains(&l2_ent),
-  }
+  let lines_through = |p: Entity| -> HashSet<Entity> {
+    let parents = dependency_graph.get_direct_parents(&p).into_iter().flatten();
+    let children = dependency_graph.get_direct_dependents(&p).into_iter().flatten();
+    parents.chain(children).filter(|ent| sym_lines.get(**ent).is_some()).cloned().collect()
+  };
+  !lines_through(p1).is_disjoint(&lines_through(p2))
 }
\ No newline at end of file
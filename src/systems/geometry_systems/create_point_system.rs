@@ -79,9 +79,7 @@ impl<'a> System<'a> for CreatePointSystem {
 
               // Get the symbolic point data from symbo
               let symbolic_point = match symbo {
-                SnapPointType::NotSnapped => Some(SymbolicPoint::Free(position)),
-                SnapPointType::SnapOnLine(line_ent, t) => Some(SymbolicPoint::OnLine(line_ent, t)),
-                SnapPointType::SnapOnIntersection(l1_ent, l2_ent) => Some(SymbolicPoint::LineLineIntersect(l1_ent, l2_ent)),
+                SnapPointType::NotSnapped | SnapPointType::SnapOnGrid => Some(SymbolicPoint::Free(position)),
                 SnapPointType::SnapOnPoint(entity) => {
 
                   // If clicked on the snapped point, mark this point as last active
@@ -90,6 +88,7 @@ impl<'a> System<'a> for CreatePointSystem {
                   // Return none since we don't create new symbolic point
                   None
                 },
+                _ => symbo.symbolic_point(),
               };
 
               // Check if we need to create a point
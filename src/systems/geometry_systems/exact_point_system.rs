@@ -0,0 +1,127 @@
+use specs::prelude::*;
+use crate::{
+  utilities::{Vector2, Color, Expression},
+  resources::{
+    PromptState, PromptKind,
+    events::{
+      PromptEvent, PromptEventChannel, PromptEventReader,
+      GeometryAction, GeometryActionChannel, GeometryActionReader,
+      SketchEvent, SketchEventChannel, Geometry, MovePoint,
+    },
+  },
+  components::{SymbolicPoint, Point, PointStyle, Selected},
+};
+
+/// Parse a constant expression such as `sqrt(2) / 2`
+fn parse_number(text: &str) -> Result<f64, String> {
+  let value = Expression::parse_with_variables(text, &[]).map_err(|err| err.to_string())?.evaluate(&|_| None);
+  if value.is_finite() { Ok(value) } else { Err("not a finite number".to_string()) }
+}
+
+/// `x, y`
+fn parse_position(text: &str) -> Result<Point, String> {
+  match text.split(',').collect::<Vec<_>>()[..] {
+    [x, y] => Ok(vec2![parse_number(x)?, parse_number(y)?]),
+    _ => Err("expected x, y".to_string()),
+  }
+}
+
+#[derive(Default)]
+pub struct ExactPointSystem {
+  prompt_event_reader: Option<PromptEventReader>,
+  geometry_action_reader: Option<GeometryActionReader>,
+}
+
+/// # Exact Point System
+///
+/// Creates a free point at exactly the coordinates typed into the prompt, or
+/// moves the edited point to them. Points on lines take their `t` instead.
+/// Editing a point opens the prompt with its current values. From the
+/// keyboard, only a single selected point can be edited. The prompt stays
+/// open with an error message when the text cannot be parsed.
+impl<'a> System<'a> for ExactPointSystem {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, PromptEventChannel>,
+    Read<'a, GeometryActionChannel>,
+    Write<'a, PromptState>,
+    Write<'a, SketchEventChannel>,
+    WriteStorage<'a, SymbolicPoint>,
+    WriteStorage<'a, Point>,
+    WriteStorage<'a, PointStyle>,
+    WriteStorage<'a, Selected>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.prompt_event_reader = Some(world.fetch_mut::<PromptEventChannel>().register_reader());
+    self.geometry_action_reader = Some(world.fetch_mut::<GeometryActionChannel>().register_reader());
+  }
+
+  fn run(&mut self, (
+    entities,
+    prompt_events,
+    geometry_action_channel,
+    mut prompt_state,
+    mut sketch_events,
+    mut sym_points,
+    mut points,
+    mut styles,
+    mut selected,
+  ): Self::SystemData) {
+    if let Some(reader_id) = &mut self.geometry_action_reader {
+      for event in geometry_action_channel.read(reader_id) {
+        let edited = match event {
+          GeometryAction::EditSelected => {
+            let selected_points : Vec<_> = (&entities, &sym_points, &selected).join().map(|(ent, sym, _)| (ent, *sym)).collect();
+            match selected_points[..] {
+              [edited] => Some(edited),
+              _ => None,
+            }
+          },
+          GeometryAction::EditPoint(ent) => sym_points.get(*ent).map(|sym| (*ent, *sym)),
+          _ => None,
+        };
+        match edited {
+          Some((ent, SymbolicPoint::Free(position))) => prompt_state.open(PromptKind::PointPosition(ent), format!("{}, {}", position.x, position.y)),
+          Some((ent, SymbolicPoint::OnLine(_, t))) => prompt_state.open(PromptKind::PointParameter(ent), format!("{}", t)),
+          _ => (),
+        }
+      }
+    }
+
+    if let Some(reader_id) = &mut self.prompt_event_reader {
+      for PromptEvent(kind, text) in prompt_events.read(reader_id) {
+        let result = match kind {
+          PromptKind::NewPoint => parse_position(text).map(|position| {
+            let sym_point = SymbolicPoint::Free(position);
+            let point_style = PointStyle { color: Color::red(), radius: 5. };
+
+            // Create the point
+            let entity = entities.create();
+            if let Err(err) = sym_points.insert(entity, sym_point) { panic!("[exact_point_system] {:?}", err) }
+            if let Err(err) = points.insert(entity, position) { panic!("[exact_point_system] {:?}", err) }
+            if let Err(err) = styles.insert(entity, point_style) { panic!("[exact_point_system] {:?}", err) }
+            if let Err(err) = selected.insert(entity, Selected) { panic!("[exact_point_system] {:?}", err) }
+            sketch_events.single_write(SketchEvent::Insert(entity, Geometry::Point(sym_point, point_style)));
+          }),
+          PromptKind::PointPosition(entity) => parse_position(text).map(|new_position| {
+            if let Some(SymbolicPoint::Free(old_position)) = sym_points.get(*entity) {
+              sketch_events.single_write(SketchEvent::MovePoint(*entity, MovePoint::Free(*old_position, new_position)));
+            }
+          }),
+          PromptKind::PointParameter(entity) => parse_number(text).map(|new_t| {
+            if let Some(SymbolicPoint::OnLine(line_ent, old_t)) = sym_points.get(*entity) {
+              sketch_events.single_write(SketchEvent::MovePoint(*entity, MovePoint::OnLine(*line_ent, *old_t, new_t)));
+            }
+          }),
+          _ => continue,
+        };
+        match result {
+          Ok(()) => prompt_state.close(),
+          Err(err) => prompt_state.set_error(err),
+        }
+      }
+    }
+  }
+}
\ No newline at end of file
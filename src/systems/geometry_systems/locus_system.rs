@@ -0,0 +1,265 @@
+use std::collections::{HashMap, HashSet};
+use specs::prelude::*;
+use crate::{
+  utilities::Vector2,
+  components::{SymbolicPoint, Point, SymbolicLine, Line, SymbolicArc, Arc, SymbolicConic, Conic, SymbolicFunction, SymbolicCurve, SymbolicLocus, Plot, Hidden},
+  resources::{
+    DependencyGraph,
+    SpatialHashTable,
+    Viewport,
+    events::{
+      ViewportEventChannel, ViewportEventReader,
+      Geometry, SketchEvent, SketchEventChannel, SketchEventReader,
+    },
+  },
+};
+use super::solver_system::{Definitions, Solved, SolvedStore, SolveResult, solve_in_order, solve_point};
+
+static LOCUS_TOLERANCE : f64 = 0.5; // In actual space
+
+/// The solved storages of the world, read only
+pub struct LiveSolved<'r, 'a> {
+  pub points: &'r ReadStorage<'a, Point>,
+  pub lines: &'r ReadStorage<'a, Line>,
+  pub arcs: &'r ReadStorage<'a, Arc>,
+  pub conics: &'r ReadStorage<'a, Conic>,
+}
+
+impl<'r, 'a> Solved for LiveSolved<'r, 'a> {
+  fn point(&self, ent: Entity) -> Option<Point> { self.points.get(ent).cloned() }
+  fn line(&self, ent: Entity) -> Option<Line> { self.lines.get(ent).cloned() }
+  fn arc(&self, ent: Entity) -> Option<Arc> { self.arcs.get(ent).cloned() }
+  fn conic(&self, ent: Entity) -> Option<Conic> { self.conics.get(ent).cloned() }
+}
+
+/// Scratch copy on top of the live solved geometries. Everything `dirty`
+/// is hidden from the live ones so that it is solved again into the scratch
+/// copy, leaving the world untouched
+struct Scratch<'s, S: Solved> {
+  live: &'s S,
+  dirty: &'s HashSet<Entity>,
+  points: HashMap<Entity, Point>,
+  lines: HashMap<Entity, Line>,
+  arcs: HashMap<Entity, Arc>,
+  conics: HashMap<Entity, Conic>,
+}
+
+impl<'s, S: Solved> Scratch<'s, S> {
+  fn new(live: &'s S, dirty: &'s HashSet<Entity>) -> Self {
+    Self { live, dirty, points: HashMap::new(), lines: HashMap::new(), arcs: HashMap::new(), conics: HashMap::new() }
+  }
+
+  fn lookup<T: Copy, F: Fn(&S) -> Option<T>>(&self, ent: Entity, scratch: &HashMap<Entity, T>, live: F) -> Option<T> {
+    match scratch.get(&ent) {
+      Some(geom) => Some(*geom),
+      None => if self.dirty.contains(&ent) { None } else { live(self.live) },
+    }
+  }
+}
+
+impl<'s, S: Solved> Solved for Scratch<'s, S> {
+  fn point(&self, ent: Entity) -> Option<Point> { self.lookup(ent, &self.points, |live| live.point(ent)) }
+  fn line(&self, ent: Entity) -> Option<Line> { self.lookup(ent, &self.lines, |live| live.line(ent)) }
+  fn arc(&self, ent: Entity) -> Option<Arc> { self.lookup(ent, &self.arcs, |live| live.arc(ent)) }
+  fn conic(&self, ent: Entity) -> Option<Conic> { self.lookup(ent, &self.conics, |live| live.conic(ent)) }
+}
+
+impl<'s, S: Solved> SolvedStore for Scratch<'s, S> {
+  fn insert_point(&mut self, ent: Entity, p: Point) { self.points.insert(ent, p); }
+  fn insert_line(&mut self, ent: Entity, line: Line) { self.lines.insert(ent, line); }
+  fn insert_arc(&mut self, ent: Entity, arc: Arc) { self.arcs.insert(ent, arc); }
+  fn insert_conic(&mut self, ent: Entity, conic: Conic) { self.conics.insert(ent, conic); }
+}
+
+/// Range of the parameter a point slides along by. Unbounded ones are
+/// limited to within `radius` of the viewport center
+pub fn parameter_range<S: Solved>(
+  sym_point: &SymbolicPoint,
+  sym_curves: &ReadStorage<SymbolicCurve>,
+  live: &S,
+  vp: &Viewport,
+  radius: f64,
+) -> Option<(f64, f64)> {
+  let center = vec2![(vp.x_min() + vp.x_max()) / 2.0, (vp.y_min() + vp.y_max()) / 2.0];
+  match sym_point {
+    SymbolicPoint::OnLine(line_ent, _) => live.line(*line_ent).map(|Line { origin, direction }| {
+      let t = (center - origin).dot(direction);
+      (t - radius, t + radius)
+    }),
+    SymbolicPoint::OnArc(_, _) => Some((0.0, 1.0)),
+    SymbolicPoint::OnConic(conic_ent, _) => live.conic(*conic_ent).map(|conic| conic.param_range(center, radius)),
+    SymbolicPoint::OnFunction(_, _) => {
+      let margin = vp.virtual_width() * 0.05;
+      Some((vp.x_min() - margin, vp.x_max() + margin))
+    },
+    SymbolicPoint::OnCurve(curve_ent, _) => sym_curves.get(*curve_ent).map(|sym_curve| sym_curve.range()),
+    SymbolicPoint::Free(_) | SymbolicPoint::LineLineIntersect(_, _) | SymbolicPoint::FunctionLineIntersect(_, _, _) => None,
+  }
+}
+
+/// Sample the locus by solving the traced point again for every trial
+/// position of the driver
+fn sample_locus<S: Solved>(sym_locus: &SymbolicLocus, defs: &Definitions, live: &S, dep_graph: &DependencyGraph, vp: &Viewport) -> Option<Plot> {
+  let SymbolicLocus { driver, traced } = *sym_locus;
+  let sym_driver = defs.sym_points.get(driver)?;
+  let radius = vp.virtual_width() + vp.virtual_height();
+  let (t_0, t_1) = parameter_range(sym_driver, defs.sym_curves, live, vp, radius)?;
+  let dirty = dep_graph.get_all_dependents(&driver);
+
+  // Only what is between the driver and the traced point is solved again,
+  // in the same order for every trial position
+  let mut needed = HashSet::new();
+  let mut stack = vec![traced];
+  while let Some(ent) = stack.pop() {
+    if ent != driver && dirty.contains(&ent) && needed.insert(ent) {
+      stack.extend(dep_graph.get_direct_parents(&ent).into_iter().flatten());
+    }
+  }
+  let order = dep_graph.topological_order(&needed).ok()?;
+  let trace = |t: f64| -> Option<Vector2> {
+    let driver_position = match solve_point(defs, live, &sym_driver.with_parameter(t)?) {
+      SolveResult::SolvedPoint(p) => p,
+      _ => return None,
+    };
+    let mut scratch = Scratch::new(live, &dirty);
+    scratch.insert_point(driver, driver_position);
+    solve_in_order(defs, &mut scratch, &order);
+    scratch.point(traced)
+  };
+  Some(Plot::sample(trace, t_0, t_1, LOCUS_TOLERANCE * vp.scale()))
+}
+
+/// # Locus System
+///
+/// Samples the path of the traced point by sweeping the driver over its
+/// whole range. The traced point is solved on a scratch copy so none of the
+/// geometries in the world move. The locus is sampled again whenever
+/// anything it depends on moves or the viewport changes.
+#[derive(Default)]
+pub struct LocusSystem {
+  viewport_events_reader_id: Option<ViewportEventReader>,
+  sketch_events_reader_id: Option<SketchEventReader>,
+}
+
+impl<'a> System<'a> for LocusSystem {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, Viewport>,
+    Read<'a, ViewportEventChannel>,
+    Read<'a, SketchEventChannel>,
+    Read<'a, DependencyGraph>,
+    Write<'a, SpatialHashTable<Entity>>,
+    ReadStorage<'a, SymbolicPoint>,
+    ReadStorage<'a, SymbolicLine>,
+    ReadStorage<'a, SymbolicArc>,
+    ReadStorage<'a, SymbolicConic>,
+    ReadStorage<'a, SymbolicFunction>,
+    ReadStorage<'a, SymbolicCurve>,
+    ReadStorage<'a, SymbolicLocus>,
+    ReadStorage<'a, Point>,
+    ReadStorage<'a, Line>,
+    ReadStorage<'a, Arc>,
+    ReadStorage<'a, Conic>,
+    ReadStorage<'a, Hidden>,
+    WriteStorage<'a, Plot>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.viewport_events_reader_id = Some(world.fetch_mut::<ViewportEventChannel>().register_reader());
+    self.sketch_events_reader_id = Some(world.fetch_mut::<SketchEventChannel>().register_reader());
+  }
+
+  fn run(&mut self, (
+    entities,
+    vp,
+    viewport_event_channel,
+    sketch_events,
+    dependency_graph,
+    mut table,
+    sym_points,
+    sym_lines,
+    sym_arcs,
+    sym_conics,
+    sym_functions,
+    sym_curves,
+    sym_loci,
+    points,
+    lines,
+    arcs,
+    conics,
+    hidden,
+    mut plots,
+  ): Self::SystemData) {
+    let mut to_sample = HashSet::new();
+
+    // Everything is sampled again when the viewport changes
+    let refresh = if let Some(reader_id) = &mut self.viewport_events_reader_id {
+      viewport_event_channel.read(reader_id).count() > 0
+    } else {
+      panic!("[locus_system] No viewport event reader id");
+    };
+    if refresh {
+      for (ent, _) in (&*entities, &sym_loci).join() {
+        to_sample.insert(ent);
+      }
+    }
+
+    // Else only the new loci and the ones depending on a moved point. Moving
+    // the driver itself along its own path does not change the locus
+    if let Some(reader_id) = &mut self.sketch_events_reader_id {
+      for event in sketch_events.read(reader_id) {
+        match event {
+          SketchEvent::Insert(entity, Geometry::Locus(_, _)) => { to_sample.insert(*entity); },
+
+          // A redefined driver moves along a different path
+          SketchEvent::Redefine(entity, _, _) => {
+            for dependent in dependency_graph.get_all_dependents(entity) {
+              if sym_loci.get(dependent).is_some() {
+                to_sample.insert(dependent);
+              }
+            }
+          },
+          SketchEvent::MovePoint(entity, _) => {
+            for dependent in dependency_graph.get_all_dependents(entity) {
+              if let Some(sym_locus) = sym_loci.get(dependent) {
+                if sym_locus.driver != *entity {
+                  to_sample.insert(dependent);
+                }
+              }
+            }
+          },
+          _ => (),
+        }
+      }
+    } else {
+      panic!("[locus_system] No sketch events reader id");
+    }
+
+    let defs = Definitions {
+      sym_points: &sym_points,
+      sym_lines: &sym_lines,
+      sym_arcs: &sym_arcs,
+      sym_conics: &sym_conics,
+      sym_functions: &sym_functions,
+      sym_curves: &sym_curves,
+    };
+    let live = LiveSolved { points: &points, lines: &lines, arcs: &arcs, conics: &conics };
+    for ent in to_sample {
+      if let Some(sym_locus) = sym_loci.get(ent) {
+        table.remove_from_all(ent);
+        match sample_locus(sym_locus, &defs, &live, &dependency_graph, &vp) {
+          Some(plot) => {
+            if hidden.get(ent).is_none() {
+              table.insert_plot(ent, &plot, &vp);
+            }
+            if let Err(err) = plots.insert(ent, plot) {
+              panic!("[locus_system] Error when inserting plot: {:?}", err);
+            }
+          },
+          None => { plots.remove(ent); },
+        }
+      }
+    }
+  }
+}
\ No newline at end of file
@@ -14,4 +14,25 @@ mod create_point_system;
 pub use create_point_system::*;
 
 mod create_line_system;
-pub use create_line_system::*;
\ No newline at end of file
+pub use create_line_system::*;
+
+mod create_arc_system;
+pub use create_arc_system::*;
+
+mod create_conic_system;
+pub use create_conic_system::*;
+
+mod create_function_system;
+pub use create_function_system::*;
+
+mod create_curve_system;
+pub use create_curve_system::*;
+
+mod locus_system;
+pub use locus_system::*;
+
+mod animation_system;
+pub use animation_system::*;
+
+mod exact_point_system;
+pub use exact_point_system::*;
\ No newline at end of file
@@ -37,6 +37,26 @@ impl<'a> System<'a> for MovePointHandler {
               MovePoint::OnLine(line_entity, _, new_t) => {
                 if let Err(err) = sym_points.insert(*entity, SymbolicPoint::OnLine(*line_entity, *new_t)) { panic!(err) }
               },
+              MovePoint::OnArc(arc_entity, _, new_t) => {
+                if let Err(err) = sym_points.insert(*entity, SymbolicPoint::OnArc(*arc_entity, *new_t)) {
+                  panic!("[move_point_handler] Error when moving point on arc: {:?}", err)
+                }
+              },
+              MovePoint::OnConic(conic_entity, _, new_t) => {
+                if let Err(err) = sym_points.insert(*entity, SymbolicPoint::OnConic(*conic_entity, *new_t)) {
+                  panic!("[move_point_handler] Error when moving point on conic: {:?}", err)
+                }
+              },
+              MovePoint::OnFunction(function_entity, _, new_x) => {
+                if let Err(err) = sym_points.insert(*entity, SymbolicPoint::OnFunction(*function_entity, *new_x)) {
+                  panic!("[move_point_handler] Error when moving point on function: {:?}", err)
+                }
+              },
+              MovePoint::OnCurve(curve_entity, _, new_t) => {
+                if let Err(err) = sym_points.insert(*entity, SymbolicPoint::OnCurve(*curve_entity, *new_t)) {
+                  panic!("[move_point_handler] Error when moving point on curve: {:?}", err)
+                }
+              },
             }
           },
           _ => (),
@@ -1,6 +1,6 @@
 use specs::prelude::*;
 use crate::{
-  components::{SymbolicPoint, Point, PointStyle, SymbolicLine, Line, LineStyle, Selected},
+  components::{SymbolicPoint, Point, PointStyle, SymbolicLine, Line, LineStyle, SymbolicArc, Arc, ArcStyle, SymbolicConic, Conic, ConicStyle, SymbolicFunction, SymbolicCurve, SymbolicLocus, Plot, PlotStyle, Selected, Traced, Animated, Hidden, HiddenByUser},
   resources::events::{SketchEvent, SketchEventChannel, SketchEventReader},
 };
 
@@ -23,7 +23,22 @@ impl<'a> System<'a> for RemoveHandler {
     WriteStorage<'a, SymbolicLine>,
     WriteStorage<'a, Line>,
     WriteStorage<'a, LineStyle>,
+    WriteStorage<'a, SymbolicArc>,
+    WriteStorage<'a, Arc>,
+    WriteStorage<'a, ArcStyle>,
+    WriteStorage<'a, SymbolicConic>,
+    WriteStorage<'a, Conic>,
+    WriteStorage<'a, ConicStyle>,
+    WriteStorage<'a, SymbolicFunction>,
+    WriteStorage<'a, SymbolicCurve>,
+    WriteStorage<'a, SymbolicLocus>,
+    WriteStorage<'a, Plot>,
+    WriteStorage<'a, PlotStyle>,
     WriteStorage<'a, Selected>,
+    WriteStorage<'a, Traced>,
+    WriteStorage<'a, Animated>,
+    WriteStorage<'a, Hidden>,
+    WriteStorage<'a, HiddenByUser>,
   );
 
   fn setup(&mut self, world: &mut World) {
@@ -39,7 +54,22 @@ impl<'a> System<'a> for RemoveHandler {
     mut sym_lines,
     mut lines,
     mut line_styles,
+    mut sym_arcs,
+    mut arcs,
+    mut arc_styles,
+    mut sym_conics,
+    mut conics,
+    mut conic_styles,
+    mut sym_functions,
+    mut sym_curves,
+    mut sym_loci,
+    mut plots,
+    mut plot_styles,
     mut selected,
+    mut traced,
+    mut animated,
+    mut hidden,
+    mut hidden_by_user,
   ): Self::SystemData) {
     if let Some(reader_id) = &mut self.sketch_event_reader {
       for event in sketch_event_channel.read(reader_id) {
@@ -51,7 +81,22 @@ impl<'a> System<'a> for RemoveHandler {
             sym_lines.remove(*entity);
             lines.remove(*entity);
             line_styles.remove(*entity);
+            sym_arcs.remove(*entity);
+            arcs.remove(*entity);
+            arc_styles.remove(*entity);
+            sym_conics.remove(*entity);
+            conics.remove(*entity);
+            conic_styles.remove(*entity);
+            sym_functions.remove(*entity);
+            sym_curves.remove(*entity);
+            sym_loci.remove(*entity);
+            plots.remove(*entity);
+            plot_styles.remove(*entity);
             selected.remove(*entity);
+            traced.remove(*entity);
+            animated.remove(*entity);
+            hidden.remove(*entity);
+            hidden_by_user.remove(*entity);
           },
           _ => (),
         }
@@ -1,122 +1,368 @@
+use std::collections::{HashMap, HashSet};
 use specs::prelude::*;
 use crate::{
-  utilities::Intersect,
-  components::{SymbolicPoint, Point, SymbolicLine, Line},
+  utilities::{Vector2, Intersect},
+  components::{SymbolicPoint, Point, SymbolicLine, Line, SymbolicArc, Arc, SymbolicConic, Conic, SymbolicFunction, SymbolicCurve},
   resources::{
     DependencyGraph,
+    SolveError,
+    SolverReport,
+    topological_order,
     events::{SketchEvent, SketchEventChannel, SketchEventReader, Geometry},
   }
 };
 
-enum ToCompute {
+static FUNCTION_INTERSECT_RADIUS : f64 = 10.0; // In virtual space
+
+pub enum ToCompute {
   Point(Entity),
   Line(Entity),
+  Arc(Entity),
+  Conic(Entity),
+  Function(Entity), // Never computed, only requested when it does not exist
+  Curve(Entity), // Same as function
+}
+
+impl ToCompute {
+  pub fn entity(&self) -> Entity {
+    match self {
+      ToCompute::Point(ent) | ToCompute::Line(ent) | ToCompute::Arc(ent) | ToCompute::Conic(ent) => *ent,
+      ToCompute::Function(ent) | ToCompute::Curve(ent) => *ent,
+    }
+  }
 }
 
-enum SolveResult {
-  AlreadyComputed, // Already Computed
+pub enum SolveResult {
   SolvedPoint(Point), // The result of point
   SolvedLine(Line), // The result of line
+  SolvedArc(Arc), // The result of arc
+  SolvedConic(Conic), // The result of conic
   Request(ToCompute), // Need other dependency
   Undefined, // The result does not exist
 }
 
-fn insert_point<'a>(points: &mut WriteStorage<'a, Point>, ent: Entity, p: Point) {
-  if let Err(err) = points.insert(ent, p) {
-    panic!("[solver_system] Error when inserting position: {:?}", err);
+/// Symbolic definitions the geometries are solved from
+pub struct Definitions<'r, 'a> {
+  pub sym_points: &'r ReadStorage<'a, SymbolicPoint>,
+  pub sym_lines: &'r ReadStorage<'a, SymbolicLine>,
+  pub sym_arcs: &'r ReadStorage<'a, SymbolicArc>,
+  pub sym_conics: &'r ReadStorage<'a, SymbolicConic>,
+  pub sym_functions: &'r ReadStorage<'a, SymbolicFunction>,
+  pub sym_curves: &'r ReadStorage<'a, SymbolicCurve>,
+}
+
+impl<'r, 'a> Definitions<'r, 'a> {
+
+  /// Whether the requested geometry is defined at all
+  fn defines(&self, to_comp: &ToCompute) -> bool {
+    match to_comp {
+      ToCompute::Point(ent) => self.sym_points.get(*ent).is_some(),
+      ToCompute::Line(ent) => self.sym_lines.get(*ent).is_some(),
+      ToCompute::Arc(ent) => self.sym_arcs.get(*ent).is_some(),
+      ToCompute::Conic(ent) => self.sym_conics.get(*ent).is_some(),
+      ToCompute::Function(ent) => self.sym_functions.get(*ent).is_some(),
+      ToCompute::Curve(ent) => self.sym_curves.get(*ent).is_some(),
+    }
   }
 }
 
-fn insert_line<'a>(lines: &mut WriteStorage<'a, Line>, ent: Entity, line: Line) {
-  if let Err(err) = lines.insert(ent, line) {
-    panic!("[solver_system] Error when inserting position: {:?}", err);
+/// Where the solver looks up the geometries it already solved
+pub trait Solved {
+  fn point(&self, ent: Entity) -> Option<Point>;
+  fn line(&self, ent: Entity) -> Option<Line>;
+  fn arc(&self, ent: Entity) -> Option<Arc>;
+  fn conic(&self, ent: Entity) -> Option<Conic>;
+}
+
+/// Where the solver stores the geometries it solves
+pub trait SolvedStore : Solved {
+  fn insert_point(&mut self, ent: Entity, p: Point);
+  fn insert_line(&mut self, ent: Entity, line: Line);
+  fn insert_arc(&mut self, ent: Entity, arc: Arc);
+  fn insert_conic(&mut self, ent: Entity, conic: Conic);
+}
+
+/// The solved storages of the world
+struct LiveStore<'r, 'a> {
+  points: &'r mut WriteStorage<'a, Point>,
+  lines: &'r mut WriteStorage<'a, Line>,
+  arcs: &'r mut WriteStorage<'a, Arc>,
+  conics: &'r mut WriteStorage<'a, Conic>,
+}
+
+impl<'r, 'a> Solved for LiveStore<'r, 'a> {
+  fn point(&self, ent: Entity) -> Option<Point> { self.points.get(ent).cloned() }
+  fn line(&self, ent: Entity) -> Option<Line> { self.lines.get(ent).cloned() }
+  fn arc(&self, ent: Entity) -> Option<Arc> { self.arcs.get(ent).cloned() }
+  fn conic(&self, ent: Entity) -> Option<Conic> { self.conics.get(ent).cloned() }
+}
+
+impl<'r, 'a> SolvedStore for LiveStore<'r, 'a> {
+  fn insert_point(&mut self, ent: Entity, p: Point) {
+    if let Err(err) = self.points.insert(ent, p) {
+      panic!("[solver_system] Error when inserting position: {:?}", err);
+    }
+  }
+
+  fn insert_line(&mut self, ent: Entity, line: Line) {
+    if let Err(err) = self.lines.insert(ent, line) {
+      panic!("[solver_system] Error when inserting position: {:?}", err);
+    }
+  }
+
+  fn insert_arc(&mut self, ent: Entity, arc: Arc) {
+    if let Err(err) = self.arcs.insert(ent, arc) {
+      panic!("[solver_system] Error when inserting arc: {:?}", err);
+    }
+  }
+
+  fn insert_conic(&mut self, ent: Entity, conic: Conic) {
+    if let Err(err) = self.conics.insert(ent, conic) {
+      panic!("[solver_system] Error when inserting conic: {:?}", err);
+    }
   }
 }
 
-fn solve_point<'a>(
-  sym_points: &ReadStorage<'a, SymbolicPoint>,
-  points: &mut WriteStorage<'a, Point>,
-  lines: &mut WriteStorage<'a, Line>,
-  ent: Entity,
-) -> SolveResult {
+/// Solve the entities in order, each one once, so the parents have to come
+/// before their children. Anything depending on an undefined geometry is
+/// undefined as well, while depending on one that does not exist is an error
+pub fn solve_in_order<S: SolvedStore>(defs: &Definitions, store: &mut S, order: &[Entity]) -> Vec<SolveError> {
+  let mut errors = vec![];
+  for ent in order.iter().cloned() {
+    let result = if let Some(sym) = defs.sym_points.get(ent) {
+      solve_point(defs, store, sym)
+    } else if let Some(sym) = defs.sym_lines.get(ent) {
+      solve_line(defs, store, sym)
+    } else if let Some(sym) = defs.sym_arcs.get(ent) {
+      solve_arc(store, sym)
+    } else if let Some(sym) = defs.sym_conics.get(ent) {
+      solve_conic(store, sym)
+    } else {
+      continue; // Functions, curves and loci are not solved here
+    };
+    match result {
+      SolveResult::SolvedPoint(p) => store.insert_point(ent, p),
+      SolveResult::SolvedLine(l) => store.insert_line(ent, l),
+      SolveResult::SolvedArc(a) => store.insert_arc(ent, a),
+      SolveResult::SolvedConic(c) => store.insert_conic(ent, c),
+      SolveResult::Undefined => (),
 
-  // First check if the result is already computed
-  match points.get(ent) {
-    Some(_) => SolveResult::AlreadyComputed,
-    None => match sym_points.get(ent) {
-      Some(sym) => match sym {
+      // Since the parents come first, a parent that is not solved is either
+      // undefined or missing
+      SolveResult::Request(req) => if !defs.defines(&req) {
+        errors.push(SolveError::Dangling(ent, req.entity()));
+      },
+    }
+  }
+  errors
+}
 
-        // If it is a free point, then the solved point is right there
-        SymbolicPoint::Free(pos) => SolveResult::SolvedPoint(*pos),
+/// Solve a point from its definition. This is public so that a point may be
+/// solved from a definition that is not stored, e.g. with a different parameter
+pub fn solve_point<S: Solved>(defs: &Definitions, solved: &S, sym: &SymbolicPoint) -> SolveResult {
+  match sym {
 
-        // If it is a point on a line, then the point is at distance t from origin
-        // along the direction. If the computed line is not found we request the
-        // algorithm to compute the line first
-        SymbolicPoint::OnLine(line_ent, t) => match lines.get(*line_ent) {
-          Some(Line { origin, direction }) => {
-            SolveResult::SolvedPoint(origin.clone() + *t * direction.clone())
-          },
-          None => SolveResult::Request(ToCompute::Line(*line_ent))
+    // If it is a free point, then the solved point is right there
+    SymbolicPoint::Free(pos) => SolveResult::SolvedPoint(*pos),
+
+    // If it is a point on a line, then the point is at distance t from origin
+    // along the direction. If the computed line is not found we request the
+    // algorithm to compute the line first
+    SymbolicPoint::OnLine(line_ent, t) => match solved.line(*line_ent) {
+      Some(Line { origin, direction }) => {
+        SolveResult::SolvedPoint(origin + *t * direction)
+      },
+      None => SolveResult::Request(ToCompute::Line(*line_ent))
+    },
+
+    // We demand two lines
+    SymbolicPoint::LineLineIntersect(l1_ent, l2_ent) => match solved.line(*l1_ent) {
+      Some(line_1) => match solved.line(*l2_ent) {
+        Some(line_2) => match line_1.intersect(line_2) {
+          Some(p) => SolveResult::SolvedPoint(p),
+          None => SolveResult::Undefined,
         },
+        None => SolveResult::Request(ToCompute::Line(*l2_ent)),
+      },
+      None => SolveResult::Request(ToCompute::Line(*l1_ent)),
+    },
 
-        // We demand two lines
-        SymbolicPoint::LineLineIntersect(l1_ent, l2_ent) => match lines.get(*l1_ent) {
-          Some(line_1) => match lines.get(*l2_ent) {
-            Some(line_2) => match line_1.intersect(*line_2) {
-              Some(p) => SolveResult::SolvedPoint(p),
-              None => SolveResult::Undefined,
-            },
-            None => SolveResult::Request(ToCompute::Line(*l2_ent)),
+    // A point on an arc stays within the arc's range
+    SymbolicPoint::OnArc(arc_ent, t) => match solved.arc(*arc_ent) {
+      Some(arc) => SolveResult::SolvedPoint(arc.point_at(t.max(0.0).min(1.0))),
+      None => SolveResult::Request(ToCompute::Arc(*arc_ent)),
+    },
+
+    // A point on a conic follows the conic's own parametrization
+    SymbolicPoint::OnConic(conic_ent, t) => match solved.conic(*conic_ent) {
+      Some(conic) => SolveResult::SolvedPoint(conic.point_at(*t)),
+      None => SolveResult::Request(ToCompute::Conic(*conic_ent)),
+    },
+
+    // Functions are not solved, but the points they reference have to be
+    SymbolicPoint::OnFunction(function_ent, x) => match defs.sym_functions.get(*function_ent) {
+      Some(sym_function) => match sym_function.bind(|ent| solved.point(ent)) {
+        Ok(sym_function) => match sym_function.point_at(*x) {
+          Some(p) => SolveResult::SolvedPoint(p),
+          None => SolveResult::Undefined,
+        },
+        Err(point_ent) => SolveResult::Request(ToCompute::Point(point_ent)),
+      },
+      None => SolveResult::Request(ToCompute::Function(*function_ent)),
+    },
+
+    // The intersection closest to the x it was created at
+    SymbolicPoint::FunctionLineIntersect(function_ent, line_ent, x) => match solved.line(*line_ent) {
+      Some(line) => match defs.sym_functions.get(*function_ent) {
+        Some(sym_function) => match sym_function.bind(|ent| solved.point(ent)) {
+          Ok(sym_function) => match sym_function.intersect_line(line, *x, FUNCTION_INTERSECT_RADIUS) {
+            Some(p) => SolveResult::SolvedPoint(p),
+            None => SolveResult::Undefined,
           },
-          None => SolveResult::Request(ToCompute::Line(*l1_ent)),
+          Err(point_ent) => SolveResult::Request(ToCompute::Point(point_ent)),
         },
+        None => SolveResult::Request(ToCompute::Function(*function_ent)),
       },
-      None => panic!("[solver_system] Could not find to compute point"),
+      None => SolveResult::Request(ToCompute::Line(*line_ent)),
+    },
+
+    // Same as function, the curve is already known. The point stays within
+    // the curve's range
+    SymbolicPoint::OnCurve(curve_ent, t) => match defs.sym_curves.get(*curve_ent) {
+      Some(sym_curve) => {
+        let (t_min, t_max) = sym_curve.range();
+        match sym_curve.point_at(t.max(t_min).min(t_max)) {
+          Some(p) => SolveResult::SolvedPoint(p),
+          None => SolveResult::Undefined,
+        }
+      },
+      None => SolveResult::Request(ToCompute::Curve(*curve_ent)),
     },
   }
 }
 
-fn solve_line<'a>(
-  sym_lines: &ReadStorage<'a, SymbolicLine>,
-  points: &mut WriteStorage<'a, Point>,
-  lines: &mut WriteStorage<'a, Line>,
-  ent: Entity,
-) -> SolveResult {
-
-  // First check the line is already computed
-  match lines.get(ent) {
-    Some(_) => SolveResult::AlreadyComputed,
-    None => match sym_lines.get(ent) {
-      Some(sym) => match sym {
-
-        // If the line is constructed from two points, then we require the two
-        // points to be computed first. After that the line is originated from
-        // point 1 to the direction of point 2.
-        SymbolicLine::TwoPoints(p1_ent, p2_ent) => match points.get(*p1_ent) {
-          Some(pos_1) => match points.get(*p2_ent) {
-            Some(pos_2) => {
-              let origin = *pos_1;
-              let direction = (*pos_2 - *pos_1).normalized();
-              SolveResult::SolvedLine(Line { origin, direction })
-            },
-            None => SolveResult::Request(ToCompute::Point(*p2_ent))
-          },
-          None => SolveResult::Request(ToCompute::Point(*p1_ent))
+fn solve_line<S: Solved>(defs: &Definitions, solved: &S, sym: &SymbolicLine) -> SolveResult {
+  match sym {
+
+    // If the line is constructed from two points, then we require the two
+    // points to be computed first. After that the line is originated from
+    // point 1 to the direction of point 2. There is no line through two
+    // points at the same position.
+    SymbolicLine::TwoPoints(p1_ent, p2_ent) => match solved.point(*p1_ent) {
+      Some(pos_1) => match solved.point(*p2_ent) {
+        Some(pos_2) if pos_1 == pos_2 => SolveResult::Undefined,
+        Some(pos_2) => {
+          let origin = pos_1;
+          let direction = (pos_2 - pos_1).normalized();
+          SolveResult::SolvedLine(Line { origin, direction })
         },
+        None => SolveResult::Request(ToCompute::Point(*p2_ent))
+      },
+      None => SolveResult::Request(ToCompute::Point(*p1_ent))
+    },
 
-        SymbolicLine::Parallel(line_ent, point_ent) => match points.get(*point_ent) {
-          Some(pos) => match lines.get(*line_ent) {
-            Some(Line { direction, .. }) => SolveResult::SolvedLine(Line { origin: *pos, direction: *direction }),
-            None => SolveResult::Request(ToCompute::Line(*line_ent))
-          },
-          None => SolveResult::Request(ToCompute::Point(*point_ent))
-        }
+    SymbolicLine::Parallel(line_ent, point_ent) => match solved.point(*point_ent) {
+      Some(pos) => match solved.line(*line_ent) {
+        Some(Line { direction, .. }) => SolveResult::SolvedLine(Line { origin: pos, direction }),
+        None => SolveResult::Request(ToCompute::Line(*line_ent))
+      },
+      None => SolveResult::Request(ToCompute::Point(*point_ent))
+    },
+
+    SymbolicLine::Perpendicular(line_ent, point_ent) => match solved.point(*point_ent) {
+      Some(pos) => match solved.line(*line_ent) {
+        Some(Line { direction, .. }) => SolveResult::SolvedLine(Line { origin: pos, direction: vec2![-direction.y, direction.x] }),
+        None => SolveResult::Request(ToCompute::Line(*line_ent))
+      },
+      None => SolveResult::Request(ToCompute::Point(*point_ent))
+    },
+
+    // There is no tangent through a point inside the circle
+    SymbolicLine::Tangent(point_ent, arc_ent, left) => match solved.point(*point_ent) {
+      Some(pos) => match solved.arc(*arc_ent) {
+        Some(arc) => match arc.tangent_from(pos, *left) {
+          Some(line) => SolveResult::SolvedLine(line),
+          None => SolveResult::Undefined,
+        },
+        None => SolveResult::Request(ToCompute::Arc(*arc_ent)),
       },
-      None => panic!("[solver_system] Could not find to compute line"),
+      None => SolveResult::Request(ToCompute::Point(*point_ent)),
+    },
+
+    // The arc is the one the point is on, which the point already depends on
+    SymbolicLine::TangentAt(point_ent) => match (solved.point(*point_ent), defs.sym_points.get(*point_ent)) {
+      (Some(pos), Some(SymbolicPoint::OnArc(arc_ent, _))) => match solved.arc(*arc_ent) {
+        Some(arc) => match arc.tangent_at(pos) {
+          Some(line) => SolveResult::SolvedLine(line),
+          None => SolveResult::Undefined,
+        },
+        None => SolveResult::Request(ToCompute::Arc(*arc_ent)),
+      },
+      (Some(_), _) => SolveResult::Undefined,
+      (None, _) => SolveResult::Request(ToCompute::Point(*point_ent)),
     },
   }
 }
 
+fn solve_arc<S: Solved>(solved: &S, sym: &SymbolicArc) -> SolveResult {
+
+  // Both kinds of arcs demand three points. Degenerated arcs (zero radius
+  // or collinear points) are undefined
+  let (p1_ent, p2_ent, p3_ent) = match sym {
+    SymbolicArc::CenterTwoPoints(p1, p2, p3) | SymbolicArc::ThreePoints(p1, p2, p3) => (*p1, *p2, *p3),
+  };
+  match (solved.point(p1_ent), solved.point(p2_ent), solved.point(p3_ent)) {
+    (Some(pos_1), Some(pos_2), Some(pos_3)) => {
+      let maybe_arc = match sym {
+        SymbolicArc::CenterTwoPoints(_, _, _) => Arc::from_center_two_points(pos_1, pos_2, pos_3),
+        SymbolicArc::ThreePoints(_, _, _) => Arc::from_three_points(pos_1, pos_2, pos_3),
+      };
+      match maybe_arc {
+        Some(arc) => SolveResult::SolvedArc(arc),
+        None => SolveResult::Undefined,
+      }
+    },
+    (None, _, _) => SolveResult::Request(ToCompute::Point(p1_ent)),
+    (_, None, _) => SolveResult::Request(ToCompute::Point(p2_ent)),
+    (_, _, None) => SolveResult::Request(ToCompute::Point(p3_ent)),
+  }
+}
+
+fn solve_conic<S: Solved>(solved: &S, sym: &SymbolicConic) -> SolveResult {
+
+  // Gather all the points the conic depends on, requesting the first
+  // one that is not computed yet
+  let point_ents = match sym {
+    SymbolicConic::Ellipse(f1, f2, p) | SymbolicConic::Hyperbola(f1, f2, p) => vec![*f1, *f2, *p],
+    SymbolicConic::Parabola(focus, _) => vec![*focus],
+    SymbolicConic::FivePoints(p1, p2, p3, p4, p5) => vec![*p1, *p2, *p3, *p4, *p5],
+  };
+  let mut positions = vec![];
+  for point_ent in point_ents {
+    match solved.point(point_ent) {
+      Some(pos) => positions.push(pos),
+      None => return SolveResult::Request(ToCompute::Point(point_ent)),
+    }
+  }
+
+  // Degenerated conics are undefined
+  let maybe_conic = match sym {
+    SymbolicConic::Ellipse(_, _, _) => Conic::ellipse(positions[0], positions[1], positions[2]),
+    SymbolicConic::Hyperbola(_, _, _) => Conic::hyperbola(positions[0], positions[1], positions[2]),
+    SymbolicConic::Parabola(_, line_ent) => match solved.line(*line_ent) {
+      Some(line) => Conic::parabola(positions[0], line),
+      None => return SolveResult::Request(ToCompute::Line(*line_ent)),
+    },
+    SymbolicConic::FivePoints(_, _, _, _, _) => Conic::through_five_points([
+      positions[0], positions[1], positions[2], positions[3], positions[4],
+    ]),
+  };
+  match maybe_conic {
+    Some(conic) => SolveResult::SolvedConic(conic),
+    None => SolveResult::Undefined,
+  }
+}
+
 pub struct SolverSystem {
   need_initialize: bool,
   sketch_events_reader_id: Option<SketchEventReader>,
@@ -136,10 +382,17 @@ impl<'a> System<'a> for SolverSystem {
     Entities<'a>,
     Read<'a, DependencyGraph>,
     Read<'a, SketchEventChannel>,
+    Write<'a, SolverReport>,
     ReadStorage<'a, SymbolicPoint>,
     ReadStorage<'a, SymbolicLine>,
+    ReadStorage<'a, SymbolicArc>,
+    ReadStorage<'a, SymbolicConic>,
+    ReadStorage<'a, SymbolicFunction>,
+    ReadStorage<'a, SymbolicCurve>,
     WriteStorage<'a, Point>,
     WriteStorage<'a, Line>,
+    WriteStorage<'a, Arc>,
+    WriteStorage<'a, Conic>,
   );
 
   fn setup(&mut self, world: &mut World) {
@@ -151,44 +404,61 @@ impl<'a> System<'a> for SolverSystem {
     entities,
     dependency_graph,
     sketch_events,
+    mut solver_report,
     sym_points,
     sym_lines,
+    sym_arcs,
+    sym_conics,
+    sym_functions,
+    sym_curves,
     mut points,
     mut lines,
+    mut arcs,
+    mut conics,
   ): Self::SystemData) {
-    let mut stack = vec![];
+    let mut dirty = HashSet::new();
+    let mut inserted_parents = HashMap::new();
 
     // Note: There are two crucial parts:
     //  1. Determine which entities to compute
     //  2. Compute
-    // The algorithm is then basically
-    //  1. Push all the entities to compute into the stack
-    //  2. Solve the entities sequentially
     // For 1, we need
     //  - When starting up the program, we need all geometries get compute
-    //    from scratch. So we push everything onto the stack.
+    //    from scratch. So everything is dirty.
     //  - Else, we read through the sketch events and make any changes
-    //    - If inserted new, then just add that new thing to the stack
-    //    - If updated, then push all descendents of that updated geom onto
-    //      the stack
-    //    - If removed, we don't really care since other algorithms should
-    //      already removed all the descendents
+    //    - If inserted new, then just that new thing is dirty
+    //    - If updated or redefined, then all descendents of that geom are
+    //      dirty
+    //    - Functions are never solved, but they are kept among the dirty
+    //      entities so that the points they reference come before the points
+    //      on them
+    //    - If removed, other algorithms should already have removed all
+    //      the descendents, so only the errors about it are dropped
+    // For 2, the dirty entities are ordered so that parents come first, and
+    // then each of them is solved once
 
     // This happens when starting up the program
     if self.need_initialize {
       self.need_initialize = false; // set to false afterwards
 
-      // Fisrt push all the lines into stack
       for (ent, _) in (&*entities, &sym_lines).join() {
         lines.remove(ent);
-        stack.push(ToCompute::Line(ent));
+        dirty.insert(ent);
+      }
+      for (ent, _) in (&*entities, &sym_arcs).join() {
+        arcs.remove(ent);
+        dirty.insert(ent);
+      }
+      for (ent, _) in (&*entities, &sym_conics).join() {
+        conics.remove(ent);
+        dirty.insert(ent);
       }
-
-      // Then push all the points into stack
-      // As we want to first calculate points
       for (ent, _) in (&*entities, &sym_points).join() {
         points.remove(ent);
-        stack.push(ToCompute::Point(ent));
+        dirty.insert(ent);
+      }
+      for (ent, _) in (&*entities, &sym_functions).join() {
+        dirty.insert(ent);
       }
     } else {
 
@@ -197,21 +467,38 @@ impl<'a> System<'a> for SolverSystem {
         for event in sketch_events.read(sketch_events_reader_id) {
           match event {
             SketchEvent::Insert(entity, geom) => match geom {
-              Geometry::Point(_, _) => stack.push(ToCompute::Point(*entity)),
-              Geometry::Line(_, _) => stack.push(ToCompute::Line(*entity)),
+              Geometry::Point(_, _) | Geometry::Line(_, _) | Geometry::Arc(_, _) | Geometry::Conic(_, _) | Geometry::Function(_, _) => {
+                dirty.insert(*entity);
+
+                // The dependency graph may not know about it yet
+                inserted_parents.insert(*entity, geom.parents());
+              },
+              Geometry::Curve(_, _) => (), // Curves need no solving
+              Geometry::Locus(_, _) => (), // Loci are solved by the locus system
             },
-            SketchEvent::Remove(_, _) => (), // Do nothing since they are already removed
+            // The removed geometries are already gone, so are the errors
+            // about them
+            SketchEvent::Remove(ent, _) => solver_report.forget(*ent),
             SketchEvent::Select(_) | SketchEvent::Deselect(_) => (), // Do nothing to select/deselect event
-            SketchEvent::MovePoint(ent, _) => {
-              let dependents = dependency_graph.get_all_dependents(ent);
-              for dependent in dependents {
-                if let Some(_) = sym_points.get(dependent) {
+            SketchEvent::MovePoint(ent, _) | SketchEvent::Redefine(ent, _, _) => {
+              if let SketchEvent::Redefine(_, _, new_geom) = event {
+
+                // The dependency graph may still have the old parents
+                inserted_parents.insert(*ent, new_geom.parents());
+              }
+              for dependent in dependency_graph.get_all_dependents(ent) {
+                if sym_points.get(dependent).is_some() {
                   points.remove(dependent);
-                  stack.push(ToCompute::Point(dependent));
-                } else if let Some(_) = sym_lines.get(dependent) {
+                } else if sym_lines.get(dependent).is_some() {
                   lines.remove(dependent);
-                  stack.push(ToCompute::Line(dependent));
+                } else if sym_arcs.get(dependent).is_some() {
+                  arcs.remove(dependent);
+                } else if sym_conics.get(dependent).is_some() {
+                  conics.remove(dependent);
+                } else if sym_functions.get(dependent).is_none() {
+                  continue;
                 }
+                dirty.insert(dependent);
               }
             }
           }
@@ -221,23 +508,268 @@ impl<'a> System<'a> for SolverSystem {
       }
     }
 
-    // Calculate all the elements in the stack
-    while !stack.is_empty() {
-      let to_comp = stack.pop().unwrap();
-      let (ent, result) = match to_comp {
-        ToCompute::Point(ent) => (ent, solve_point(&sym_points, &mut points, &mut lines, ent)),
-        ToCompute::Line(ent) => (ent, solve_line(&sym_lines, &mut points, &mut lines, ent)),
-      };
-      match result {
-        SolveResult::AlreadyComputed => (),
-        SolveResult::Undefined => (),
-        SolveResult::SolvedLine(l) => insert_line(&mut lines, ent, l),
-        SolveResult::SolvedPoint(p) => insert_point(&mut points, ent, p),
-        SolveResult::Request(req) => {
-          stack.push(to_comp);
-          stack.push(req);
-        },
-      }
+    if dirty.is_empty() {
+      return;
     }
+
+    // Order the dirty entities. The ones in a cycle cannot be ordered and
+    // are left unsolved
+    let (order, stuck) = topological_order(&dirty, |ent| match inserted_parents.get(ent) {
+      Some(parents) => parents.clone(),
+      None => dependency_graph.get_direct_parents(ent).map_or(vec![], |parents| parents.iter().cloned().collect()),
+    });
+    let defs = Definitions {
+      sym_points: &sym_points,
+      sym_lines: &sym_lines,
+      sym_arcs: &sym_arcs,
+      sym_conics: &sym_conics,
+      sym_functions: &sym_functions,
+      sym_curves: &sym_curves,
+    };
+    let mut store = LiveStore { points: &mut points, lines: &mut lines, arcs: &mut arcs, conics: &mut conics };
+    solver_report.errors = solve_in_order(&defs, &mut store, &order);
+    if !stuck.is_empty() {
+      solver_report.errors.push(SolveError::Cycle(stuck));
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::time::Instant;
+  use crate::{
+    utilities::{Vector2, Color},
+    components::{PointStyle, LineStyle},
+    resources::events::MovePoint,
+    systems::cache_managers::DependencyGraphCache,
+  };
+  use super::*;
+
+  fn world() -> World {
+    let mut world = World::new();
+    world.register::<SymbolicPoint>();
+    world.register::<SymbolicLine>();
+    world.register::<SymbolicArc>();
+    world.register::<SymbolicConic>();
+    world.register::<SymbolicFunction>();
+    world.register::<SymbolicCurve>();
+    world.register::<Point>();
+    world.register::<Line>();
+    world.register::<Arc>();
+    world.register::<Conic>();
+    world
+  }
+
+  /// Solve every entity of the world once, returning the errors
+  fn solve_world(world: &World, dirty: &HashSet<Entity>) -> Vec<SolveError> {
+    let (sym_points, sym_lines, sym_arcs, sym_conics) = (world.read_storage(), world.read_storage(), world.read_storage(), world.read_storage());
+    let (sym_functions, sym_curves) = (world.read_storage(), world.read_storage());
+    let defs = Definitions {
+      sym_points: &sym_points,
+      sym_lines: &sym_lines,
+      sym_arcs: &sym_arcs,
+      sym_conics: &sym_conics,
+      sym_functions: &sym_functions,
+      sym_curves: &sym_curves,
+    };
+    let (order, stuck) = topological_order(dirty, |ent| {
+      if let Some(sym) = defs.sym_points.get(*ent) { sym.parents() }
+      else if let Some(sym) = defs.sym_lines.get(*ent) { sym.parents() }
+      else if let Some(sym) = defs.sym_arcs.get(*ent) { sym.parents() }
+      else if let Some(sym) = defs.sym_functions.get(*ent) { sym.references.clone() }
+      else { vec![] }
+    });
+    let (mut points, mut lines, mut arcs, mut conics) = (world.write_storage(), world.write_storage(), world.write_storage(), world.write_storage());
+    let mut store = LiveStore { points: &mut points, lines: &mut lines, arcs: &mut arcs, conics: &mut conics };
+    let mut errors = solve_in_order(&defs, &mut store, &order);
+    if !stuck.is_empty() {
+      errors.push(SolveError::Cycle(stuck));
+    }
+    errors
+  }
+
+  #[test]
+  fn test_dangling_reference() {
+    let mut world = world();
+    let missing = world.create_entity().build();
+    let p = world.create_entity().with(SymbolicPoint::Free(vec2![0., 0.])).build();
+    let line = world.create_entity().with(SymbolicLine::TwoPoints(p, missing)).build();
+    let on_line = world.create_entity().with(SymbolicPoint::OnLine(line, 1.)).build();
+    let on_function = world.create_entity().with(SymbolicPoint::OnFunction(missing, 0.)).build();
+
+    let errors = solve_world(&world, &[p, line, on_line, on_function].iter().cloned().collect());
+    assert_eq!(errors.len(), 2);
+    assert!(errors.contains(&SolveError::Dangling(line, missing)));
+    assert!(errors.contains(&SolveError::Dangling(on_function, missing)));
+    assert!(world.read_storage::<Point>().get(p).is_some());
+    assert!(world.read_storage::<Line>().get(line).is_none());
+    assert!(world.read_storage::<Point>().get(on_line).is_none());
+  }
+
+  #[test]
+  fn test_cycle() {
+    let mut world = world();
+    let p = world.create_entity().with(SymbolicPoint::Free(vec2![0., 0.])).build();
+    let (l1, l2) = (world.create_entity().build(), world.create_entity().build());
+    let q = world.create_entity().with(SymbolicPoint::LineLineIntersect(l1, l2)).build();
+    let mut sym_lines = world.write_storage::<SymbolicLine>();
+    sym_lines.insert(l1, SymbolicLine::TwoPoints(p, q)).unwrap();
+    sym_lines.insert(l2, SymbolicLine::Parallel(l1, p)).unwrap();
+    drop(sym_lines);
+
+    let mut errors = solve_world(&world, &[p, l1, l2, q].iter().cloned().collect());
+    assert_eq!(errors.len(), 1);
+    if let Some(SolveError::Cycle(ents)) = errors.get_mut(0) {
+      ents.sort();
+      assert_eq!(*ents, vec![l1, l2, q]);
+    } else {
+      panic!("expected a cycle");
+    }
+  }
+
+  #[test]
+  fn test_line_through_one_point() {
+    let mut world = world();
+    let p = world.create_entity().with(SymbolicPoint::Free(vec2![1., 1.])).build();
+    let q = world.create_entity().with(SymbolicPoint::Free(vec2![1., 1.])).build();
+    let line = world.create_entity().with(SymbolicLine::TwoPoints(p, p)).build();
+    let through_same = world.create_entity().with(SymbolicLine::TwoPoints(p, q)).build();
+    let on_line = world.create_entity().with(SymbolicPoint::OnLine(line, 1.)).build();
+
+    assert_eq!(solve_world(&world, &[p, q, line, through_same, on_line].iter().cloned().collect()), vec![]);
+    assert!(world.read_storage::<Line>().get(line).is_none());
+    assert!(world.read_storage::<Line>().get(through_same).is_none());
+    assert!(world.read_storage::<Point>().get(on_line).is_none());
+  }
+
+  #[test]
+  fn test_tangents() {
+    let mut world = world();
+    let center = world.create_entity().with(SymbolicPoint::Free(vec2![0., 0.])).build();
+    let through = world.create_entity().with(SymbolicPoint::Free(vec2![1., 0.])).build();
+    let circle = world.create_entity().with(SymbolicArc::CenterTwoPoints(center, through, through)).build();
+    let outside = world.create_entity().with(SymbolicPoint::Free(vec2![2., 0.])).build();
+    let inside = world.create_entity().with(SymbolicPoint::Free(vec2![0.5, 0.])).build();
+    let on_circle = world.create_entity().with(SymbolicPoint::OnArc(circle, 0.25)).build();
+    let tangents : Vec<Entity> = vec![
+      SymbolicLine::Tangent(outside, circle, true),
+      SymbolicLine::Tangent(outside, circle, false),
+      SymbolicLine::Tangent(inside, circle, true),
+      SymbolicLine::TangentAt(on_circle),
+    ].into_iter().map(|sym| world.create_entity().with(sym).build()).collect();
+
+    let mut dirty : HashSet<Entity> = [center, through, circle, outside, inside, on_circle].iter().cloned().collect();
+    dirty.extend(tangents.iter());
+    assert_eq!(solve_world(&world, &dirty), vec![]);
+    let lines = world.read_storage::<Line>();
+    let (left, right) = (lines.get(tangents[0]).unwrap(), lines.get(tangents[1]).unwrap());
+    assert!(left.direction.y < 0.0 && right.direction.y > 0.0);
+    assert!(lines.get(tangents[2]).is_none());
+    let at = lines.get(tangents[3]).unwrap();
+    assert!((at.origin - vec2![0., 1.]).magnitude() < 1e-9 && at.direction.y.abs() < 1e-9);
+  }
+
+  #[test]
+  fn test_function_references() {
+    let mut world = world();
+    let a = world.create_entity().with(SymbolicPoint::Free(vec2![1., 2.])).build();
+    let expression = SymbolicFunction::parse_expression("p0_y * (x - p0_x)").unwrap();
+    let function = world.create_entity().with(SymbolicFunction { expression, references: vec![a] }).build();
+    let on_function = world.create_entity().with(SymbolicPoint::OnFunction(function, 3.)).build();
+    let chained = world.create_entity().with(SymbolicFunction {
+      expression: SymbolicFunction::parse_expression("p0_x").unwrap(),
+      references: vec![on_function],
+    }).build();
+    let on_chained = world.create_entity().with(SymbolicPoint::OnFunction(chained, 0.)).build();
+
+    let dirty = [on_chained, chained, on_function, function, a].iter().cloned().collect();
+    assert_eq!(solve_world(&world, &dirty), vec![]);
+    let points = world.read_storage::<Point>();
+    assert_eq!(points.get(on_function), Some(&vec2![3., 4.]));
+    assert_eq!(points.get(on_chained), Some(&vec2![0., 3.]));
+  }
+
+  #[test]
+  fn test_system_follows_events() {
+    let mut world = world();
+    let mut dispatcher = DispatcherBuilder::new()
+      .with(DependencyGraphCache::default(), "dependency_graph_cache", &[])
+      .with(SolverSystem::default(), "solver_system", &["dependency_graph_cache"])
+      .build();
+    dispatcher.setup(&mut world);
+    dispatcher.dispatch(&world);
+
+    // Geometries are created the way the systems creating them do
+    let (point_style, line_style) = (PointStyle { radius: 5., color: Color::black() }, LineStyle { width: 2., color: Color::black() });
+    let create_point = |world: &mut World, sym: SymbolicPoint| {
+      let entity = world.create_entity().with(sym).build();
+      world.write_resource::<SketchEventChannel>().single_write(SketchEvent::Insert(entity, Geometry::Point(sym, point_style)));
+      entity
+    };
+    let p = create_point(&mut world, SymbolicPoint::Free(vec2![0., 0.]));
+    let q = create_point(&mut world, SymbolicPoint::Free(vec2![2., 0.]));
+    let line = world.create_entity().with(SymbolicLine::TwoPoints(p, q)).build();
+    world.write_resource::<SketchEventChannel>().single_write(SketchEvent::Insert(line, Geometry::Line(SymbolicLine::TwoPoints(p, q), line_style)));
+    let on_line = create_point(&mut world, SymbolicPoint::OnLine(line, 1.));
+    dispatcher.dispatch(&world);
+    assert_eq!(world.read_storage::<Point>().get(on_line), Some(&vec2![1., 0.]));
+
+    // Moving a point moves the points depending on it
+    world.write_storage::<SymbolicPoint>().insert(q, SymbolicPoint::Free(vec2![0., 2.])).unwrap();
+    world.write_resource::<SketchEventChannel>().single_write(SketchEvent::MovePoint(q, MovePoint::Free(vec2![2., 0.], vec2![0., 2.])));
+    dispatcher.dispatch(&world);
+    assert_eq!(world.read_storage::<Point>().get(on_line), Some(&vec2![0., 1.]));
+
+    // The line is redefined on a point created along with it
+    let s = create_point(&mut world, SymbolicPoint::Free(vec2![-2., 0.]));
+    let (old_geom, new_geom) = (Geometry::Line(SymbolicLine::TwoPoints(p, q), line_style), Geometry::Line(SymbolicLine::TwoPoints(p, s), line_style));
+    world.write_storage::<SymbolicLine>().insert(line, SymbolicLine::TwoPoints(p, s)).unwrap();
+    world.write_resource::<SketchEventChannel>().single_write(SketchEvent::Redefine(line, old_geom, new_geom));
+    dispatcher.dispatch(&world);
+    assert_eq!(world.read_storage::<Point>().get(on_line), Some(&vec2![-1., 0.]));
+    assert!(world.read_resource::<SolverReport>().errors.is_empty());
+
+    // The errors about a geometry go away with it
+    let missing = world.create_entity().build();
+    let dangling = create_point(&mut world, SymbolicPoint::OnFunction(missing, 0.));
+    dispatcher.dispatch(&world);
+    assert_eq!(world.read_resource::<SolverReport>().errors, vec![SolveError::Dangling(dangling, missing)]);
+    world.delete_entity(dangling).unwrap();
+    world.write_resource::<SketchEventChannel>().single_write(SketchEvent::Remove(dangling, Geometry::Point(SymbolicPoint::OnFunction(missing, 0.), point_style)));
+    dispatcher.dispatch(&world);
+    assert!(world.read_resource::<SolverReport>().errors.is_empty());
+  }
+
+  /// Run with `cargo test --release bench_solve -- --ignored --nocapture`
+  #[test]
+  #[ignore]
+  fn bench_solve_10k() {
+    let mut world = world();
+    let count = 2500;
+
+    // Free points, lines through consecutive ones, a point on every line,
+    // and the intersections of consecutive lines: about 10k geometries
+    let mut dirty = HashSet::new();
+    let free : Vec<Entity> = (0..count).map(|i| {
+      let (x, y) = (i as f64, ((i * i) % 97) as f64);
+      world.create_entity().with(SymbolicPoint::Free(vec2![x, y])).build()
+    }).collect();
+    let lines : Vec<Entity> = free.windows(2).map(|p| {
+      world.create_entity().with(SymbolicLine::TwoPoints(p[0], p[1])).build()
+    }).collect();
+    let on_lines : Vec<Entity> = lines.iter().map(|l| {
+      world.create_entity().with(SymbolicPoint::OnLine(*l, 0.5)).build()
+    }).collect();
+    let intersections : Vec<Entity> = lines.windows(2).map(|l| {
+      world.create_entity().with(SymbolicPoint::LineLineIntersect(l[0], l[1])).build()
+    }).collect();
+    dirty.extend(free.iter().chain(lines.iter()).chain(on_lines.iter()).chain(intersections.iter()));
+
+    let start = Instant::now();
+    let errors = solve_world(&world, &dirty);
+    println!("Solved {} geometries in {:?}", dirty.len(), start.elapsed());
+    assert!(errors.is_empty());
+    assert_eq!(world.read_storage::<Line>().join().count(), lines.len());
+    assert!(on_lines.iter().all(|p| world.read_storage::<Point>().get(*p).is_some()));
   }
 }
\ No newline at end of file
@@ -3,6 +3,7 @@ use crate::{
   utilities::Key,
   resources::{
     InputState,
+    PromptState,
     geometry::CreateLineData
   }
 };
@@ -21,11 +22,12 @@ pub struct AbortCreateLineViaKeyboard;
 impl<'a> System<'a> for AbortCreateLineViaKeyboard {
   type SystemData = (
     Read<'a, InputState>,
+    Read<'a, PromptState>,
     Write<'a, CreateLineData>,
   );
 
-  fn run(&mut self, (input_state, mut create_line_data): Self::SystemData) {
-    if input_state.keyboard.just_activated(Key::Escape) {
+  fn run(&mut self, (input_state, prompt_state, mut create_line_data): Self::SystemData) {
+    if input_state.keyboard.just_activated(Key::Escape) && !prompt_state.is_active() {
       if create_line_data.maybe_first_point.is_some() {
         create_line_data.maybe_first_point = None;
       }
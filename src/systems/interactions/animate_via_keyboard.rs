@@ -0,0 +1,43 @@
+use specs::prelude::*;
+use crate::{
+  utilities::Key,
+  resources::{
+    InputState,
+    PromptState,
+    events::{AnimationEvent, AnimationEventChannel},
+  },
+};
+
+pub struct AnimateViaKeyboard;
+
+impl<'a> System<'a> for AnimateViaKeyboard {
+  type SystemData = (
+    Read<'a, InputState>,
+    Read<'a, PromptState>,
+    Write<'a, AnimationEventChannel>,
+  );
+
+  fn run(&mut self, (input_state, prompt_state, mut animation_event_channel): Self::SystemData) {
+    if prompt_state.is_active() {
+      return;
+    }
+
+    if input_state.keyboard.is_activated(Key::LCommand) || input_state.keyboard.is_activated(Key::RCommand) {
+      if input_state.keyboard.just_activated(Key::M) {
+        animation_event_channel.single_write(AnimationEvent::ToggleSelected);
+      } else if input_state.keyboard.just_activated(Key::Period) {
+        animation_event_channel.single_write(AnimationEvent::Stop);
+      } else if input_state.keyboard.just_activated(Key::Up) {
+        animation_event_channel.single_write(AnimationEvent::Faster);
+      } else if input_state.keyboard.just_activated(Key::Down) {
+        animation_event_channel.single_write(AnimationEvent::Slower);
+      } else if input_state.keyboard.just_activated(Key::R) {
+        animation_event_channel.single_write(AnimationEvent::Reverse);
+      } else if input_state.keyboard.just_activated(Key::J) {
+        animation_event_channel.single_write(AnimationEvent::ToggleBounce);
+      }
+    } else if input_state.keyboard.just_activated(Key::Space) {
+      animation_event_channel.single_write(AnimationEvent::TogglePlay);
+    }
+  }
+}
\ No newline at end of file
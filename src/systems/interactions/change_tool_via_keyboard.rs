@@ -4,6 +4,7 @@ pub use crate::{
   utilities::Key,
   resources::{
     InputState,
+    PromptState,
     Tool,
     events::{ToolChangeEventChannel, ToolChangeEvent},
   },
@@ -14,10 +15,18 @@ pub struct ChangeToolViaKeyboard;
 impl<'a> System<'a> for ChangeToolViaKeyboard {
   type SystemData = (
     Read<'a, InputState>,
+    Read<'a, PromptState>,
     Write<'a, ToolChangeEventChannel>,
   );
 
-  fn run(&mut self, (input_state, mut tool_change_events): Self::SystemData) {
+  fn run(&mut self, (input_state, prompt_state, mut tool_change_events): Self::SystemData) {
+
+    // Keys held with command are shortcuts (e.g. Command + A), not tool changes.
+    // Keys typed into a prompt are not tool changes either
+    if prompt_state.is_active() || input_state.keyboard.is_activated(Key::LCommand) || input_state.keyboard.is_activated(Key::RCommand) {
+      return;
+    }
+
     if input_state.keyboard.just_activated(Key::S) {
       tool_change_events.single_write(ToolChangeEvent(Tool::Select));
     } else if input_state.keyboard.just_activated(Key::P) {
@@ -26,6 +35,18 @@ impl<'a> System<'a> for ChangeToolViaKeyboard {
       tool_change_events.single_write(ToolChangeEvent(Tool::Line));
     } else if input_state.keyboard.just_activated(Key::C) {
       tool_change_events.single_write(ToolChangeEvent(Tool::Circle));
+    } else if input_state.keyboard.just_activated(Key::A) && input_state.keyboard.is_shift_activated() {
+      tool_change_events.single_write(ToolChangeEvent(Tool::CenterArc));
+    } else if input_state.keyboard.just_activated(Key::A) {
+      tool_change_events.single_write(ToolChangeEvent(Tool::Arc));
+    } else if input_state.keyboard.just_activated(Key::E) {
+      tool_change_events.single_write(ToolChangeEvent(Tool::Ellipse));
+    } else if input_state.keyboard.just_activated(Key::H) {
+      tool_change_events.single_write(ToolChangeEvent(Tool::Hyperbola));
+    } else if input_state.keyboard.just_activated(Key::O) {
+      tool_change_events.single_write(ToolChangeEvent(Tool::Conic));
+    } else if input_state.keyboard.just_activated(Key::B) {
+      tool_change_events.single_write(ToolChangeEvent(Tool::Parabola));
     } else if input_state.keyboard.just_activated(Key::V) {
       tool_change_events.single_write(ToolChangeEvent(Tool::ViewportDrag));
     }
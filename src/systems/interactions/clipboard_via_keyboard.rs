@@ -0,0 +1,40 @@
+use specs::prelude::*;
+use crate::{
+  utilities::Key,
+  resources::{
+    InputState,
+    PromptState,
+    events::{GeometryAction, GeometryActionChannel},
+  },
+};
+
+/// # Clipboard Via Keyboard
+///
+/// Cmd+C copies the selection, Cmd+X copies then removes it, and Cmd+V
+/// pastes the copy.
+pub struct ClipboardViaKeyboard;
+
+impl<'a> System<'a> for ClipboardViaKeyboard {
+  type SystemData = (
+    Read<'a, InputState>,
+    Read<'a, PromptState>,
+    Write<'a, GeometryActionChannel>,
+  );
+
+  fn run(&mut self, (input_state, prompt_state, mut geometry_action_channel): Self::SystemData) {
+    if prompt_state.is_active() {
+      return;
+    }
+
+    if input_state.keyboard.is_activated(Key::LCommand) || input_state.keyboard.is_activated(Key::RCommand) {
+      if input_state.keyboard.just_activated(Key::C) {
+        geometry_action_channel.single_write(GeometryAction::CopySelected);
+      } else if input_state.keyboard.just_activated(Key::X) {
+        geometry_action_channel.single_write(GeometryAction::CopySelected);
+        geometry_action_channel.single_write(GeometryAction::RemoveSelected);
+      } else if input_state.keyboard.just_activated(Key::V) {
+        geometry_action_channel.single_write(GeometryAction::Paste);
+      }
+    }
+  }
+}
\ No newline at end of file
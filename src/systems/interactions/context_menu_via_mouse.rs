@@ -0,0 +1,129 @@
+use specs::prelude::*;
+use crate::{
+  utilities::Key,
+  resources::{
+    InputState,
+    Viewport, ViewportTransform,
+    SpatialHashTable,
+    ContextMenu, MenuEntry,
+    events::{
+      MouseEvent, MouseEventChannel, MouseEventReader,
+      GeometryAction, GeometryActionChannel,
+      SketchEvent, SketchEventChannel,
+    },
+  },
+  components::{SymbolicPoint, Point, Line, Arc, Conic, Plot, Selected},
+};
+use super::helpers::hitting_object;
+
+static SELECT_DIST_THRES : f64 = 5.0; // Pixel
+
+#[derive(Default)]
+pub struct ContextMenuViaMouse {
+  mouse_event_reader: Option<MouseEventReader>,
+}
+
+/// # Context Menu Via Mouse
+///
+/// Right clicking something selects it, unless it is selected already, and
+/// opens the context menu for it. The entries act on the selection, except
+/// constructing a perpendicular or a tangent which is for the clicked line
+/// or arc. Pressing anywhere, or Escape, closes the menu. This needs to run
+/// after everything else using the mouse, so that they see the menu until
+/// it is closed.
+impl<'a> System<'a> for ContextMenuViaMouse {
+  type SystemData = (
+    Read<'a, InputState>,
+    Read<'a, MouseEventChannel>,
+    Read<'a, Viewport>,
+    Read<'a, SpatialHashTable<Entity>>,
+    Write<'a, ContextMenu>,
+    Write<'a, GeometryActionChannel>,
+    Write<'a, SketchEventChannel>,
+    ReadStorage<'a, SymbolicPoint>,
+    ReadStorage<'a, Point>,
+    ReadStorage<'a, Line>,
+    ReadStorage<'a, Arc>,
+    ReadStorage<'a, Conic>,
+    ReadStorage<'a, Plot>,
+    ReadStorage<'a, Selected>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.mouse_event_reader = Some(world.fetch_mut::<MouseEventChannel>().register_reader());
+  }
+
+  fn run(&mut self, (
+    input_state,
+    mouse_event_channel,
+    viewport,
+    spatial_table,
+    mut context_menu,
+    mut geometry_action_channel,
+    mut sketch_event_channel,
+    sym_points,
+    points,
+    lines,
+    arcs,
+    conics,
+    plots,
+    selected,
+  ): Self::SystemData) {
+    if let Some(reader_id) = &mut self.mouse_event_reader {
+      for event in mouse_event_channel.read(reader_id) {
+        match event {
+          MouseEvent::RightClick(mouse_pos) => {
+            match hitting_object(*mouse_pos, &viewport, &spatial_table, &points, &lines, &arcs, &conics, &plots, SELECT_DIST_THRES) {
+              Some(entity) => {
+                if selected.get(entity).is_none() {
+                  geometry_action_channel.single_write(GeometryAction::DeselectAllExcept(entity));
+                  sketch_event_channel.single_write(SketchEvent::Select(entity));
+                }
+
+                // Only what applies to the type of the clicked geometry
+                let mut entries = vec![MenuEntry::Delete, MenuEntry::Hide, MenuEntry::Style];
+                if lines.get(entity).is_some() {
+                  entries.push(MenuEntry::ConstructPerpendicular);
+                }
+                if arcs.get(entity).is_some() {
+                  entries.push(MenuEntry::Fill);
+                  entries.push(MenuEntry::ConstructTangent);
+                }
+                if let Some(SymbolicPoint::Free(_)) | Some(SymbolicPoint::OnLine(_, _)) = sym_points.get(entity) {
+                  entries.push(MenuEntry::Properties);
+                }
+                context_menu.open(entity, *mouse_pos, entries);
+              },
+              None => context_menu.close(),
+            }
+          },
+          MouseEvent::MouseDown(mouse_pos) if context_menu.is_active() => {
+            if let (Some(entry), Some(menu)) = (context_menu.entry_at(*mouse_pos), context_menu.get()) {
+              geometry_action_channel.single_write(match entry {
+                MenuEntry::Delete => GeometryAction::RemoveSelected,
+                MenuEntry::Hide => GeometryAction::HideSelected,
+                MenuEntry::Style => GeometryAction::RecolorSelected,
+                MenuEntry::Fill => GeometryAction::RefillSelected,
+                MenuEntry::ConstructPerpendicular => GeometryAction::ConstructPerpendicular(menu.entity, menu.position.to_virtual(&viewport)),
+                MenuEntry::ConstructTangent => GeometryAction::ConstructTangentAt(menu.entity, menu.position.to_virtual(&viewport)),
+                MenuEntry::Properties => GeometryAction::EditPoint(menu.entity),
+              });
+            }
+            context_menu.dismiss();
+          },
+          MouseEvent::MouseUp(_) if context_menu.is_active() && context_menu.get().is_none() => {
+            context_menu.close();
+          },
+          _ => (),
+        }
+      }
+    } else {
+      panic!("[context_menu_via_mouse] No reader id");
+    }
+
+    if input_state.keyboard.just_activated(Key::Escape) && context_menu.get().is_some() {
+      context_menu.close();
+    }
+  }
+}
\ No newline at end of file
@@ -0,0 +1,30 @@
+use specs::prelude::*;
+use crate::{
+  utilities::Key,
+  resources::{
+    InputState,
+    PromptState,
+    events::{GeometryAction, GeometryActionChannel},
+  },
+};
+
+pub struct CreateLocusViaKeyboard;
+
+impl<'a> System<'a> for CreateLocusViaKeyboard {
+  type SystemData = (
+    Read<'a, InputState>,
+    Read<'a, PromptState>,
+    Write<'a, GeometryActionChannel>,
+  );
+
+  fn run(&mut self, (input_state, prompt_state, mut geometry_action_channel): Self::SystemData) {
+    if prompt_state.is_active() {
+      return;
+    }
+
+    if (input_state.keyboard.is_activated(Key::LCommand) || input_state.keyboard.is_activated(Key::RCommand))
+      && input_state.keyboard.just_activated(Key::L) {
+        geometry_action_channel.single_write(GeometryAction::CreateLocusFromSelected);
+      }
+  }
+}
\ No newline at end of file
@@ -0,0 +1,49 @@
+use specs::prelude::*;
+use crate::{
+  utilities::Key,
+  resources::{
+    InputState,
+    PromptState,
+    PromptKind,
+    CustomToolLibrary,
+    events::{GeometryAction, GeometryActionChannel},
+  },
+};
+
+/// # Custom Tool Via Keyboard
+///
+/// Cmd+G first marks the selected points and lines as the givens of a new
+/// custom tool, then asks for its name once the results are selected.
+/// Cmd+Shift+G asks for the name of a tool to apply. Escape cancels both.
+/// This needs to run after the prompt is edited, like opening any prompt.
+pub struct CustomToolViaKeyboard;
+
+impl<'a> System<'a> for CustomToolViaKeyboard {
+  type SystemData = (
+    Read<'a, InputState>,
+    Write<'a, PromptState>,
+    Write<'a, CustomToolLibrary>,
+    Write<'a, GeometryActionChannel>,
+  );
+
+  fn run(&mut self, (input_state, mut prompt_state, mut library, mut geometry_action_channel): Self::SystemData) {
+    if prompt_state.is_active() {
+      return;
+    }
+
+    if input_state.keyboard.is_activated(Key::LCommand) || input_state.keyboard.is_activated(Key::RCommand) {
+      if input_state.keyboard.just_activated(Key::G) {
+        if input_state.keyboard.is_shift_activated() {
+          prompt_state.open(PromptKind::ApplyCustomTool, String::new());
+        } else if library.pending_givens.is_some() {
+          prompt_state.open(PromptKind::CustomToolName, String::new());
+        } else {
+          geometry_action_channel.single_write(GeometryAction::MarkCustomToolGivens);
+        }
+      }
+    } else if input_state.keyboard.just_activated(Key::Escape) {
+      library.pending_givens = None;
+      library.application = None;
+    }
+  }
+}
\ No newline at end of file
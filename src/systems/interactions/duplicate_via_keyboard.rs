@@ -0,0 +1,33 @@
+use specs::prelude::*;
+use crate::{
+  utilities::Key,
+  resources::{
+    InputState,
+    PromptState,
+    events::{GeometryAction, GeometryActionChannel},
+  },
+};
+
+/// # Duplicate Via Keyboard
+///
+/// Cmd+U duplicates the selection.
+pub struct DuplicateViaKeyboard;
+
+impl<'a> System<'a> for DuplicateViaKeyboard {
+  type SystemData = (
+    Read<'a, InputState>,
+    Read<'a, PromptState>,
+    Write<'a, GeometryActionChannel>,
+  );
+
+  fn run(&mut self, (input_state, prompt_state, mut geometry_action_channel): Self::SystemData) {
+    if prompt_state.is_active() {
+      return;
+    }
+
+    let cmd = input_state.keyboard.is_activated(Key::LCommand) || input_state.keyboard.is_activated(Key::RCommand);
+    if cmd && input_state.keyboard.just_activated(Key::U) {
+      geometry_action_channel.single_write(GeometryAction::DuplicateSelected);
+    }
+  }
+}
\ No newline at end of file
@@ -0,0 +1,39 @@
+use specs::prelude::*;
+use crate::{
+  utilities::Key,
+  resources::{
+    InputState,
+    PromptState,
+    events::{PromptEvent, PromptEventChannel},
+  },
+};
+
+pub struct EditPromptViaKeyboard;
+
+/// # EditPromptViaKeyboard
+///
+/// Types into the active prompt. `Return` submits the text, `Escape` closes
+/// the prompt and `Backspace` removes the last character.
+impl<'a> System<'a> for EditPromptViaKeyboard {
+  type SystemData = (
+    Read<'a, InputState>,
+    Write<'a, PromptState>,
+    Write<'a, PromptEventChannel>,
+  );
+
+  fn run(&mut self, (input_state, mut prompt_state, mut prompt_events): Self::SystemData) {
+    if input_state.keyboard.just_activated(Key::Escape) {
+      prompt_state.close();
+    } else if let Some(prompt) = prompt_state.get_mut() {
+      if input_state.keyboard.just_activated(Key::Return) {
+        prompt_events.single_write(PromptEvent(prompt.kind, prompt.text.clone()));
+      } else if input_state.keyboard.just_activated(Key::Backspace) {
+        prompt.text.pop();
+        prompt.error = None;
+      } else if !input_state.text.is_empty() {
+        prompt.text.extend(input_state.text.chars().filter(|c| !c.is_control()));
+        prompt.error = None;
+      }
+    }
+  }
+}
\ No newline at end of file
@@ -2,7 +2,7 @@ use specs::prelude::*;
 use crate::{
   utilities::Vector2,
   resources::{Viewport, ViewportTransform, SpatialHashTable},
-  components::{Point, Line},
+  components::{Point, Line, Arc, Conic, Plot},
 };
 
 pub fn hitting_object<'a>(
@@ -11,6 +11,9 @@ pub fn hitting_object<'a>(
   spatial_table: &SpatialHashTable<Entity>,
   points: &ReadStorage<'a, Point>,
   lines: &ReadStorage<'a, Line>,
+  arcs: &ReadStorage<'a, Arc>,
+  conics: &ReadStorage<'a, Conic>,
+  plots: &ReadStorage<'a, Plot>,
   threshold: f64,
 ) -> Option<Entity> {
 
@@ -34,10 +37,27 @@ pub fn hitting_object<'a>(
         if dist < threshold && (maybe_selected_line.is_none() || dist < maybe_selected_line.unwrap().1) {
           maybe_selected_line = Some((entity, dist));
         }
+      } else if let Some(a) = arcs.get(entity) {
+        let actual_closest_point = a.closest_point(virtual_mouse_pos).to_actual(viewport);
+        let dist = (actual_closest_point - mouse_pos).magnitude();
+        if dist < threshold && (maybe_selected_line.is_none() || dist < maybe_selected_line.unwrap().1) {
+          maybe_selected_line = Some((entity, dist));
+        }
+      } else if let Some(c) = conics.get(entity) {
+        let actual_closest_point = c.closest_point(virtual_mouse_pos).to_actual(viewport);
+        let dist = (actual_closest_point - mouse_pos).magnitude();
+        if dist < threshold && (maybe_selected_line.is_none() || dist < maybe_selected_line.unwrap().1) {
+          maybe_selected_line = Some((entity, dist));
+        }
+      } else if let Some((_, closest)) = plots.get(entity).and_then(|plot| plot.closest(virtual_mouse_pos)) {
+        let dist = (closest.to_actual(viewport) - mouse_pos).magnitude();
+        if dist < threshold && (maybe_selected_line.is_none() || dist < maybe_selected_line.unwrap().1) {
+          maybe_selected_line = Some((entity, dist));
+        }
       }
     }
   }
 
-  // Return point in priority to line
+  // Return point in priority to line (and curves)
   maybe_selected_point.or(maybe_selected_line).map(|(ent, _)| ent)
 }
\ No newline at end of file
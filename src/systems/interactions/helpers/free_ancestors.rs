@@ -0,0 +1,29 @@
+use std::collections::HashSet;
+use specs::prelude::*;
+use crate::{
+  utilities::Vector2,
+  resources::DependencyGraph,
+  components::SymbolicPoint,
+};
+
+/// Free points the given entities are eventually defined by, along with
+/// their positions
+pub fn free_ancestors<'a>(
+  entities: Vec<Entity>,
+  sym_points: &ReadStorage<'a, SymbolicPoint>,
+  dependency_graph: &DependencyGraph,
+) -> Vec<(Entity, Vector2)> {
+  let mut visited = HashSet::new();
+  let mut stack = entities;
+  let mut result = vec![];
+  while let Some(ent) = stack.pop() {
+    if !visited.insert(ent) {
+      continue;
+    }
+    match sym_points.get(ent) {
+      Some(SymbolicPoint::Free(position)) => result.push((ent, *position)),
+      _ => stack.extend(dependency_graph.get_direct_parents(&ent).into_iter().flatten()),
+    }
+  }
+  result
+}
\ No newline at end of file
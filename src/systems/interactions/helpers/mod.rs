@@ -1,2 +1,5 @@
 mod check_hit_object;
-pub use check_hit_object::*;
\ No newline at end of file
+pub use check_hit_object::*;
+
+mod free_ancestors;
+pub use free_ancestors::*;
\ No newline at end of file
@@ -0,0 +1,44 @@
+use specs::prelude::*;
+use crate::{
+  utilities::Key,
+  resources::{
+    InputState,
+    PromptState,
+    Iterations,
+    events::{GeometryAction, GeometryActionChannel},
+  },
+};
+
+/// # Iterate Via Keyboard
+///
+/// Cmd+I iterates the selected points, whose images are picked afterwards.
+/// Cmd+= and Cmd+- change the depth of the selected iteration. Escape stops
+/// picking the images.
+pub struct IterateViaKeyboard;
+
+impl<'a> System<'a> for IterateViaKeyboard {
+  type SystemData = (
+    Read<'a, InputState>,
+    Read<'a, PromptState>,
+    Write<'a, Iterations>,
+    Write<'a, GeometryActionChannel>,
+  );
+
+  fn run(&mut self, (input_state, prompt_state, mut iterations, mut geometry_action_channel): Self::SystemData) {
+    if prompt_state.is_active() {
+      return;
+    }
+
+    if input_state.keyboard.is_activated(Key::LCommand) || input_state.keyboard.is_activated(Key::RCommand) {
+      if input_state.keyboard.just_activated(Key::I) {
+        geometry_action_channel.single_write(GeometryAction::IterateSelected);
+      } else if input_state.keyboard.just_activated(Key::Equals) {
+        geometry_action_channel.single_write(GeometryAction::ChangeIterationDepth(1));
+      } else if input_state.keyboard.just_activated(Key::Minus) {
+        geometry_action_channel.single_write(GeometryAction::ChangeIterationDepth(-1));
+      }
+    } else if input_state.keyboard.just_activated(Key::Escape) {
+      iterations.picking = None;
+    }
+  }
+}
\ No newline at end of file
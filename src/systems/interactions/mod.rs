@@ -1,4 +1,4 @@
-mod helpers;
+pub mod helpers;
 
 mod change_tool_via_keyboard;
 pub use change_tool_via_keyboard::ChangeToolViaKeyboard;
@@ -31,4 +31,55 @@ mod snap_point_system;
 pub use snap_point_system::*;
 
 mod abort_create_line_via_keyboard;
-pub use abort_create_line_via_keyboard::*;
\ No newline at end of file
+pub use abort_create_line_via_keyboard::*;
+
+mod edit_prompt_via_keyboard;
+pub use edit_prompt_via_keyboard::*;
+
+mod open_prompt_via_keyboard;
+pub use open_prompt_via_keyboard::*;
+
+mod create_locus_via_keyboard;
+pub use create_locus_via_keyboard::*;
+
+mod trace_via_keyboard;
+pub use trace_via_keyboard::*;
+
+mod animate_via_keyboard;
+pub use animate_via_keyboard::*;
+
+mod transform_selection_via_drag;
+pub use transform_selection_via_drag::*;
+
+mod nudge_via_keyboard;
+pub use nudge_via_keyboard::*;
+
+mod protocol_via_keyboard;
+pub use protocol_via_keyboard::*;
+
+mod custom_tool_via_keyboard;
+pub use custom_tool_via_keyboard::*;
+
+mod pick_custom_tool_givens;
+pub use pick_custom_tool_givens::*;
+
+mod iterate_via_keyboard;
+pub use iterate_via_keyboard::*;
+
+mod pick_iteration_images;
+pub use pick_iteration_images::*;
+
+mod clipboard_via_keyboard;
+pub use clipboard_via_keyboard::*;
+
+mod duplicate_via_keyboard;
+pub use duplicate_via_keyboard::*;
+
+mod redefine_via_keyboard;
+pub use redefine_via_keyboard::*;
+
+mod context_menu_via_mouse;
+pub use context_menu_via_mouse::*;
+
+mod tangents_via_keyboard;
+pub use tangents_via_keyboard::*;
\ No newline at end of file
@@ -34,6 +34,12 @@ impl<'a> System<'a> for MouseEventEmitter {
   );
 
   fn run(&mut self, (input_state, mut mouse_event_channel): Self::SystemData) {
+
+    // Right clicks are emitted as soon as the button is pressed
+    if input_state.mouse_right_button.just_activated() {
+      mouse_event_channel.single_write(MouseEvent::RightClick(input_state.mouse_abs_pos));
+    }
+
     if input_state.mouse_left_button.just_activated() {
 
       // Pressed mouse left button just now
@@ -1,26 +1,47 @@
 use specs::prelude::*;
 use crate::{
+  utilities::Vector2,
   resources::{
     Tool,
     InputState,
     Viewport, ViewportTransform,
     SpatialHashTable,
+    ConstructionProtocol,
+    ContextMenu,
+    DependencyGraph,
+    geometry::{SelectionHandles, DraggedPoint, MaybeSnapPoint, SnapPoint, SnapPointType},
     events::{
+      GeometryAction, GeometryActionChannel,
       ToolChangeEvent, ToolChangeEventChannel, ToolChangeEventReader,
       SketchEventChannel, SketchEvent, MovePoint,
       MouseEvent, MouseEventChannel, MouseEventReader,
     },
   },
-  components::{SymbolicPoint, Point, Line},
+  components::{SymbolicPoint, Point, Line, Arc, Conic, Plot, Selected},
 };
-use super::helpers::hitting_object;
+use super::helpers::{hitting_object, free_ancestors};
 
 static SELECT_DIST_THRES : f64 = 5.0; // Pixel
 
+enum Dragging {
+  Point(Entity, SymbolicPoint), // A single point, which moves along whatever it is on
+  Translation(Vector2, Vec<(Entity, Vector2)>), // Virtual start position, free points with their start positions
+}
+
+/// # Move Point Via Drag
+///
+/// Dragging a single point moves it along whatever it is on. Dragging
+/// anything else, or any member of a selection of several things, translates
+/// all the free points they are defined by. Things with no free points to
+/// move are not dragged. Only the last mouse position of each frame is
+/// moved to.
+///
+/// A dragged free point moves to where it snaps. Dropped with Alt held, it is
+/// attached to what it snaps to, or merged into the point it snaps to.
 pub struct MovePointViaDrag {
   tool_change_event_reader: Option<ToolChangeEventReader>,
   mouse_event_reader: Option<MouseEventReader>,
-  dragging_point: Option<(Entity, SymbolicPoint)>,
+  dragging: Option<Dragging>,
 }
 
 impl Default for MovePointViaDrag {
@@ -28,7 +49,7 @@ impl Default for MovePointViaDrag {
     Self {
       tool_change_event_reader: None,
       mouse_event_reader: None,
-      dragging_point: None,
+      dragging: None,
     }
   }
 }
@@ -40,10 +61,22 @@ impl<'a> System<'a> for MovePointViaDrag {
     Write<'a, MouseEventChannel>,
     Read<'a, Viewport>,
     Read<'a, SpatialHashTable<Entity>>,
+    Read<'a, SelectionHandles>,
+    Read<'a, ConstructionProtocol>,
+    Read<'a, ContextMenu>,
+    Read<'a, DependencyGraph>,
+    Read<'a, MaybeSnapPoint>,
+    Write<'a, DraggedPoint>,
     Write<'a, SketchEventChannel>,
+    Write<'a, GeometryActionChannel>,
+    Entities<'a>,
     ReadStorage<'a, SymbolicPoint>,
     ReadStorage<'a, Point>,
     ReadStorage<'a, Line>,
+    ReadStorage<'a, Arc>,
+    ReadStorage<'a, Conic>,
+    ReadStorage<'a, Plot>,
+    ReadStorage<'a, Selected>,
   );
 
   fn setup(&mut self, world: &mut World) {
@@ -58,10 +91,22 @@ impl<'a> System<'a> for MovePointViaDrag {
     mut mouse_event_channel,
     viewport,
     spatial_table,
+    selection_handles,
+    protocol,
+    context_menu,
+    dependency_graph,
+    maybe_snap_point,
+    mut dragged_point,
     mut sketch_event_channel,
+    mut geometry_action_channel,
+    entities,
     sym_points,
     points,
     lines,
+    arcs,
+    conics,
+    plots,
+    selected,
   ): Self::SystemData) {
 
     // First use tool change to setup mouse event reader.
@@ -83,51 +128,115 @@ impl<'a> System<'a> for MovePointViaDrag {
       }
     }
 
+    let mut maybe_drag_to = None;
     if let Some(reader_id) = &mut self.mouse_event_reader {
       for event in mouse_event_channel.read(reader_id) {
         match event {
           MouseEvent::DragBegin(start_position) => {
-            if !input_state.keyboard.is_shift_activated() {
-              if let Some(entity) = hitting_object(*start_position, &viewport, &spatial_table, &points, &lines, SELECT_DIST_THRES) {
-                if let Some(sym_point) = sym_points.get(entity) {
-                  self.dragging_point = Some((entity, *sym_point));
+            if !input_state.keyboard.is_shift_activated() && selection_handles.hit(*start_position).is_none() && protocol.row_at(*start_position, &viewport).is_none() && !context_menu.is_active() {
+              if let Some(entity) = hitting_object(*start_position, &viewport, &spatial_table, &points, &lines, &arcs, &conics, &plots, SELECT_DIST_THRES) {
+                let is_selected = selected.get(entity).is_some();
+                let in_selection = is_selected && (&selected).join().nth(1).is_some();
+                self.dragging = match sym_points.get(entity) {
+                  Some(sym_point) if !in_selection => Some(Dragging::Point(entity, *sym_point)),
+                  _ => {
+                    let to_drag = if is_selected { (&entities, &selected).join().map(|(ent, _)| ent).collect() } else { vec![entity] };
+                    let free_points = free_ancestors(to_drag, &sym_points, &dependency_graph);
+                    if free_points.is_empty() {
+                      None
+                    } else {
+                      Some(Dragging::Translation(start_position.to_virtual(&viewport), free_points))
+                    }
+                  },
+                };
 
-                  // Note that we let the dragging point to be selected directly
+                // Note that we let the dragged object to be selected directly
+                if self.dragging.is_some() && !is_selected {
                   sketch_event_channel.single_write(SketchEvent::Select(entity));
                 }
+                if let Some(Dragging::Point(_, SymbolicPoint::Free(_))) = self.dragging {
+                  dragged_point.set(entity);
+                }
               }
             }
           },
           MouseEvent::DragMove(_, curr_position) => {
-            match self.dragging_point {
-              Some((ent, sym_point)) => {
-                match sym_point {
-                  SymbolicPoint::Free(old_position) => {
-                    let new_position = curr_position.to_virtual(&viewport);
-                    sketch_event_channel.single_write(SketchEvent::MovePoint(ent, MovePoint::Free(old_position, new_position)));
-                  },
-                  SymbolicPoint::OnLine(line_entity, old_t) => {
-                    if let Some(line) = lines.get(line_entity) {
-                      let virtual_mouse_position = curr_position.to_virtual(&viewport);
-                      let projected_position = virtual_mouse_position.project(*line);
-                      let diff = projected_position - line.origin;
-                      let sign = diff.dot(line.direction).signum();
-                      let new_t = sign * diff.magnitude();
-                      sketch_event_channel.single_write(SketchEvent::MovePoint(ent, MovePoint::OnLine(line_entity, old_t, new_t)))
-                    }
-                  },
-                  _ => (),
-                }
-              },
-              None => (),
-            }
+            maybe_drag_to = Some(*curr_position);
           },
           MouseEvent::DragEnd(_) => {
-            self.dragging_point = None;
+            if let Some(Dragging::Point(ent, SymbolicPoint::Free(_))) = self.dragging {
+              if input_state.keyboard.is_alt_activated() {
+                match maybe_snap_point.get() {
+                  Some(SnapPoint { symbo: SnapPointType::NotSnapped, .. }) | None => (),
+                  Some(SnapPoint { symbo, .. }) => geometry_action_channel.single_write(GeometryAction::AttachPoint(ent, symbo)),
+                }
+              }
+            }
+            self.dragging = None;
+            dragged_point.clear();
           },
           _ => (),
         }
       }
     }
+
+    // Move everything to where the mouse ends up in this frame
+    if let Some(curr_position) = maybe_drag_to {
+      match &self.dragging {
+        Some(Dragging::Point(ent, sym_point)) => {
+          let ent = *ent;
+          match *sym_point {
+            SymbolicPoint::Free(old_position) => {
+              let new_position = match maybe_snap_point.get() {
+                Some(SnapPoint { position, .. }) => position,
+                None => curr_position.to_virtual(&viewport),
+              };
+              sketch_event_channel.single_write(SketchEvent::MovePoint(ent, MovePoint::Free(old_position, new_position)));
+            },
+            SymbolicPoint::OnLine(line_entity, old_t) => {
+              if let Some(line) = lines.get(line_entity) {
+                let virtual_mouse_position = curr_position.to_virtual(&viewport);
+                let projected_position = virtual_mouse_position.project(*line);
+                let diff = projected_position - line.origin;
+                let sign = diff.dot(line.direction).signum();
+                let new_t = sign * diff.magnitude();
+                sketch_event_channel.single_write(SketchEvent::MovePoint(ent, MovePoint::OnLine(line_entity, old_t, new_t)))
+              }
+            },
+            SymbolicPoint::OnArc(arc_entity, old_t) => {
+              if let Some(arc) = arcs.get(arc_entity) {
+                let new_t = arc.param_of(curr_position.to_virtual(&viewport));
+                sketch_event_channel.single_write(SketchEvent::MovePoint(ent, MovePoint::OnArc(arc_entity, old_t, new_t)))
+              }
+            },
+            SymbolicPoint::OnConic(conic_entity, old_t) => {
+              if let Some(conic) = conics.get(conic_entity) {
+                let new_t = conic.param_of(curr_position.to_virtual(&viewport));
+                sketch_event_channel.single_write(SketchEvent::MovePoint(ent, MovePoint::OnConic(conic_entity, old_t, new_t)))
+              }
+            },
+            SymbolicPoint::OnFunction(function_entity, old_x) => {
+              let new_x = curr_position.to_virtual(&viewport).x;
+              sketch_event_channel.single_write(SketchEvent::MovePoint(ent, MovePoint::OnFunction(function_entity, old_x, new_x)))
+            },
+            SymbolicPoint::OnCurve(curve_entity, old_t) => {
+              if let Some((new_t, _)) = plots.get(curve_entity).and_then(|plot| plot.closest(curr_position.to_virtual(&viewport))) {
+                sketch_event_channel.single_write(SketchEvent::MovePoint(ent, MovePoint::OnCurve(curve_entity, old_t, new_t)))
+              }
+            },
+            _ => (),
+          }
+        },
+        Some(Dragging::Translation(start_position, free_points)) => {
+          let offset = curr_position.to_virtual(&viewport) - *start_position;
+          for (ent, position) in free_points {
+            if let Some(SymbolicPoint::Free(old_position)) = sym_points.get(*ent) {
+              sketch_event_channel.single_write(SketchEvent::MovePoint(*ent, MovePoint::Free(*old_position, *position + offset)));
+            }
+          }
+        },
+        None => (),
+      }
+    }
   }
 }
\ No newline at end of file
@@ -0,0 +1,81 @@
+use specs::prelude::*;
+use crate::{
+  utilities::{Key, Vector2},
+  resources::{
+    InputState,
+    PromptState,
+    Viewport,
+    events::{SketchEvent, SketchEventChannel, MovePoint},
+  },
+  components::{SymbolicPoint, Line, Selected},
+};
+
+static NUDGE_DIST : f64 = 1.0; // Pixel
+static SHIFT_NUDGE_DIST : f64 = 10.0; // Pixel
+
+/// # Nudge Via Keyboard
+///
+/// Arrow keys move the selected free points by a pixel, or ten with shift.
+/// Points on lines step along their line by how far the nudge goes in the
+/// line's direction.
+pub struct NudgeViaKeyboard;
+
+impl<'a> System<'a> for NudgeViaKeyboard {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, InputState>,
+    Read<'a, PromptState>,
+    Read<'a, Viewport>,
+    Write<'a, SketchEventChannel>,
+    ReadStorage<'a, SymbolicPoint>,
+    ReadStorage<'a, Line>,
+    ReadStorage<'a, Selected>,
+  );
+
+  fn run(&mut self, (
+    entities,
+    input_state,
+    prompt_state,
+    viewport,
+    mut sketch_event_channel,
+    sym_points,
+    lines,
+    selected,
+  ): Self::SystemData) {
+    if prompt_state.is_active() {
+      return;
+    }
+    if input_state.keyboard.is_activated(Key::LCommand) || input_state.keyboard.is_activated(Key::RCommand) {
+      return;
+    }
+
+    // Get the direction in virtual space, where y goes up
+    let mut direction = vec2![0., 0.];
+    if input_state.keyboard.just_activated(Key::Left) { direction.x -= 1.0; }
+    if input_state.keyboard.just_activated(Key::Right) { direction.x += 1.0; }
+    if input_state.keyboard.just_activated(Key::Up) { direction.y += 1.0; }
+    if input_state.keyboard.just_activated(Key::Down) { direction.y -= 1.0; }
+    if direction == vec2![0., 0.] {
+      return;
+    }
+    let dist = if input_state.keyboard.is_shift_activated() { SHIFT_NUDGE_DIST } else { NUDGE_DIST };
+    let offset = direction * dist * viewport.scale();
+
+    for (ent, sym_point, _) in (&entities, &sym_points, &selected).join() {
+      match *sym_point {
+        SymbolicPoint::Free(old_position) => {
+          sketch_event_channel.single_write(SketchEvent::MovePoint(ent, MovePoint::Free(old_position, old_position + offset)));
+        },
+        SymbolicPoint::OnLine(line_ent, old_t) => {
+          if let Some(line) = lines.get(line_ent) {
+            let dt = offset.dot(line.direction);
+            if dt != 0.0 {
+              sketch_event_channel.single_write(SketchEvent::MovePoint(ent, MovePoint::OnLine(line_ent, old_t, old_t + dt)));
+            }
+          }
+        },
+        _ => (),
+      }
+    }
+  }
+}
\ No newline at end of file
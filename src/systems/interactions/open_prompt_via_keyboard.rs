@@ -0,0 +1,49 @@
+use specs::prelude::*;
+use crate::{
+  utilities::Key,
+  resources::{
+    InputState, PromptState, PromptKind, SnapSettings,
+    events::{GeometryAction, GeometryActionChannel},
+  },
+};
+
+pub struct OpenPromptViaKeyboard;
+
+/// # OpenPromptViaKeyboard
+///
+/// Opens a prompt on its shortcut. This needs to run after the prompt is
+/// edited, so that the key opening the prompt is not typed into it. Cmd+E
+/// asks for the selected point to be edited. Editing a point, or the snap
+/// settings with Cmd+Comma, starts from the current values.
+impl<'a> System<'a> for OpenPromptViaKeyboard {
+  type SystemData = (
+    Read<'a, InputState>,
+    Read<'a, SnapSettings>,
+    Write<'a, PromptState>,
+    Write<'a, GeometryActionChannel>,
+  );
+
+  fn run(&mut self, (input_state, snap_settings, mut prompt_state, mut geometry_action_channel): Self::SystemData) {
+    if prompt_state.is_active() {
+      return;
+    }
+
+    if input_state.keyboard.is_activated(Key::LCommand) || input_state.keyboard.is_activated(Key::RCommand) {
+      if input_state.keyboard.just_activated(Key::P) {
+        prompt_state.open(PromptKind::NewPoint, String::new());
+      } else if input_state.keyboard.just_activated(Key::E) {
+        geometry_action_channel.single_write(GeometryAction::EditSelected);
+      } else if input_state.keyboard.just_activated(Key::Comma) {
+        prompt_state.open(PromptKind::SnapSettings, snap_settings.to_string());
+      }
+    } else if input_state.keyboard.just_activated(Key::F) {
+      if input_state.keyboard.is_shift_activated() {
+        prompt_state.open(PromptKind::Parametric, String::new());
+      } else {
+        prompt_state.open(PromptKind::Function, String::new());
+      }
+    } else if input_state.keyboard.just_activated(Key::R) {
+      prompt_state.open(PromptKind::Polar, String::new());
+    }
+  }
+}
\ No newline at end of file
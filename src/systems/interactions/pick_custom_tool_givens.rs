@@ -0,0 +1,53 @@
+use specs::prelude::*;
+use crate::{
+  resources::{
+    CustomToolLibrary,
+    GivenKind,
+    events::{SketchEvent, SketchEventChannel, SketchEventReader},
+  },
+  components::{SymbolicPoint, SymbolicLine},
+};
+
+/// # Pick Custom Tool Givens
+///
+/// While a custom tool is being applied, every point or line getting
+/// selected is picked as its next given if it is of the expected kind.
+#[derive(Default)]
+pub struct PickCustomToolGivens {
+  sketch_events_reader_id: Option<SketchEventReader>,
+}
+
+impl<'a> System<'a> for PickCustomToolGivens {
+  type SystemData = (
+    Read<'a, SketchEventChannel>,
+    Write<'a, CustomToolLibrary>,
+    ReadStorage<'a, SymbolicPoint>,
+    ReadStorage<'a, SymbolicLine>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.sketch_events_reader_id = Some(world.fetch_mut::<SketchEventChannel>().register_reader());
+  }
+
+  fn run(&mut self, (sketch_events, mut library, sym_points, sym_lines): Self::SystemData) {
+    if let Some(reader_id) = &mut self.sketch_events_reader_id {
+      for event in sketch_events.read(reader_id) {
+        if let SketchEvent::Select(entity) = event {
+          let is_expected = match library.next_given() {
+            Some(GivenKind::Point) => sym_points.get(*entity).is_some(),
+            Some(GivenKind::Line) => sym_lines.get(*entity).is_some(),
+            None => false,
+          };
+          if let Some(application) = &mut library.application {
+            if is_expected && !application.picks.contains(entity) {
+              application.picks.push(*entity);
+            }
+          }
+        }
+      }
+    } else {
+      panic!("[pick_custom_tool_givens] No sketch events reader id");
+    }
+  }
+}
\ No newline at end of file
@@ -0,0 +1,48 @@
+use specs::prelude::*;
+use crate::{
+  resources::{
+    DependencyGraph,
+    Iterations,
+    events::{SketchEvent, SketchEventChannel, SketchEventReader},
+  },
+  components::SymbolicPoint,
+};
+
+/// # Pick Iteration Images
+///
+/// While the images of an iteration are being picked, every point getting
+/// selected that depends on the pre-images is picked as the next image.
+#[derive(Default)]
+pub struct PickIterationImages {
+  sketch_events_reader_id: Option<SketchEventReader>,
+}
+
+impl<'a> System<'a> for PickIterationImages {
+  type SystemData = (
+    Read<'a, SketchEventChannel>,
+    Read<'a, DependencyGraph>,
+    Write<'a, Iterations>,
+    ReadStorage<'a, SymbolicPoint>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.sketch_events_reader_id = Some(world.fetch_mut::<SketchEventChannel>().register_reader());
+  }
+
+  fn run(&mut self, (sketch_events, dependency_graph, mut iterations, sym_points): Self::SystemData) {
+    if let Some(reader_id) = &mut self.sketch_events_reader_id {
+      for event in sketch_events.read(reader_id) {
+        if let (SketchEvent::Select(entity), Some(picking)) = (event, &mut iterations.picking) {
+          let depends_on_pre_images = picking.pre_images.iter()
+            .any(|pre_image| pre_image != entity && dependency_graph.get_all_dependents(pre_image).contains(entity));
+          if sym_points.get(*entity).is_some() && depends_on_pre_images && picking.images.len() < picking.pre_images.len() {
+            picking.images.push(*entity);
+          }
+        }
+      }
+    } else {
+      panic!("[pick_iteration_images] No sketch events reader id");
+    }
+  }
+}
\ No newline at end of file
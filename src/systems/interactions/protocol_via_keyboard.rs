@@ -0,0 +1,44 @@
+use specs::prelude::*;
+use crate::{
+  utilities::Key,
+  resources::{
+    InputState,
+    PromptState,
+    ConstructionProtocol,
+    events::{GeometryAction, GeometryActionChannel},
+  },
+};
+
+/// # Protocol Via Keyboard
+///
+/// Cmd+K shows or hides the construction protocol panel, and Cmd+Left and
+/// Cmd+Right step backward and forward through the construction. Cmd+Shift+H
+/// shows everything hidden from the context menu again.
+pub struct ProtocolViaKeyboard;
+
+impl<'a> System<'a> for ProtocolViaKeyboard {
+  type SystemData = (
+    Read<'a, InputState>,
+    Read<'a, PromptState>,
+    Write<'a, ConstructionProtocol>,
+    Write<'a, GeometryActionChannel>,
+  );
+
+  fn run(&mut self, (input_state, prompt_state, mut protocol, mut geometry_action_channel): Self::SystemData) {
+    if prompt_state.is_active() {
+      return;
+    }
+
+    if input_state.keyboard.is_activated(Key::LCommand) || input_state.keyboard.is_activated(Key::RCommand) {
+      if input_state.keyboard.just_activated(Key::K) {
+        protocol.panel_visible = !protocol.panel_visible;
+      } else if input_state.keyboard.just_activated(Key::Left) {
+        geometry_action_channel.single_write(GeometryAction::StepProtocolBackward);
+      } else if input_state.keyboard.just_activated(Key::Right) {
+        geometry_action_channel.single_write(GeometryAction::StepProtocolForward);
+      } else if input_state.keyboard.just_activated(Key::H) && input_state.keyboard.is_shift_activated() {
+        geometry_action_channel.single_write(GeometryAction::ShowAllHidden);
+      }
+    }
+  }
+}
\ No newline at end of file
@@ -3,6 +3,7 @@ use crate::{
   utilities::Key,
   resources::{
     InputState,
+    PromptState,
     events::{GeometryAction, GeometryActionChannel},
   },
 };
@@ -12,10 +13,15 @@ pub struct RemoveSelectedViaDelete;
 impl<'a> System<'a> for RemoveSelectedViaDelete {
   type SystemData = (
     Read<'a, InputState>,
+    Read<'a, PromptState>,
     Write<'a, GeometryActionChannel>,
   );
 
-  fn run(&mut self, (input_state, mut geometry_action_channel): Self::SystemData) {
+  fn run(&mut self, (input_state, prompt_state, mut geometry_action_channel): Self::SystemData) {
+    if prompt_state.is_active() {
+      return;
+    }
+
     if input_state.keyboard.just_activated(Key::Backspace) || input_state.keyboard.just_activated(Key::Delete) {
       geometry_action_channel.single_write(GeometryAction::RemoveSelected);
     }
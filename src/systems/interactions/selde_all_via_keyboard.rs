@@ -3,24 +3,43 @@ use crate::{
   utilities::Key,
   resources::{
     InputState,
+    PromptState,
     events::{GeometryAction, GeometryActionChannel},
   },
 };
 
+/// # Select And Deselect All Via Keyboard
+///
+/// Cmd+A selects everything and Cmd+D deselects everything. Cmd+1 selects
+/// only the points and Cmd+2 only the lines. Cmd+[ adds everything the
+/// selection is defined on, and Cmd+] everything defined on the selection.
 pub struct SeldeAllViaKeyboard;
 
 impl<'a> System<'a> for SeldeAllViaKeyboard {
   type SystemData = (
     Read<'a, InputState>,
+    Read<'a, PromptState>,
     Write<'a, GeometryActionChannel>,
   );
 
-  fn run(&mut self, (input_state, mut geometry_action_channel): Self::SystemData) {
+  fn run(&mut self, (input_state, prompt_state, mut geometry_action_channel): Self::SystemData) {
+    if prompt_state.is_active() {
+      return;
+    }
+
     if input_state.keyboard.is_activated(Key::LCommand) || input_state.keyboard.is_activated(Key::RCommand) {
       if input_state.keyboard.just_activated(Key::A) {
         geometry_action_channel.single_write(GeometryAction::SelectAll);
       } else if input_state.keyboard.just_activated(Key::D) {
         geometry_action_channel.single_write(GeometryAction::DeselectAll);
+      } else if input_state.keyboard.just_activated(Key::D1) {
+        geometry_action_channel.single_write(GeometryAction::SelectAllPoints);
+      } else if input_state.keyboard.just_activated(Key::D2) {
+        geometry_action_channel.single_write(GeometryAction::SelectAllLines);
+      } else if input_state.keyboard.just_activated(Key::LeftBracket) {
+        geometry_action_channel.single_write(GeometryAction::SelectParents);
+      } else if input_state.keyboard.just_activated(Key::RightBracket) {
+        geometry_action_channel.single_write(GeometryAction::SelectChildren);
       }
     }
   }
@@ -8,7 +8,9 @@ use crate::{
     SpatialHashTable,
     InputState,
     Tool,
-    geometry::SelectRectangle,
+    ConstructionProtocol,
+    ContextMenu,
+    geometry::{SelectRectangle, SelectionHandles},
     events::{
       MouseEvent, MouseEventChannel, MouseEventReader,
       ToolChangeEvent, ToolChangeEventChannel, ToolChangeEventReader,
@@ -16,11 +18,13 @@ use crate::{
       GeometryActionChannel, GeometryAction,
     },
   },
-  components::{Point, Line, Selected},
+  components::{Point, Line, Arc, Conic, Plot, Selected},
 };
 use super::helpers::hitting_object;
 
 static SELECT_DIST_THRES : f64 = 5.0; // Pixel
+static ARC_SELECT_SAMPLES : usize = 64;
+static CONIC_SELECT_SAMPLES : usize = 256;
 
 pub struct SeldeViaMouse {
   tool_change_reader: Option<ToolChangeEventReader>,
@@ -50,8 +54,14 @@ impl<'a> System<'a> for SeldeViaMouse {
     Write<'a, GeometryActionChannel>,
     Write<'a, SketchEventChannel>,
     Write<'a, SelectRectangle>,
+    Read<'a, SelectionHandles>,
+    Read<'a, ConstructionProtocol>,
+    Read<'a, ContextMenu>,
     ReadStorage<'a, Point>,
     ReadStorage<'a, Line>,
+    ReadStorage<'a, Arc>,
+    ReadStorage<'a, Conic>,
+    ReadStorage<'a, Plot>,
     ReadStorage<'a, Selected>,
   );
 
@@ -70,8 +80,14 @@ impl<'a> System<'a> for SeldeViaMouse {
     mut geometry_action_channel,
     mut sketch_event_channel,
     mut select_rectangle,
+    selection_handles,
+    protocol,
+    context_menu,
     points,
     lines,
+    arcs,
+    conics,
+    plots,
     selected,
   ): Self::SystemData) {
 
@@ -94,14 +110,25 @@ impl<'a> System<'a> for SeldeViaMouse {
       }
     }
 
-    // Read the mouse event
+    // Read the mouse event. Anything on the selection handles is left to
+    // transforming the selection, or to the context menu while it is open,
+    // and pressing on a row of the protocol panel selects the geometry of
+    // that step
     if let Some(reader_id) = &mut self.mouse_event_reader {
       for event in mouse_event_channel.read(reader_id) {
         match event {
+          MouseEvent::MouseDown(_) | MouseEvent::Click(_) | MouseEvent::DragBegin(_) if context_menu.is_active() => (),
+          MouseEvent::MouseDown(mouse_pos) | MouseEvent::Click(mouse_pos) | MouseEvent::DragBegin(mouse_pos) if selection_handles.hit(*mouse_pos).is_some() => (),
+          MouseEvent::MouseDown(mouse_pos) if protocol.row_at(*mouse_pos, &*viewport).is_some() => {
+            if let Some(index) = protocol.row_at(*mouse_pos, &*viewport) {
+              geometry_action_channel.single_write(GeometryAction::SelectProtocolStep(index));
+            }
+          },
+          MouseEvent::Click(mouse_pos) | MouseEvent::DragBegin(mouse_pos) if protocol.row_at(*mouse_pos, &*viewport).is_some() => (),
           MouseEvent::MouseDown(mouse_pos) => {
 
             // Check if hitting something
-            if let Some(entity) = hitting_object(*mouse_pos, &*viewport, &*spatial_table, &points, &lines, SELECT_DIST_THRES) {
+            if let Some(entity) = hitting_object(*mouse_pos, &*viewport, &*spatial_table, &points, &lines, &arcs, &conics, &plots, SELECT_DIST_THRES) {
 
               // Check if shift is held
               if input_state.keyboard.is_shift_activated() {
@@ -112,7 +139,7 @@ impl<'a> System<'a> for SeldeViaMouse {
                 } else {
                   sketch_event_channel.single_write(SketchEvent::Select(entity));
                 }
-              } else {
+              } else if selected.get(entity).is_none() {
 
                 // If no shift, always select
                 geometry_action_channel.single_write(GeometryAction::DeselectAllExcept(entity));
@@ -124,10 +151,20 @@ impl<'a> System<'a> for SeldeViaMouse {
               geometry_action_channel.single_write(GeometryAction::DeselectAll);
             }
           },
+          MouseEvent::Click(mouse_pos) => {
+
+            // Pressing on something already selected keeps the selection so
+            // that it can be dragged together. Only a click selects it alone
+            if !input_state.keyboard.is_shift_activated() {
+              if let Some(entity) = hitting_object(*mouse_pos, &*viewport, &*spatial_table, &points, &lines, &arcs, &conics, &plots, SELECT_DIST_THRES) {
+                geometry_action_channel.single_write(GeometryAction::DeselectAllExcept(entity));
+              }
+            }
+          },
           MouseEvent::DragBegin(start_position) => {
 
             // We need the dragging begin from an empty space
-            if hitting_object(*start_position, &*viewport, &*spatial_table, &points, &lines, SELECT_DIST_THRES).is_none() {
+            if hitting_object(*start_position, &*viewport, &*spatial_table, &points, &lines, &arcs, &conics, &plots, SELECT_DIST_THRES).is_none() {
 
               // If ther's no shift, clear the selection
               if !input_state.keyboard.is_shift_activated() {
@@ -156,7 +193,7 @@ impl<'a> System<'a> for SeldeViaMouse {
               select_rectangle.set(rect);
 
               // Select all the elements intersecting with AABB
-              let mut new_entities = get_entities_in_aabb(rect, &*viewport, &*spatial_table, &points, &lines);
+              let mut new_entities = get_entities_in_aabb(rect, &*viewport, &*spatial_table, &points, &lines, &arcs, &conics, &plots);
               let mut to_remove = vec![];
               for entity in &self.drag_selected_new_entities {
                 if !new_entities.contains(entity) {
@@ -193,6 +230,9 @@ fn get_entities_in_aabb<'a>(
   spatial_table: &SpatialHashTable<Entity>,
   points: &ReadStorage<'a, Point>,
   lines: &ReadStorage<'a, Line>,
+  arcs: &ReadStorage<'a, Arc>,
+  conics: &ReadStorage<'a, Conic>,
+  plots: &ReadStorage<'a, Plot>,
 ) -> HashSet<Entity> {
   let mut result = HashSet::new();
 
@@ -208,6 +248,20 @@ fn get_entities_in_aabb<'a>(
       if actual.intersect(aabb).is_some() {
         result.insert(entity);
       }
+    } else if let Some(arc) = arcs.get(entity) {
+      let virtual_points = arc.sample(ARC_SELECT_SAMPLES);
+      if virtual_points.windows(2).any(|s| (s[0].to_actual(viewport), s[1].to_actual(viewport)).intersect(aabb).is_some()) {
+        result.insert(entity);
+      }
+    } else if let Some(conic) = conics.get(entity) {
+      let polylines = conic.sample(viewport.virtual_aabb(), CONIC_SELECT_SAMPLES);
+      if polylines.iter().any(|pl| pl.windows(2).any(|s| (s[0].to_actual(viewport), s[1].to_actual(viewport)).intersect(aabb).is_some())) {
+        result.insert(entity);
+      }
+    } else if let Some(plot) = plots.get(entity) {
+      if plot.positions().iter().any(|pl| pl.windows(2).any(|s| (s[0].to_actual(viewport), s[1].to_actual(viewport)).intersect(aabb).is_some())) {
+        result.insert(entity);
+      }
     }
   }
 
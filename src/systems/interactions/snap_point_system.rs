@@ -1,3 +1,4 @@
+use std::collections::HashSet;
 use itertools::Itertools;
 use specs::prelude::*;
 use crate::{
@@ -7,16 +8,24 @@ use crate::{
     ToolState,
     Viewport,
     ViewportTransform,
-    geometry::{MaybeSnapPoint, SnapPoint, SnapPointType},
+    ConstructionProtocol,
+    ContextMenu,
+    DependencyGraph,
+    SnapSettings,
+    geometry::{MaybeSnapPoint, SnapPoint, SnapPointType, DraggedPoint, CreateConicData},
   },
-  components::{Point, Line},
+  components::{Point, Line, Arc, Conic, SymbolicFunction, SymbolicCurve, Plot},
   utilities::{Vector2, Intersect},
 };
 
-static SNAP_TO_POINT_THRES : f64 = 12.0; // In actual space
-static SNAP_TO_LINE_THRES : f64 = 6.0; // In actual space
-static SNAP_TO_INTERSECTION_THRES : f64 = 15.0; // In actual space
-
+/// # Snap Point System
+///
+/// Finds where a point would go under the mouse: on a point, a line, an
+/// intersection and so on. This is for the tools creating points, and for a
+/// dragged free point, which also snaps to the grid. The dragged point moves
+/// to where it snaps, and is attached to it if dropped with Alt held. What
+/// is snapped to, and from how far, is up to the `SnapSettings`. Nothing is
+/// snapped to while Ctrl is held.
 pub struct SnapPointSystem;
 
 impl<'a> System<'a> for SnapPointSystem {
@@ -25,9 +34,20 @@ impl<'a> System<'a> for SnapPointSystem {
     Read<'a, ToolState>,
     Read<'a, Viewport>,
     Read<'a, SpatialHashTable<Entity>>,
+    Read<'a, ConstructionProtocol>,
+    Read<'a, ContextMenu>,
+    Read<'a, DependencyGraph>,
+    Read<'a, DraggedPoint>,
+    Read<'a, SnapSettings>,
+    Read<'a, CreateConicData>,
     Write<'a, MaybeSnapPoint>,
     ReadStorage<'a, Point>,
     ReadStorage<'a, Line>,
+    ReadStorage<'a, Arc>,
+    ReadStorage<'a, Conic>,
+    ReadStorage<'a, SymbolicFunction>,
+    ReadStorage<'a, SymbolicCurve>,
+    ReadStorage<'a, Plot>,
   );
 
   fn run(&mut self, (
@@ -35,11 +55,33 @@ impl<'a> System<'a> for SnapPointSystem {
     tool_state,
     vp,
     table,
+    protocol,
+    context_menu,
+    dependency_graph,
+    dragged_point,
+    settings,
+    create_conic_data,
     mut maybe_snap_point,
     points,
     lines,
+    arcs,
+    conics,
+    sym_functions,
+    sym_curves,
+    plots,
   ): Self::SystemData) {
-    if tool_state.depend_on_active_point() {
+    // Nothing is created through the protocol panel or the context menu, nor
+    // while the directrix of a parabola is being picked
+    if protocol.row_at(input_state.mouse_abs_pos, &*vp).is_some() || context_menu.is_active() || create_conic_data.picking_line {
+      maybe_snap_point.clear();
+      return;
+    }
+
+    // A dragged point cannot snap to itself or to anything depending on it
+    let dragging = dragged_point.get();
+    let skipped = dragging.map_or_else(HashSet::new, |ent| dependency_graph.get_all_dependents(&ent));
+
+    if tool_state.depend_on_active_point() || dragging.is_some() {
 
       // First get the mouse position and virtual mouse position
       let mouse_pos = input_state.mouse_abs_pos;
@@ -51,11 +93,13 @@ impl<'a> System<'a> for SnapPointSystem {
         symbo: SnapPointType::NotSnapped,
       });
 
-      // Then get the potential neighbors
-      let maybe_neighbors = table.get_neighbor_entities_of_point(virtual_mouse_pos, &*vp);
+      // Then get the potential neighbors, unless snapping is turned off for now
+      let snapping = !input_state.keyboard.is_ctrl_activated();
+      let maybe_neighbors = if snapping { table.get_neighbor_entities_of_point(virtual_mouse_pos, &*vp) } else { None };
       if let Some(neighbor_entities) = maybe_neighbors {
 
         let mut closest_lines : Vec<(Entity, Line)> = vec![];
+        let mut closest_functions : Vec<Entity> = vec![];
         let mut maybe_smallest_dist_to_line : Option<f64> = None;
         let mut maybe_snap_point_on_line = None;
         let mut maybe_smallest_dist_to_point : Option<f64> = None;
@@ -64,9 +108,12 @@ impl<'a> System<'a> for SnapPointSystem {
 
         // Loop through all the neighbor entities
         for entity in neighbor_entities {
+          if skipped.contains(&entity) {
+            continue;
+          }
           if let Some(p) = points.get(entity) {
-            let norm_dist = (p.to_actual(&*vp) - mouse_pos).magnitude() / SNAP_TO_POINT_THRES;
-            if norm_dist < 1.0 {
+            let norm_dist = (p.to_actual(&*vp) - mouse_pos).magnitude() / settings.point.threshold;
+            if norm_dist < 1.0 && settings.point.enabled {
               if maybe_smallest_dist_to_point.is_none() || norm_dist < maybe_smallest_dist_to_point.unwrap() {
                 is_snapping_to_point = true;
                 maybe_smallest_dist_to_point = Some(norm_dist);
@@ -81,11 +128,11 @@ impl<'a> System<'a> for SnapPointSystem {
           } else if let Some(l) = lines.get(entity) {
             let actual_proj_point = mouse_pos.project(l.to_actual(&*vp));
             let dist = (actual_proj_point - mouse_pos).magnitude();
-            if dist <= SNAP_TO_POINT_THRES {
+            if dist <= settings.point.threshold {
               closest_lines.push((entity, *l));
             }
-            let norm_dist = dist / SNAP_TO_LINE_THRES;
-            if norm_dist < 1.0 && !is_snapping_to_point {
+            let norm_dist = dist / settings.line.threshold;
+            if norm_dist < 1.0 && !is_snapping_to_point && settings.line.enabled {
               let virtual_proj_point = actual_proj_point.to_virtual(&*vp);
               let p_to_origin = virtual_proj_point - l.origin;
               let p_to_origin_dist = p_to_origin.magnitude();
@@ -100,6 +147,60 @@ impl<'a> System<'a> for SnapPointSystem {
                 });
               }
             }
+          } else if let Some(a) = arcs.get(entity) {
+            let t = a.param_of(virtual_mouse_pos);
+            let virtual_closest_point = a.point_at(t);
+            let norm_dist = (virtual_closest_point.to_actual(&*vp) - mouse_pos).magnitude() / settings.line.threshold;
+            if norm_dist < 1.0 && !is_snapping_to_point && settings.line.enabled {
+              if maybe_smallest_dist_to_line.is_none() || norm_dist < maybe_smallest_dist_to_line.unwrap() {
+                maybe_smallest_dist_to_line = Some(norm_dist);
+
+                // Set the snap point to snap on arc
+                maybe_snap_point_on_line = Some(SnapPoint {
+                  position: virtual_closest_point,
+                  symbo: SnapPointType::SnapOnArc(entity, t),
+                });
+              }
+            }
+          } else if let Some(c) = conics.get(entity) {
+            let t = c.param_of(virtual_mouse_pos);
+            let virtual_closest_point = c.point_at(t);
+            let norm_dist = (virtual_closest_point.to_actual(&*vp) - mouse_pos).magnitude() / settings.line.threshold;
+            if norm_dist < 1.0 && !is_snapping_to_point && settings.line.enabled {
+              if maybe_smallest_dist_to_line.is_none() || norm_dist < maybe_smallest_dist_to_line.unwrap() {
+                maybe_smallest_dist_to_line = Some(norm_dist);
+
+                // Set the snap point to snap on conic
+                maybe_snap_point_on_line = Some(SnapPoint {
+                  position: virtual_closest_point,
+                  symbo: SnapPointType::SnapOnConic(entity, t),
+                });
+              }
+            }
+          } else if let Some((t, virtual_closest_point)) = plots.get(entity).and_then(|plot| plot.closest(virtual_mouse_pos)) {
+            let dist = (virtual_closest_point.to_actual(&*vp) - mouse_pos).magnitude();
+            let is_function = sym_functions.get(entity).is_some();
+            if dist <= settings.point.threshold && is_function {
+              closest_functions.push(entity);
+            }
+            let norm_dist = dist / settings.line.threshold;
+            let maybe_symbo = if is_function {
+              Some(SnapPointType::SnapOnFunction(entity, t)) // Where t is x
+            } else if sym_curves.get(entity).is_some() {
+              Some(SnapPointType::SnapOnCurve(entity, t))
+            } else {
+              None
+            };
+            if let Some(symbo) = maybe_symbo {
+              if norm_dist < 1.0 && !is_snapping_to_point && settings.line.enabled {
+                if maybe_smallest_dist_to_line.is_none() || norm_dist < maybe_smallest_dist_to_line.unwrap() {
+                  maybe_smallest_dist_to_line = Some(norm_dist);
+
+                  // Set the snap point to snap on the plot
+                  maybe_snap_point_on_line = Some(SnapPoint { position: virtual_closest_point, symbo });
+                }
+              }
+            }
           }
         }
 
@@ -109,13 +210,13 @@ impl<'a> System<'a> for SnapPointSystem {
         }
 
         // Check if snapping to an intersection
-        if !is_snapping_to_point {
+        if !is_snapping_to_point && settings.intersection.enabled {
           let mut maybe_smallest_dist = None;
           for comb in closest_lines.iter().combinations(2) {
             if let &[(l1_ent, l1), (l2_ent, l2)] = &*comb {
               if let Some(itsct) = l1.intersect(*l2) {
                 let actual : Vector2 = itsct.to_actual(&*vp);
-                let norm_dist = (mouse_pos - actual).magnitude() / SNAP_TO_INTERSECTION_THRES;
+                let norm_dist = (mouse_pos - actual).magnitude() / settings.intersection.threshold;
                 if norm_dist < 1.0 {
                   if maybe_smallest_dist.is_none() || norm_dist < maybe_smallest_dist.unwrap() {
                     maybe_smallest_dist = Some(norm_dist);
@@ -130,6 +231,41 @@ impl<'a> System<'a> for SnapPointSystem {
               }
             }
           }
+
+          // Functions intersecting with lines, searching around the mouse
+          let search_radius = settings.intersection.threshold * vp.scale();
+          for function_ent in &closest_functions {
+            if let Some(Ok(sym_function)) = sym_functions.get(*function_ent).map(|f| f.bind(|ent| points.get(ent).cloned())) {
+              for (line_ent, line) in &closest_lines {
+                if let Some(itsct) = sym_function.intersect_line(*line, virtual_mouse_pos.x, search_radius) {
+                  let actual : Vector2 = itsct.to_actual(&*vp);
+                  let norm_dist = (mouse_pos - actual).magnitude() / settings.intersection.threshold;
+                  if norm_dist < 1.0 {
+                    if maybe_smallest_dist.is_none() || norm_dist < maybe_smallest_dist.unwrap() {
+                      maybe_smallest_dist = Some(norm_dist);
+
+                      // Set the snap point to the function line intersection
+                      maybe_snap_point.set(SnapPoint {
+                        position: itsct,
+                        symbo: SnapPointType::SnapOnFunctionLineIntersection(*function_ent, *line_ent, itsct.x),
+                      });
+                    }
+                  }
+                }
+              }
+            }
+          }
+        }
+      }
+
+      // The dragged point snaps to the grid at last, or else it is where the
+      // mouse is already
+      if let (Some(_), Some(SnapPoint { symbo: SnapPointType::NotSnapped, .. })) = (dragging, maybe_snap_point.get()) {
+        let grid_point = vp.closest_grid_point(virtual_mouse_pos);
+        if snapping && settings.grid.enabled && (grid_point.to_actual(&*vp) - mouse_pos).magnitude() < settings.grid.threshold {
+          maybe_snap_point.set(SnapPoint { position: grid_point, symbo: SnapPointType::SnapOnGrid });
+        } else {
+          maybe_snap_point.clear();
         }
       }
     } else {
@@ -0,0 +1,162 @@
+use specs::prelude::*;
+use crate::{
+  utilities::{Vector2, AABB},
+  resources::{
+    Tool,
+    Viewport, ViewportTransform,
+    DependencyGraph,
+    ContextMenu,
+    geometry::{SelectionHandles, SelectionBox, Handle},
+    events::{
+      ToolChangeEvent, ToolChangeEventChannel, ToolChangeEventReader,
+      SketchEventChannel, SketchEvent, MovePoint,
+      MouseEvent, MouseEventChannel, MouseEventReader,
+    },
+  },
+  components::{SymbolicPoint, Point, Selected},
+};
+use super::helpers::free_ancestors;
+
+static BOX_MARGIN : f64 = 10.0; // Pixel
+
+struct Transforming {
+  handle: Handle,
+  center: Vector2, // Virtual
+  start: Vector2, // Virtual mouse position
+  free_points: Vec<(Entity, Vector2)>, // Free points with their start positions
+}
+
+fn rotate(v: Vector2, angle: f64) -> Vector2 {
+  let (sin, cos) = angle.sin_cos();
+  vec2![v.x * cos - v.y * sin, v.x * sin + v.y * cos]
+}
+
+/// # Transform Selection Via Drag
+///
+/// When several things are selected, a box with handles is put around the
+/// free points they are defined by, together with the selected points.
+/// Dragging a corner scales and dragging the handle above rotates all those
+/// free points around the center of the box.
+#[derive(Default)]
+pub struct TransformSelectionViaDrag {
+  tool_change_event_reader: Option<ToolChangeEventReader>,
+  mouse_event_reader: Option<MouseEventReader>,
+  transforming: Option<Transforming>,
+}
+
+impl<'a> System<'a> for TransformSelectionViaDrag {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, ToolChangeEventChannel>,
+    Write<'a, MouseEventChannel>,
+    Read<'a, Viewport>,
+    Read<'a, ContextMenu>,
+    Read<'a, DependencyGraph>,
+    Write<'a, SelectionHandles>,
+    Write<'a, SketchEventChannel>,
+    ReadStorage<'a, SymbolicPoint>,
+    ReadStorage<'a, Point>,
+    ReadStorage<'a, Selected>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.tool_change_event_reader = Some(world.fetch_mut::<ToolChangeEventChannel>().register_reader());
+    self.mouse_event_reader = Some(world.fetch_mut::<MouseEventChannel>().register_reader());
+  }
+
+  fn run(&mut self, (
+    entities,
+    tool_change_event_channel,
+    mut mouse_event_channel,
+    viewport,
+    context_menu,
+    dependency_graph,
+    mut selection_handles,
+    mut sketch_event_channel,
+    sym_points,
+    points,
+    selected,
+  ): Self::SystemData) {
+
+    // Same as dragging points, we only listen to mouse event when the tool
+    // state is select
+    if let Some(reader_id) = &mut self.tool_change_event_reader {
+      for event in tool_change_event_channel.read(reader_id) {
+        match event {
+          ToolChangeEvent(Tool::Select) => {
+            self.mouse_event_reader = Some(mouse_event_channel.register_reader());
+          },
+          _ => {
+            self.mouse_event_reader = None;
+          }
+        }
+      }
+    }
+
+    // Find the free points to transform and put the box around them
+    let selected_entities : Vec<Entity> = (&entities, &selected).join().map(|(ent, _)| ent).collect();
+    let free_points = free_ancestors(selected_entities.clone(), &sym_points, &dependency_graph);
+    let positions : Vec<Vector2> = selected_entities.iter()
+      .filter_map(|ent| if sym_points.get(*ent).is_some() { points.get(*ent).cloned() } else { None })
+      .chain(free_points.iter().map(|(_, p)| *p))
+      .map(|p| p.to_actual(&viewport))
+      .collect();
+    selection_handles.clear();
+    if self.mouse_event_reader.is_some() && selected_entities.len() > 1 && !free_points.is_empty() {
+      let (x_min, x_max) = positions.iter().fold((std::f64::INFINITY, std::f64::NEG_INFINITY), |(lo, hi), p| (lo.min(p.x), hi.max(p.x)));
+      let (y_min, y_max) = positions.iter().fold((std::f64::INFINITY, std::f64::NEG_INFINITY), |(lo, hi), p| (lo.min(p.y), hi.max(p.y)));
+      let aabb = AABB::new(x_min - BOX_MARGIN, y_min - BOX_MARGIN, x_max - x_min + 2.0 * BOX_MARGIN, y_max - y_min + 2.0 * BOX_MARGIN);
+      let center = vec2![(x_min + x_max) / 2.0, (y_min + y_max) / 2.0].to_virtual(&viewport);
+      selection_handles.set(SelectionBox { aabb, center });
+    }
+
+    let mut maybe_drag_to = None;
+    if let Some(reader_id) = &mut self.mouse_event_reader {
+      for event in mouse_event_channel.read(reader_id) {
+        match event {
+          MouseEvent::DragBegin(start_position) if !context_menu.is_active() => {
+            if let Some(selection_box) = selection_handles.get() {
+              if let Some(handle) = selection_box.hit(*start_position) {
+                self.transforming = Some(Transforming {
+                  handle,
+                  center: selection_box.center,
+                  start: start_position.to_virtual(&viewport),
+                  free_points: free_points.clone(),
+                });
+              }
+            }
+          },
+          MouseEvent::DragMove(_, curr_position) => {
+            maybe_drag_to = Some(*curr_position);
+          },
+          MouseEvent::DragEnd(_) => {
+            self.transforming = None;
+          },
+          _ => (),
+        }
+      }
+    }
+
+    // Transform everything by where the mouse ends up in this frame
+    if let (Some(curr_position), Some(transforming)) = (maybe_drag_to, &self.transforming) {
+      let Transforming { handle, center, start, free_points } = transforming;
+      let (from, to) = (*start - *center, curr_position.to_virtual(&viewport) - *center);
+      let transform : Box<dyn Fn(Vector2) -> Vector2> = match handle {
+        Handle::Rotate => {
+          let angle = to.y.atan2(to.x) - from.y.atan2(from.x);
+          Box::new(move |p| *center + rotate(p - *center, angle))
+        },
+        Handle::Scale => {
+          let factor = if from.magnitude() > 0.0 { to.magnitude() / from.magnitude() } else { 1.0 };
+          Box::new(move |p| *center + factor * (p - *center))
+        },
+      };
+      for (ent, position) in free_points {
+        if let Some(SymbolicPoint::Free(old_position)) = sym_points.get(*ent) {
+          sketch_event_channel.single_write(SketchEvent::MovePoint(*ent, MovePoint::Free(*old_position, transform(*position))));
+        }
+      }
+    }
+  }
+}
\ No newline at end of file
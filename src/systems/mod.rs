@@ -6,4 +6,4 @@ pub mod geometry_systems;
 pub mod geometry_renderers;
 
 mod window_system;
-pub use window_system::WindowSystem;
\ No newline at end of file
+pub use window_system::{WindowSystem, WINDOW_TITLE};
\ No newline at end of file
@@ -5,4 +5,7 @@ mod exit_state_manager;
 pub use exit_state_manager::{ExitStateManager, ExitState};
 
 mod viewport_state_manager;
-pub use viewport_state_manager::*;
\ No newline at end of file
+pub use viewport_state_manager::*;
+
+mod snap_settings_manager;
+pub use snap_settings_manager::SnapSettingsManager;
\ No newline at end of file
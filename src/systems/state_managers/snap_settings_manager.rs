@@ -0,0 +1,53 @@
+use specs::prelude::*;
+use crate::{
+  utilities::preferences_file,
+  resources::{
+    SnapSettings,
+    PromptState, PromptKind,
+    events::{PromptEvent, PromptEventChannel, PromptEventReader},
+  },
+};
+
+static PREFERENCES_FILE : &str = "snap_settings.txt";
+
+#[derive(Default)]
+pub struct SnapSettingsManager {
+  prompt_event_reader: Option<PromptEventReader>,
+}
+
+/// # Snap Settings Manager
+///
+/// Loads the snap settings from the preferences file, then changes them to
+/// what is typed into the snap prompt and saves them again. The prompt
+/// stays open with an error message when the text cannot be parsed.
+impl<'a> System<'a> for SnapSettingsManager {
+  type SystemData = (
+    Read<'a, PromptEventChannel>,
+    Write<'a, PromptState>,
+    Write<'a, SnapSettings>,
+  );
+
+  fn setup(&mut self, world: &mut World) {
+    Self::SystemData::setup(world);
+    self.prompt_event_reader = Some(world.fetch_mut::<PromptEventChannel>().register_reader());
+
+    // Unreadable settings fall back to the defaults
+    let _ = world.fetch_mut::<SnapSettings>().load(&preferences_file(PREFERENCES_FILE));
+  }
+
+  fn run(&mut self, (prompt_events, mut prompt_state, mut snap_settings): Self::SystemData) {
+    if let Some(reader_id) = &mut self.prompt_event_reader {
+      for PromptEvent(kind, text) in prompt_events.read(reader_id) {
+        if *kind != PromptKind::SnapSettings {
+          continue;
+        }
+        match snap_settings.apply(text).and_then(|_| snap_settings.save(&preferences_file(PREFERENCES_FILE))) {
+          Ok(()) => prompt_state.close(),
+          Err(err) => prompt_state.set_error(err),
+        }
+      }
+    } else {
+      panic!("[snap_settings_manager] No reader id");
+    }
+  }
+}
\ No newline at end of file
@@ -3,12 +3,16 @@ use specs::prelude::*;
 use crate::{
   utilities::{Vector2, Intersect, Color, Key},
   resources::{
-    DeltaTime, Viewport, ViewportTransform, InputState,
+    DeltaTime, Viewport, ViewportTransform, InputState, PromptState, TraceLayer, TraceMark, SnapSettings, ConstructionProtocol, CustomToolLibrary, Iterations, SolverReport, ContextMenu,
     events::{ExitEvent, ExitEventChannel, ViewportEvent, ViewportEventChannel, MouseEvent, MouseEventChannel},
   },
-  components::{Selected, Point, PointStyle, Line, LineStyle, Rectangle, RectangleStyle},
+  components::{Selected, Point, PointStyle, Line, LineStyle, Arc, ArcStyle, ArcFill, Conic, ConicStyle, Plot, PlotStyle, Rectangle, RectangleStyle, Hidden},
 };
 
+pub static WINDOW_TITLE : &str = "Geometry Sketchpad - Untitled.gsp";
+
+static CONIC_DRAW_SAMPLES : usize = 512;
+
 fn draw_line(line: &Line, style: &LineStyle, selected: bool, vp: &Viewport, context: Context, graphics: &mut G2d) {
   let aabb = vp.virtual_aabb();
   let itsct = line.intersect(aabb);
@@ -25,6 +29,67 @@ fn draw_line(line: &Line, style: &LineStyle, selected: bool, vp: &Viewport, cont
   }
 }
 
+fn draw_arc_fill(arc: &Arc, style: &ArcStyle, vp: &Viewport, context: Context, graphics: &mut G2d) {
+  let actual_length = arc.length() / vp.scale();
+  let samples = ((actual_length / 4.0).ceil() as usize).max(8).min(256);
+  let outline = arc.sample(samples).into_iter().map(|p| p.to_actual(vp).into());
+  match style.fill {
+    ArcFill::None => (),
+    ArcFill::Sector(color) => {
+      let polygon : Vec<[f64; 2]> = std::iter::once(arc.center.to_actual(vp).into()).chain(outline).collect();
+      piston_window::polygon(color.into(), &polygon, context.transform, graphics);
+    },
+    ArcFill::Segment(color) => {
+      let polygon : Vec<[f64; 2]> = outline.collect();
+      piston_window::polygon(color.into(), &polygon, context.transform, graphics);
+    },
+  }
+}
+
+fn draw_arc(arc: &Arc, style: &ArcStyle, selected: bool, vp: &Viewport, context: Context, graphics: &mut G2d) {
+  let center = arc.center.to_actual(vp);
+  let radius = arc.radius / vp.scale();
+
+  // Angles are counter-clockwise in virtual space but clockwise in actual space
+  // since y is flipped. A full circle is drawn slightly short to not be empty
+  let span = arc.span.min(std::f64::consts::PI * 1.9999);
+  let (start, end) = (-arc.start - span, -arc.start);
+  circle_arc(style.color.into(), style.width, start, end, [center.x - radius, center.y - radius, radius * 2., radius * 2.], context.transform, graphics);
+  if selected {
+    for r in &[radius - style.width / 2.0 - 3.0, radius + style.width / 2.0 + 3.0] {
+      if *r > 0.0 {
+        circle_arc(Color::magenta().into(), 0.5, start, end, [center.x - r, center.y - r, r * 2., r * 2.], context.transform, graphics);
+      }
+    }
+  }
+}
+
+/// Draw polylines given in virtual space. Every segment is clipped to the
+/// screen first so that far away points do not blow up the rendering
+fn draw_polylines(polylines: &[Vec<Vector2>], color: Color, width: f64, selected: bool, vp: &Viewport, context: Context, graphics: &mut G2d) {
+  let aabb = vp.virtual_aabb();
+  for polyline in polylines {
+    for seg in polyline.windows(2) {
+      if let Some((from, to)) = (seg[0], seg[1]).intersect(aabb) {
+        let from = from.to_actual(vp);
+        let to = to.to_actual(vp);
+        line_from_to(color.into(), width, from, to, context.transform, graphics);
+        if selected && from != to {
+          let Vector2 { x: dx, y: dy } = (to - from).normalized();
+          let perp_dir = vec2![-dy, dx] * (width / 2.0 + 3.0);
+          line_from_to(Color::magenta().into(), 0.5, from - perp_dir, to - perp_dir, context.transform, graphics);
+          line_from_to(Color::magenta().into(), 0.5, from + perp_dir, to + perp_dir, context.transform, graphics);
+        }
+      }
+    }
+  }
+}
+
+fn draw_conic(conic: &Conic, style: &ConicStyle, selected: bool, vp: &Viewport, context: Context, graphics: &mut G2d) {
+  let polylines = conic.sample(vp.virtual_aabb(), CONIC_DRAW_SAMPLES);
+  draw_polylines(&polylines, style.color, style.width, selected, vp, context, graphics);
+}
+
 fn draw_point(point: &Point, style: &PointStyle, selected: bool, vp: &Viewport, context: Context, graphics: &mut G2d) {
   let actual = point.to_actual(vp);
   if selected {
@@ -54,6 +119,38 @@ fn draw_point(point: &Point, style: &PointStyle, selected: bool, vp: &Viewport,
   );
 }
 
+fn draw_trace_mark(mark: &TraceMark, vp: &Viewport, context: Context, graphics: &mut G2d) {
+  match mark {
+    TraceMark::Point(from, to, style) => {
+      let (from, to) = (from.to_actual(vp), to.to_actual(vp));
+      let radius = style.radius - 1.5;
+      if from == to {
+        ellipse(style.color.into(), [to.x - radius, to.y - radius, radius * 2., radius * 2.], context.transform, graphics);
+      } else {
+        line_from_to(style.color.into(), radius, from, to, context.transform, graphics);
+      }
+    },
+    TraceMark::Line(line, style) => draw_line(line, style, false, vp, context, graphics),
+  }
+}
+
+/// The grid points snap to, at the spacing of the current zoom
+fn draw_grid(vp: &Viewport, context: Context, graphics: &mut G2d) {
+  let spacing = vp.grid_spacing();
+  let color = Color::new(0.0, 0.0, 0.0, 0.08).into();
+  let (x_min, x_max, y_min, y_max) = (vp.x_min(), vp.x_max(), vp.y_min(), vp.y_max());
+  let mut x = (x_min / spacing).ceil() * spacing;
+  while x <= x_max {
+    line_from_to(color, 0.5, vec2![x, y_min].to_actual(vp), vec2![x, y_max].to_actual(vp), context.transform, graphics);
+    x += spacing;
+  }
+  let mut y = (y_min / spacing).ceil() * spacing;
+  while y <= y_max {
+    line_from_to(color, 0.5, vec2![x_min, y].to_actual(vp), vec2![x_max, y].to_actual(vp), context.transform, graphics);
+    y += spacing;
+  }
+}
+
 fn draw_rectangle(rect: &Rectangle, style: &RectangleStyle, context: Context, graphics: &mut G2d) {
   line_from_to(style.border.color.into(), style.border.width, [rect.x, rect.y], [rect.x, rect.y + rect.height], context.transform, graphics);
   line_from_to(style.border.color.into(), style.border.width, [rect.x, rect.y], [rect.x + rect.width, rect.y], context.transform, graphics);
@@ -62,6 +159,15 @@ fn draw_rectangle(rect: &Rectangle, style: &RectangleStyle, context: Context, gr
   rectangle(style.fill.into(), [rect.x, rect.y, rect.width, rect.height], context.transform, graphics);
 }
 
+/// The length and central angle of the arc, if it is the only thing selected
+fn selected_arc_status(arcs: &ReadStorage<Arc>, selected: &ReadStorage<Selected>) -> Option<String> {
+  let mut selection = (arcs.maybe(), selected).join();
+  match (selection.next(), selection.next()) {
+    (Some((Some(arc), _)), None) => Some(format!("Arc of length {:.2} and central angle {:.1}°", arc.length(), arc.central_angle().to_degrees())),
+    _ => None,
+  }
+}
+
 pub struct WindowSystem {
   pub window: PistonWindow,
 }
@@ -69,6 +175,11 @@ pub struct WindowSystem {
 impl<'a> System<'a> for WindowSystem {
   type SystemData = (
     Read<'a, Viewport>,
+    Read<'a, PromptState>,
+    Read<'a, TraceLayer>,
+    Read<'a, SnapSettings>,
+    Read<'a, ConstructionProtocol>,
+    (Read<'a, ContextMenu>, Read<'a, CustomToolLibrary>, Read<'a, Iterations>, Read<'a, SolverReport>), // Statuses for the title
     Write<'a, DeltaTime>,
     Write<'a, ExitEventChannel>,
     Write<'a, InputState>,
@@ -78,13 +189,25 @@ impl<'a> System<'a> for WindowSystem {
     ReadStorage<'a, PointStyle>,
     ReadStorage<'a, Line>,
     ReadStorage<'a, LineStyle>,
+    ReadStorage<'a, Arc>,
+    ReadStorage<'a, ArcStyle>,
+    ReadStorage<'a, Conic>,
+    ReadStorage<'a, ConicStyle>,
+    ReadStorage<'a, Plot>,
+    ReadStorage<'a, PlotStyle>,
     ReadStorage<'a, Rectangle>,
     ReadStorage<'a, RectangleStyle>,
     ReadStorage<'a, Selected>,
+    ReadStorage<'a, Hidden>,
   );
 
   fn run(&mut self, (
     viewport,
+    prompt_state,
+    trace_layer,
+    snap_settings,
+    protocol,
+    (context_menu, custom_tool_library, iterations, solver_report),
     mut delta_time,
     mut exit_event_channel,
     mut input_state,
@@ -94,14 +217,42 @@ impl<'a> System<'a> for WindowSystem {
     point_styles,
     lines,
     line_styles,
+    arcs,
+    arc_styles,
+    conics,
+    conic_styles,
+    plots,
+    plot_styles,
     rects,
     rect_styles,
     selected,
+    hidden,
   ): Self::SystemData) {
 
     // Reset information
     input_state.reset_relative_data();
 
+    // The active prompt, if any, is shown in the window title. Else the
+    // context menu, what to pick next, what the solver could not solve, the
+    // measurements of the selected arc, then the hovered row of the protocol
+    // panel or the current step
+    let title = match prompt_state.get() {
+      Some(prompt) => match &prompt.error {
+        Some(error) => format!("{}{}_ ({})", prompt.kind.label(), prompt.text, error),
+        None => format!("{}{}_", prompt.kind.label(), prompt.text),
+      },
+      None => context_menu.status(input_state.mouse_abs_pos).or_else(|| custom_tool_library.status()).or_else(|| iterations.status()).or_else(|| solver_report.status()).or_else(|| selected_arc_status(&arcs, &selected)).unwrap_or_else(|| {
+        let hovered = protocol.row_at(input_state.mouse_abs_pos, &*viewport);
+        match hovered.or_else(|| if protocol.is_stepping() { protocol.current() } else { None }) {
+          Some(index) => format!("Step {} of {}: {}", index + 1, protocol.steps().len(), protocol.steps()[index].description),
+          None => WINDOW_TITLE.to_string(),
+        }
+      }),
+    };
+    if self.window.get_title() != title {
+      self.window.set_title(title);
+    }
+
     // Handle window events
     // Will loop through and handle events until a render event happens (See line 149)
     loop {
@@ -138,6 +289,9 @@ impl<'a> System<'a> for WindowSystem {
                   _ => (),
                 }
               },
+              Input::Text(text) => {
+                input_state.text.push_str(&text);
+              },
               Input::Resize(ResizeArgs { window_size, .. }) => {
                 viewport_events.single_write(ViewportEvent::Resize(Vector2::from(window_size)));
               },
@@ -152,23 +306,62 @@ impl<'a> System<'a> for WindowSystem {
               self.window.draw_2d(&event, |context, graphics, _device| {
                 clear(Color::white().into(), graphics); // We clean the screen
 
+                // The grid points snap to goes below everything
+                if snap_settings.grid.enabled {
+                  draw_grid(&*viewport, context, graphics);
+                }
+
+                // Then traces
+                for mark in trace_layer.marks() {
+                  draw_trace_mark(mark, &*viewport, context, graphics);
+                }
+
+                // Then arc fills
+                for (arc, style, _) in (&arcs, &arc_styles, !&hidden).join() {
+                  draw_arc_fill(arc, style, &*viewport, context, graphics);
+                }
+
                 // Fisrt draw regular lines
-                for (line, style, _) in (&lines, &line_styles, !&selected).join() {
+                for (line, style, _, _) in (&lines, &line_styles, !&selected, !&hidden).join() {
                   draw_line(line, style, false, &*viewport, context, graphics);
                 }
 
                 // Fisrt draw lines
-                for (line, style, _) in (&lines, &line_styles, &selected).join() {
+                for (line, style, _, _) in (&lines, &line_styles, &selected, !&hidden).join() {
                   draw_line(line, style, true, &*viewport, context, graphics);
                 }
 
+                // Arcs go along with lines
+                for (arc, style, _, _) in (&arcs, &arc_styles, !&selected, !&hidden).join() {
+                  draw_arc(arc, style, false, &*viewport, context, graphics);
+                }
+                for (arc, style, _, _) in (&arcs, &arc_styles, &selected, !&hidden).join() {
+                  draw_arc(arc, style, true, &*viewport, context, graphics);
+                }
+
+                // So do conics
+                for (conic, style, _, _) in (&conics, &conic_styles, !&selected, !&hidden).join() {
+                  draw_conic(conic, style, false, &*viewport, context, graphics);
+                }
+                for (conic, style, _, _) in (&conics, &conic_styles, &selected, !&hidden).join() {
+                  draw_conic(conic, style, true, &*viewport, context, graphics);
+                }
+
+                // And plots
+                for (plot, style, _, _) in (&plots, &plot_styles, !&selected, !&hidden).join() {
+                  draw_polylines(&plot.positions(), style.color, style.width, false, &*viewport, context, graphics);
+                }
+                for (plot, style, _, _) in (&plots, &plot_styles, &selected, !&hidden).join() {
+                  draw_polylines(&plot.positions(), style.color, style.width, true, &*viewport, context, graphics);
+                }
+
                 // Then draw regular points (not selected)
-                for (point, style, _) in (&points, &point_styles, !&selected).join() {
+                for (point, style, _, _) in (&points, &point_styles, !&selected, !&hidden).join() {
                   draw_point(point, style, false, &*viewport, context, graphics);
                 }
 
                 // Then draw selected points (as points are on top of lines)
-                for (point, style, _) in (&points, &point_styles, &selected).join() {
+                for (point, style, _, _) in (&points, &point_styles, &selected, !&hidden).join() {
                   draw_point(point, style, true, &*viewport, context, graphics);
                 }
 
@@ -0,0 +1,183 @@
+use std::f64::consts::PI;
+use super::{Vector2, Line};
+
+static TANGENT_TOLERANCE : f64 = 1e-9; // Relative to the radius
+
+/// An arc of a circle. Angles are in radians and measured counter-clockwise
+/// in virtual space. The arc sweeps from `start` for `span` radians, where
+/// `span` is in (0, 2π].
+#[derive(Debug, Copy, Clone)]
+pub struct Arc {
+  pub center: Vector2,
+  pub radius: f64,
+  pub start: f64,
+  pub span: f64,
+}
+
+fn normalize_angle(angle: f64) -> f64 {
+  let a = angle % (2.0 * PI);
+  if a < 0.0 { a + 2.0 * PI } else { a }
+}
+
+fn angle_of(v: Vector2) -> f64 {
+  v.y.atan2(v.x)
+}
+
+impl Arc {
+  /// Arc around `center` starting at `from` and going counter-clockwise
+  /// until reaching the ray towards `to`. `to` only decides the end angle.
+  pub fn from_center_two_points(center: Vector2, from: Vector2, to: Vector2) -> Option<Self> {
+    let radius = (from - center).magnitude();
+    if radius == 0.0 || (to - center).magnitude() == 0.0 {
+      None
+    } else {
+      let start = angle_of(from - center);
+      let span = normalize_angle(angle_of(to - center) - start);
+      let span = if span == 0.0 { 2.0 * PI } else { span };
+      Some(Self { center, radius, start, span })
+    }
+  }
+
+  /// Arc starting at `from`, passing through `through` and ending at `to`.
+  /// Returns None when the three points are collinear.
+  pub fn from_three_points(from: Vector2, through: Vector2, to: Vector2) -> Option<Self> {
+    let d1 = through - from;
+    let d2 = to - from;
+    let det = 2.0 * (d1.x * d2.y - d1.y * d2.x);
+    if det == 0.0 {
+      None
+    } else {
+      let l1 = d1.dot(d1);
+      let l2 = d2.dot(d2);
+      let center = from + vec2![(d2.y * l1 - d1.y * l2) / det, (d1.x * l2 - d2.x * l1) / det];
+      let radius = (from - center).magnitude();
+
+      // Positive determinant means from -> through -> to is counter-clockwise
+      let (first, last) = if det > 0.0 { (from, to) } else { (to, from) };
+      let start = angle_of(first - center);
+      let span = normalize_angle(angle_of(last - center) - start);
+      Some(Self { center, radius, start, span })
+    }
+  }
+
+  /// Point on the arc at parameter `t`, where 0 is the start and 1 is the end
+  pub fn point_at(&self, t: f64) -> Vector2 {
+    let angle = self.start + t * self.span;
+    self.center + self.radius * vec2![angle.cos(), angle.sin()]
+  }
+
+  /// Parameter of the point on the arc closest to `p`, clamped to [0, 1]
+  pub fn param_of(&self, p: Vector2) -> f64 {
+    let delta = normalize_angle(angle_of(p - self.center) - self.start);
+    if delta <= self.span {
+      delta / self.span
+    } else if delta - self.span < 2.0 * PI - delta {
+      1.0
+    } else {
+      0.0
+    }
+  }
+
+  pub fn closest_point(&self, p: Vector2) -> Vector2 {
+    self.point_at(self.param_of(p))
+  }
+
+  /// Sample `n + 1` evenly spaced points from start to end
+  pub fn sample(&self, n: usize) -> Vec<Vector2> {
+    (0..=n).map(|i| self.point_at(i as f64 / n as f64)).collect()
+  }
+
+  pub fn central_angle(&self) -> f64 {
+    self.span
+  }
+
+  pub fn length(&self) -> f64 {
+    self.radius * self.span
+  }
+
+  /// One of the two tangents to the circle of the arc through `p`, the left
+  /// or the right one when looking at the center from `p`. The two are the
+  /// same when `p` is on the circle, and there is none when it is inside.
+  /// Which one is which does not change as `p` moves around, so it can be
+  /// kept track of
+  pub fn tangent_from(&self, p: Vector2, left: bool) -> Option<Line> {
+    let to_center = self.center - p;
+    let dist = to_center.magnitude();
+    let ratio = self.radius / dist;
+    if dist == 0.0 || ratio > 1.0 + TANGENT_TOLERANCE {
+      None
+    } else {
+      let angle = ratio.min(1.0).asin() * if left { 1.0 } else { -1.0 };
+      let (cos, sin) = (angle.cos(), angle.sin());
+      let u = to_center / dist;
+      Some(Line { origin: p, direction: vec2![u.x * cos - u.y * sin, u.x * sin + u.y * cos] })
+    }
+  }
+
+  /// The tangent to the circle of the arc at `p`, which should be on it
+  pub fn tangent_at(&self, p: Vector2) -> Option<Line> {
+    let radial = p - self.center;
+    if radial.magnitude() == 0.0 {
+      None
+    } else {
+      Some(Line { origin: p, direction: vec2![-radial.y, radial.x].normalized() })
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn approx(a: Vector2, b: Vector2) -> bool {
+    (a - b).magnitude() < 1e-9
+  }
+
+  #[test]
+  fn test_three_points_counter_clockwise() {
+    let arc = Arc::from_three_points(vec2![1., 0.], vec2![0., 1.], vec2![-1., 0.]).unwrap();
+    assert!(approx(arc.center, vec2![0., 0.]));
+    assert!((arc.radius - 1.0).abs() < 1e-9);
+    assert!((arc.central_angle() - PI).abs() < 1e-9);
+    assert!(approx(arc.point_at(0.5), vec2![0., 1.]));
+  }
+
+  #[test]
+  fn test_three_points_clockwise() {
+    let arc = Arc::from_three_points(vec2![1., 0.], vec2![0., -1.], vec2![-1., 0.]).unwrap();
+    assert!((arc.central_angle() - PI).abs() < 1e-9);
+    assert!(approx(arc.point_at(0.5), vec2![0., -1.]));
+  }
+
+  #[test]
+  fn test_three_points_collinear() {
+    assert!(Arc::from_three_points(vec2![0., 0.], vec2![1., 1.], vec2![2., 2.]).is_none());
+  }
+
+  #[test]
+  fn test_param_clamped() {
+    let arc = Arc::from_center_two_points(vec2![0., 0.], vec2![1., 0.], vec2![0., 1.]).unwrap();
+    assert!((arc.length() - PI / 2.0).abs() < 1e-9);
+    assert!((arc.param_of(vec2![1., 1.]) - 0.5).abs() < 1e-9);
+    assert_eq!(arc.param_of(vec2![1., -0.1]), 0.0);
+    assert_eq!(arc.param_of(vec2![-0.1, 1.]), 1.0);
+  }
+
+  #[test]
+  fn test_tangents() {
+    let arc = Arc::from_center_two_points(vec2![0., 0.], vec2![1., 0.], vec2![1., 0.]).unwrap();
+    let p = vec2![2., 0.];
+    let (left, right) = (arc.tangent_from(p, true).unwrap(), arc.tangent_from(p, false).unwrap());
+    for line in &[left, right] {
+      let foot = line.origin + (arc.center - line.origin).dot(line.direction) * line.direction;
+      assert!(((foot - arc.center).magnitude() - 1.0).abs() < 1e-9);
+    }
+    assert!(left.direction.y < 0.0 && right.direction.y > 0.0);
+
+    // On the circle both are the tangent there, inside there is none
+    let on = arc.tangent_from(vec2![0., 1.], true).unwrap();
+    assert!(on.direction.y.abs() < 1e-9);
+    assert!(arc.tangent_at(vec2![0., 1.]).unwrap().direction.y.abs() < 1e-9);
+    assert!(arc.tangent_from(vec2![0.5, 0.], true).is_none());
+  }
+}
\ No newline at end of file
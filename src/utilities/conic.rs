@@ -0,0 +1,327 @@
+use std::{cmp::Ordering, f64::consts::PI};
+use super::{Vector2, Line, AABB};
+
+static EPSILON : f64 = 1e-9;
+
+/// A non-degenerated conic section in its canonical shape, solved from the
+/// implicit form `A x² + B xy + C y² + D x + E y + F = 0`.
+#[derive(Debug, Copy, Clone)]
+pub struct Conic {
+  pub kind: ConicKind,
+}
+
+/// Canonical shape of a conic. `axis` is a unit vector; the second axis is
+/// `axis` rotated counter-clockwise by 90 degrees.
+#[derive(Debug, Copy, Clone)]
+pub enum ConicKind {
+  Ellipse { center: Vector2, axis: Vector2, a: f64, b: f64 }, // x'²/a² + y'²/b² = 1
+  Hyperbola { center: Vector2, axis: Vector2, a: f64, b: f64 }, // x'²/a² - y'²/b² = 1
+  Parabola { vertex: Vector2, axis: Vector2, p: f64 }, // x' = p y'², opening along axis
+}
+
+fn perp(v: Vector2) -> Vector2 {
+  vec2![-v.y, v.x]
+}
+
+/// Implicit coefficients of `x'²/a² + sign * y'²/b² = 1`
+fn central_coefficients(center: Vector2, axis: Vector2, a: f64, b: f64, sign: f64) -> [f64; 6] {
+  let v = perp(axis);
+  let (ia, ib) = (1.0 / (a * a), sign / (b * b));
+  let q00 = ia * axis.x * axis.x + ib * v.x * v.x;
+  let q01 = ia * axis.x * axis.y + ib * v.x * v.y;
+  let q11 = ia * axis.y * axis.y + ib * v.y * v.y;
+  let d = -2.0 * (q00 * center.x + q01 * center.y);
+  let e = -2.0 * (q01 * center.x + q11 * center.y);
+  let f = q00 * center.x * center.x + 2.0 * q01 * center.x * center.y + q11 * center.y * center.y - 1.0;
+  [q00, 2.0 * q01, q11, d, e, f]
+}
+
+/// Determinant of a square matrix using gaussian elimination. NaN entries
+/// give a NaN determinant
+fn determinant(mut m: Vec<Vec<f64>>) -> f64 {
+  let n = m.len();
+  let mut det = 1.0;
+  for col in 0..n {
+    let pivot = (col..n).max_by(|i, j| m[*i][col].abs().partial_cmp(&m[*j][col].abs()).unwrap_or(Ordering::Equal)).unwrap();
+    if m[pivot][col] == 0.0 {
+      return 0.0;
+    }
+    if pivot != col {
+      m.swap(pivot, col);
+      det = -det;
+    }
+    det *= m[col][col];
+    let (upper, lower) = m.split_at_mut(col + 1);
+    let pivot_row = &upper[col];
+    for row in lower.iter_mut() {
+      let factor = row[col] / pivot_row[col];
+      for (x, pivot_x) in row[col..].iter_mut().zip(&pivot_row[col..]) {
+        *x -= factor * pivot_x;
+      }
+    }
+  }
+  det
+}
+
+impl Conic {
+
+  /// Classify the implicit form. Returns None for degenerated conics (empty,
+  /// a single point or pairs of lines) and non-finite coefficients
+  pub fn from_implicit(coefficients: [f64; 6]) -> Option<Self> {
+    let [a, b, c, d, e, f] = coefficients;
+    let scale = a.abs().max(b.abs()).max(c.abs());
+    if coefficients.iter().any(|x| !x.is_finite()) || scale < EPSILON {
+      return None;
+    }
+
+    // Rotate the frame so that the xy term vanishes
+    let theta = 0.5 * b.atan2(a - c);
+    let u = vec2![theta.cos(), theta.sin()];
+    let v = perp(u);
+    let ar = a * u.x * u.x + b * u.x * u.y + c * u.y * u.y;
+    let cr = a * v.x * v.x + b * v.x * v.y + c * v.y * v.y;
+    let dr = d * u.x + e * u.y;
+    let er = d * v.x + e * v.y;
+
+    let kind = match (ar.abs() < EPSILON * scale, cr.abs() < EPSILON * scale) {
+      (false, false) => {
+
+        // ar (x' - h)² + cr (y' - k)² = rhs
+        let (h, k) = (-dr / (2.0 * ar), -er / (2.0 * cr));
+        let rhs = ar * h * h + cr * k * k - f;
+        let center = h * u + k * v;
+        if ar * cr > 0.0 {
+          if rhs / ar > 0.0 {
+            ConicKind::Ellipse { center, axis: u, a: (rhs / ar).sqrt(), b: (rhs / cr).sqrt() }
+          } else {
+            return None;
+          }
+        } else if rhs.abs() < EPSILON * scale {
+          return None;
+        } else if rhs / ar > 0.0 {
+          ConicKind::Hyperbola { center, axis: u, a: (rhs / ar).sqrt(), b: (-rhs / cr).sqrt() }
+        } else {
+          ConicKind::Hyperbola { center, axis: v, a: (rhs / cr).sqrt(), b: (-rhs / ar).sqrt() }
+        }
+      },
+      (true, false) => {
+
+        // cr (y' - k)² + dr x' + rest = 0
+        if dr.abs() < EPSILON * scale {
+          return None;
+        }
+        let k = -er / (2.0 * cr);
+        let rest = f - cr * k * k;
+        let vertex = (-rest / dr) * u + k * v;
+        let p = -cr / dr;
+        ConicKind::Parabola { vertex, axis: if p > 0.0 { u } else { -u }, p: p.abs() }
+      },
+      (false, true) => {
+
+        // ar (x' - h)² + er y' + rest = 0
+        if er.abs() < EPSILON * scale {
+          return None;
+        }
+        let h = -dr / (2.0 * ar);
+        let rest = f - ar * h * h;
+        let vertex = h * u + (-rest / er) * v;
+        let p = -ar / er;
+        ConicKind::Parabola { vertex, axis: if p > 0.0 { v } else { -v }, p: p.abs() }
+      },
+      (true, true) => return None,
+    };
+    Some(Self { kind })
+  }
+
+  /// Ellipse with foci `f1`, `f2` passing through `p`
+  pub fn ellipse(f1: Vector2, f2: Vector2, p: Vector2) -> Option<Self> {
+    let focal = f2 - f1;
+    let c = focal.magnitude() / 2.0;
+    let a = ((p - f1).magnitude() + (p - f2).magnitude()) / 2.0;
+    let b_sq = a * a - c * c;
+    if b_sq <= EPSILON * a * a {
+      None
+    } else {
+      let axis = if c > 0.0 { focal.normalized() } else { vec2![1.0, 0.0] };
+      Self::from_implicit(central_coefficients((f1 + f2) / 2.0, axis, a, b_sq.sqrt(), 1.0))
+    }
+  }
+
+  /// Hyperbola with foci `f1`, `f2` passing through `p`
+  pub fn hyperbola(f1: Vector2, f2: Vector2, p: Vector2) -> Option<Self> {
+    let focal = f2 - f1;
+    let c = focal.magnitude() / 2.0;
+    let a = ((p - f1).magnitude() - (p - f2).magnitude()).abs() / 2.0;
+    let b_sq = c * c - a * a;
+    if a <= EPSILON * c || b_sq <= EPSILON * c * c {
+      None
+    } else {
+      Self::from_implicit(central_coefficients((f1 + f2) / 2.0, focal.normalized(), a, b_sq.sqrt(), -1.0))
+    }
+  }
+
+  /// Parabola with the given focus and directrix
+  pub fn parabola(focus: Vector2, directrix: Line) -> Option<Self> {
+
+    // |P - focus|² = (n · (P - origin))², with n the unit normal of the directrix
+    let n = perp(directrix.direction.normalized());
+    let no = n.dot(directrix.origin);
+    Self::from_implicit([
+      1.0 - n.x * n.x,
+      -2.0 * n.x * n.y,
+      1.0 - n.y * n.y,
+      -2.0 * focus.x + 2.0 * no * n.x,
+      -2.0 * focus.y + 2.0 * no * n.y,
+      focus.dot(focus) - no * no,
+    ])
+  }
+
+  /// General conic passing through five points
+  pub fn through_five_points(points: [Vector2; 5]) -> Option<Self> {
+    let rows : Vec<[f64; 6]> = points.iter().map(|p| [p.x * p.x, p.x * p.y, p.y * p.y, p.x, p.y, 1.0]).collect();
+
+    // The coefficients span the null space of the 5x6 system, which are the
+    // signed minors of the matrix
+    let mut coefficients = [0.0; 6];
+    for (i, coefficient) in coefficients.iter_mut().enumerate() {
+      let minor = rows.iter().map(|row| (0..6).filter(|j| *j != i).map(|j| row[j]).collect()).collect();
+      *coefficient = if i % 2 == 0 { 1.0 } else { -1.0 } * determinant(minor);
+    }
+    Self::from_implicit(coefficients)
+  }
+
+  /// Point on the conic at parameter `t`. For ellipses `t` is the eccentric
+  /// angle, for hyperbolas `t` in (-π/2, π/2) is the branch along `axis` and
+  /// (π/2, 3π/2) the other one, and for parabolas `t` is the distance along
+  /// the second axis from the vertex.
+  pub fn point_at(&self, t: f64) -> Vector2 {
+    match self.kind {
+      ConicKind::Ellipse { center, axis, a, b } => center + a * t.cos() * axis + b * t.sin() * perp(axis),
+      ConicKind::Hyperbola { center, axis, a, b } => center + a / t.cos() * axis + b * t.tan() * perp(axis),
+      ConicKind::Parabola { vertex, axis, p } => vertex + p * t * t * axis + t * perp(axis),
+    }
+  }
+
+  /// Parameter of a point on (or near) the conic
+  pub fn param_of(&self, q: Vector2) -> f64 {
+    match self.kind {
+      ConicKind::Ellipse { center, axis, a, b } => {
+        let d = q - center;
+        (d.dot(perp(axis)) / b).atan2(d.dot(axis) / a)
+      },
+      ConicKind::Hyperbola { center, axis, b, .. } => {
+        let d = q - center;
+        let t = (d.dot(perp(axis)) / b).atan();
+        if d.dot(axis) >= 0.0 { t } else { PI + t }
+      },
+      ConicKind::Parabola { vertex, axis, .. } => (q - vertex).dot(perp(axis)),
+    }
+  }
+
+  /// Range of `t` covering the whole conic, or for parabolas the part of it
+  /// within `radius` of `q`
+  pub fn param_range(&self, q: Vector2, radius: f64) -> (f64, f64) {
+    match self.kind {
+      ConicKind::Ellipse { .. } => (0.0, 2.0 * PI),
+      ConicKind::Hyperbola { .. } => (-PI / 2.0, 3.0 * PI / 2.0),
+      ConicKind::Parabola { .. } => {
+        let t = self.param_of(q);
+        (t - radius, t + radius)
+      },
+    }
+  }
+
+  pub fn closest_point(&self, q: Vector2) -> Vector2 {
+    self.point_at(self.param_of(q))
+  }
+
+  /// Sample the part of the conic that can be visible inside `aabb` into
+  /// poly lines of `n + 1` points each
+  pub fn sample(&self, aabb: AABB, n: usize) -> Vec<Vec<Vector2>> {
+    let corners = [
+      vec2![aabb.x, aabb.y],
+      vec2![aabb.x + aabb.width, aabb.y],
+      vec2![aabb.x, aabb.y + aabb.height],
+      vec2![aabb.x + aabb.width, aabb.y + aabb.height],
+    ];
+    let reach = |from: Vector2| corners.iter().map(|c| (*c - from).magnitude()).fold(0.0, f64::max);
+    let range = |from: f64, to: f64| -> Vec<Vector2> {
+      (0..(n + 1)).map(|i| self.point_at(from + (to - from) * i as f64 / n as f64)).collect()
+    };
+    match self.kind {
+      ConicKind::Ellipse { .. } => vec![range(0.0, 2.0 * PI)],
+      ConicKind::Hyperbola { center, a, b, .. } => {
+
+        // Beyond `limit` the points are further than any corner from the center
+        let r = reach(center);
+        let limit = (r / b).atan().min((a / r).min(1.0).acos()).max(EPSILON);
+        vec![range(-limit, limit), range(PI - limit, PI + limit)]
+      },
+      ConicKind::Parabola { vertex, .. } => {
+        let r = reach(vertex);
+        vec![range(-r, r)]
+      },
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn on_conic(conic: &Conic, p: Vector2) -> bool {
+    (conic.closest_point(p) - p).magnitude() < 1e-6
+  }
+
+  #[test]
+  fn test_ellipse_from_foci() {
+    let conic = Conic::ellipse(vec2![-3., 0.], vec2![3., 0.], vec2![0., 4.]).unwrap();
+    match conic.kind {
+      ConicKind::Ellipse { a, b, .. } => {
+        assert!((a.max(b) - 5.0).abs() < 1e-9);
+        assert!((a.min(b) - 4.0).abs() < 1e-9);
+      },
+      _ => panic!("Expected an ellipse"),
+    }
+    assert!(on_conic(&conic, vec2![5., 0.]));
+    assert!(on_conic(&conic, vec2![0., -4.]));
+  }
+
+  #[test]
+  fn test_hyperbola_from_foci() {
+    let conic = Conic::hyperbola(vec2![-5., 0.], vec2![5., 0.], vec2![3., 0.]).unwrap();
+    assert!(on_conic(&conic, vec2![-3., 0.]));
+    assert!(on_conic(&conic, vec2![5., 16. / 3.]));
+    assert!(on_conic(&conic, vec2![-5., 16. / 3.]));
+    assert!(!on_conic(&conic, vec2![0., 0.]));
+  }
+
+  #[test]
+  fn test_parabola_from_focus_directrix() {
+    let directrix = Line { origin: vec2![0., -1.], direction: vec2![1., 0.] };
+    let conic = Conic::parabola(vec2![0., 1.], directrix).unwrap();
+    assert!(on_conic(&conic, vec2![0., 0.]));
+    assert!(on_conic(&conic, vec2![2., 1.]));
+    assert!(on_conic(&conic, vec2![-4., 4.]));
+  }
+
+  #[test]
+  fn test_five_points() {
+    let conic = Conic::through_five_points([
+      vec2![1., 0.], vec2![0., 1.], vec2![-1., 0.], vec2![0., -1.], vec2![0.6, 0.8],
+    ]).unwrap();
+    match conic.kind {
+      ConicKind::Ellipse { a, b, .. } => {
+        assert!((a - 1.0).abs() < 1e-9);
+        assert!((b - 1.0).abs() < 1e-9);
+      },
+      _ => panic!("Expected a circle"),
+    }
+    assert!(Conic::through_five_points([
+      vec2![0., 0.], vec2![1., 1.], vec2![2., 2.], vec2![3., 3.], vec2![4., 4.],
+    ]).is_none());
+    assert!(Conic::through_five_points([
+      vec2![std::f64::NAN, 0.], vec2![0., 1.], vec2![-1., 0.], vec2![0., -1.], vec2![0.6, 0.8],
+    ]).is_none());
+  }
+}
\ No newline at end of file
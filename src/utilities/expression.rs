@@ -0,0 +1,435 @@
+use std::fmt;
+use std::f64::consts::{PI, E};
+
+/// A real valued expression such as `2x^2 - sin(x / 2)`. Variables are
+/// referenced by name and bound when evaluating.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expression {
+  Number(f64),
+  Variable(String),
+  Negate(Box<Expression>),
+  Binary(BinaryOp, Box<Expression>, Box<Expression>),
+  Call(Builtin, Box<Expression>),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum BinaryOp {
+  Add,
+  Sub,
+  Mul,
+  Div,
+  Pow,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Builtin {
+  Sin, Cos, Tan,
+  Asin, Acos, Atan,
+  Sqrt, Abs, Exp, Ln, Log,
+  Floor, Ceil, Sign,
+}
+
+static BUILTINS : [(&str, Builtin); 14] = [
+  ("sin", Builtin::Sin), ("cos", Builtin::Cos), ("tan", Builtin::Tan),
+  ("asin", Builtin::Asin), ("acos", Builtin::Acos), ("atan", Builtin::Atan),
+  ("sqrt", Builtin::Sqrt), ("abs", Builtin::Abs), ("exp", Builtin::Exp),
+  ("ln", Builtin::Ln), ("log", Builtin::Log),
+  ("floor", Builtin::Floor), ("ceil", Builtin::Ceil), ("sign", Builtin::Sign),
+];
+
+impl Builtin {
+  fn from_name(name: &str) -> Option<Self> {
+    BUILTINS.iter().find(|(n, _)| *n == name).map(|(_, b)| *b)
+  }
+
+  fn name(&self) -> &'static str {
+    BUILTINS.iter().find(|(_, b)| b == self).map(|(n, _)| *n).unwrap()
+  }
+
+  fn apply(&self, v: f64) -> f64 {
+    match self {
+      Builtin::Sin => v.sin(),
+      Builtin::Cos => v.cos(),
+      Builtin::Tan => v.tan(),
+      Builtin::Asin => v.asin(),
+      Builtin::Acos => v.acos(),
+      Builtin::Atan => v.atan(),
+      Builtin::Sqrt => v.sqrt(),
+      Builtin::Abs => v.abs(),
+      Builtin::Exp => v.exp(),
+      Builtin::Ln => v.ln(),
+      Builtin::Log => v.log10(),
+      Builtin::Floor => v.floor(),
+      Builtin::Ceil => v.ceil(),
+      Builtin::Sign => if v == 0.0 { 0.0 } else { v.signum() },
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+  Empty,
+  UnexpectedEnd,
+  UnexpectedCharacter(usize, char), // (position, character)
+  UnknownVariable(String),
+}
+
+impl fmt::Display for ParseError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      ParseError::Empty => write!(f, "empty expression"),
+      ParseError::UnexpectedEnd => write!(f, "unexpected end of expression"),
+      ParseError::UnexpectedCharacter(pos, c) => write!(f, "unexpected '{}' at {}", c, pos + 1),
+      ParseError::UnknownVariable(name) => write!(f, "unknown variable '{}'", name),
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+  Number(f64),
+  Ident(String),
+  Op(char), // One of + - * / ^ ( ) |
+}
+
+fn tokenize(s: &str) -> Result<Vec<(usize, Token)>, ParseError> {
+  let chars : Vec<char> = s.chars().collect();
+  let mut tokens = vec![];
+  let mut i = 0;
+  while i < chars.len() {
+    let c = chars[i];
+    if c.is_whitespace() {
+      i += 1;
+    } else if c.is_ascii_digit() || c == '.' {
+      let start = i;
+      while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+        i += 1;
+      }
+      let literal : String = chars[start..i].iter().collect();
+      match literal.parse::<f64>() {
+        Ok(n) => tokens.push((start, Token::Number(n))),
+        Err(_) => return Err(ParseError::UnexpectedCharacter(start, c)),
+      }
+    } else if c.is_alphabetic() {
+      let start = i;
+      while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+        i += 1;
+      }
+      tokens.push((start, Token::Ident(chars[start..i].iter().collect())));
+    } else if "+-*/^()|".contains(c) {
+      tokens.push((i, Token::Op(c)));
+      i += 1;
+    } else {
+      return Err(ParseError::UnexpectedCharacter(i, c));
+    }
+  }
+  Ok(tokens)
+}
+
+/// Recursive descent parser. Precedence from low to high: `+ -`, `* /` (and
+/// implicit multiplication as in `2x`), unary minus, `^` (right associative)
+struct Parser {
+  source: Vec<char>,
+  tokens: Vec<(usize, Token)>,
+  curr: usize,
+}
+
+impl Parser {
+  fn peek(&self) -> Option<&Token> {
+    self.tokens.get(self.curr).map(|(_, t)| t)
+  }
+
+  fn unexpected(&self) -> ParseError {
+    match self.tokens.get(self.curr) {
+      Some((pos, _)) => ParseError::UnexpectedCharacter(*pos, self.source[*pos]),
+      None => ParseError::UnexpectedEnd,
+    }
+  }
+
+  fn expect(&mut self, op: char) -> Result<(), ParseError> {
+    if self.peek() == Some(&Token::Op(op)) {
+      self.curr += 1;
+      Ok(())
+    } else {
+      Err(self.unexpected())
+    }
+  }
+
+  fn sum(&mut self) -> Result<Expression, ParseError> {
+    let mut lhs = self.product()?;
+    loop {
+      let op = match self.peek() {
+        Some(Token::Op('+')) => BinaryOp::Add,
+        Some(Token::Op('-')) => BinaryOp::Sub,
+        _ => return Ok(lhs),
+      };
+      self.curr += 1;
+      lhs = Expression::Binary(op, Box::new(lhs), Box::new(self.product()?));
+    }
+  }
+
+  fn product(&mut self) -> Result<Expression, ParseError> {
+    let mut lhs = self.unary()?;
+    loop {
+      let op = match self.peek() {
+        Some(Token::Op('*')) => { self.curr += 1; BinaryOp::Mul },
+        Some(Token::Op('/')) => { self.curr += 1; BinaryOp::Div },
+        Some(Token::Number(_)) | Some(Token::Ident(_)) | Some(Token::Op('(')) => BinaryOp::Mul,
+        _ => return Ok(lhs),
+      };
+      lhs = Expression::Binary(op, Box::new(lhs), Box::new(self.unary()?));
+    }
+  }
+
+  fn unary(&mut self) -> Result<Expression, ParseError> {
+    match self.peek() {
+      Some(Token::Op('-')) => { self.curr += 1; Ok(Expression::Negate(Box::new(self.unary()?))) },
+      Some(Token::Op('+')) => { self.curr += 1; self.unary() },
+      _ => self.power(),
+    }
+  }
+
+  fn power(&mut self) -> Result<Expression, ParseError> {
+    let base = self.atom()?;
+    if self.peek() == Some(&Token::Op('^')) {
+      self.curr += 1;
+      Ok(Expression::Binary(BinaryOp::Pow, Box::new(base), Box::new(self.unary()?)))
+    } else {
+      Ok(base)
+    }
+  }
+
+  fn atom(&mut self) -> Result<Expression, ParseError> {
+    let token = match self.peek() {
+      Some(token) => token.clone(),
+      None => return Err(ParseError::UnexpectedEnd),
+    };
+    match token {
+      Token::Number(n) => { self.curr += 1; Ok(Expression::Number(n)) },
+      Token::Ident(name) => {
+        self.curr += 1;
+        match (name.as_str(), Builtin::from_name(&name)) {
+          ("pi", _) => Ok(Expression::Number(PI)),
+          ("e", _) => Ok(Expression::Number(E)),
+          (_, Some(builtin)) => {
+
+            // Builtins can be applied without parenthesis, e.g. `sin x`
+            let arg = if self.peek() == Some(&Token::Op('(')) { self.atom()? } else { self.unary()? };
+            Ok(Expression::Call(builtin, Box::new(arg)))
+          },
+          (_, None) => Ok(Expression::Variable(name)),
+        }
+      },
+      Token::Op('(') => {
+        self.curr += 1;
+        let inner = self.sum()?;
+        self.expect(')')?;
+        Ok(inner)
+      },
+      Token::Op('|') => {
+        self.curr += 1;
+        let inner = self.sum()?;
+        self.expect('|')?;
+        Ok(Expression::Call(Builtin::Abs, Box::new(inner)))
+      },
+      Token::Op(_) => Err(self.unexpected()),
+    }
+  }
+}
+
+impl Expression {
+  pub fn parse(s: &str) -> Result<Self, ParseError> {
+    let tokens = tokenize(s)?;
+    if tokens.is_empty() {
+      return Err(ParseError::Empty);
+    }
+    let mut parser = Parser { source: s.chars().collect(), tokens, curr: 0 };
+    let expr = parser.sum()?;
+    if parser.curr < parser.tokens.len() {
+      Err(parser.unexpected())
+    } else {
+      Ok(expr)
+    }
+  }
+
+  /// Parse an expression that may only reference the given variables
+  pub fn parse_with_variables(s: &str, allowed: &[&str]) -> Result<Self, ParseError> {
+    let expr = Self::parse(s)?;
+    match expr.variables().into_iter().find(|v| !allowed.contains(&v.as_str())) {
+      Some(unknown) => Err(ParseError::UnknownVariable(unknown)),
+      None => Ok(expr),
+    }
+  }
+
+  /// All the variable names referenced, without duplicates
+  pub fn variables(&self) -> Vec<String> {
+    let mut result = vec![];
+    self.collect_variables(&mut result);
+    result
+  }
+
+  fn collect_variables(&self, result: &mut Vec<String>) {
+    match self {
+      Expression::Number(_) => (),
+      Expression::Variable(name) => if !result.contains(name) { result.push(name.clone()) },
+      Expression::Negate(e) | Expression::Call(_, e) => e.collect_variables(result),
+      Expression::Binary(_, lhs, rhs) => {
+        lhs.collect_variables(result);
+        rhs.collect_variables(result);
+      },
+    }
+  }
+
+  /// Evaluate with variables looked up by `vars`. Unbound variables and
+  /// values outside of a function's domain evaluate to NaN
+  pub fn evaluate<F: Fn(&str) -> Option<f64>>(&self, vars: &F) -> f64 {
+    match self {
+      Expression::Number(n) => *n,
+      Expression::Variable(name) => vars(name).unwrap_or(std::f64::NAN),
+      Expression::Negate(e) => -e.evaluate(vars),
+      Expression::Call(builtin, e) => builtin.apply(e.evaluate(vars)),
+      Expression::Binary(op, lhs, rhs) => {
+        let (l, r) = (lhs.evaluate(vars), rhs.evaluate(vars));
+        match op {
+          BinaryOp::Add => l + r,
+          BinaryOp::Sub => l - r,
+          BinaryOp::Mul => l * r,
+          BinaryOp::Div => l / r,
+          BinaryOp::Pow => l.powf(r),
+        }
+      },
+    }
+  }
+
+  /// Evaluate with a single variable bound
+  pub fn evaluate_at(&self, var: &str, value: f64) -> f64 {
+    self.evaluate(&|name: &str| if name == var { Some(value) } else { None })
+  }
+
+  /// The same expression with the variables bound by `vars` replaced by
+  /// their value, and the other ones left as they are
+  pub fn substitute<F: Fn(&str) -> Option<f64>>(&self, vars: &F) -> Self {
+    match self {
+      Expression::Number(_) => self.clone(),
+      Expression::Variable(name) => match vars(name) {
+        Some(value) => Expression::Number(value),
+        None => self.clone(),
+      },
+      Expression::Negate(e) => Expression::Negate(Box::new(e.substitute(vars))),
+      Expression::Call(builtin, e) => Expression::Call(*builtin, Box::new(e.substitute(vars))),
+      Expression::Binary(op, lhs, rhs) => Expression::Binary(*op, Box::new(lhs.substitute(vars)), Box::new(rhs.substitute(vars))),
+    }
+  }
+
+  /// The same expression with the variables renamed by `names`, and the
+  /// other ones left as they are
+  pub fn rename<F: Fn(&str) -> Option<String>>(&self, names: &F) -> Self {
+    match self {
+      Expression::Number(_) => self.clone(),
+      Expression::Variable(name) => Expression::Variable(names(name).unwrap_or_else(|| name.clone())),
+      Expression::Negate(e) => Expression::Negate(Box::new(e.rename(names))),
+      Expression::Call(builtin, e) => Expression::Call(*builtin, Box::new(e.rename(names))),
+      Expression::Binary(op, lhs, rhs) => Expression::Binary(*op, Box::new(lhs.rename(names)), Box::new(rhs.rename(names))),
+    }
+  }
+
+  fn precedence(&self) -> u8 {
+    match self {
+      Expression::Binary(BinaryOp::Add, _, _) | Expression::Binary(BinaryOp::Sub, _, _) => 1,
+      Expression::Binary(BinaryOp::Mul, _, _) | Expression::Binary(BinaryOp::Div, _, _) => 2,
+      Expression::Negate(_) => 3,
+      Expression::Binary(BinaryOp::Pow, _, _) => 4,
+      _ => 5,
+    }
+  }
+}
+
+fn fmt_operand(f: &mut fmt::Formatter, e: &Expression, min_precedence: u8) -> fmt::Result {
+  if e.precedence() < min_precedence { write!(f, "({})", e) } else { write!(f, "{}", e) }
+}
+
+impl fmt::Display for Expression {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Expression::Number(n) => write!(f, "{}", n),
+      Expression::Variable(name) => write!(f, "{}", name),
+      Expression::Negate(e) => { write!(f, "-")?; fmt_operand(f, e, 3) },
+      Expression::Call(builtin, e) => write!(f, "{}({})", builtin.name(), e),
+      Expression::Binary(op, lhs, rhs) => {
+        let (symbol, p) = match op {
+          BinaryOp::Add => (" + ", 1),
+          BinaryOp::Sub => (" - ", 1),
+          BinaryOp::Mul => (" * ", 2),
+          BinaryOp::Div => (" / ", 2),
+          BinaryOp::Pow => ("^", 4),
+        };
+
+        // Left associative operators need parenthesis on the right for equal
+        // precedence, `^` is right associative so the other way around
+        let (lp, rp) = if *op == BinaryOp::Pow { (p + 1, p) } else { (p, p + 1) };
+        fmt_operand(f, lhs, lp)?;
+        write!(f, "{}", symbol)?;
+        fmt_operand(f, rhs, rp)
+      },
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn eval(s: &str, x: f64) -> f64 {
+    Expression::parse(s).unwrap().evaluate_at("x", x)
+  }
+
+  #[test]
+  fn test_precedence() {
+    assert_eq!(eval("1 + 2 * 3", 0.), 7.);
+    assert_eq!(eval("-x^2", 3.), -9.);
+    assert_eq!(eval("2^3^2", 0.), 512.);
+    assert_eq!(eval("2^-1", 0.), 0.5);
+    assert_eq!(eval("(1 + 2) * 3", 0.), 9.);
+    assert_eq!(eval("8 / 4 / 2", 0.), 1.);
+  }
+
+  #[test]
+  fn test_implicit_multiplication_and_builtins() {
+    assert_eq!(eval("2x", 4.), 8.);
+    assert_eq!(eval("3(x + 1)", 1.), 6.);
+    assert_eq!(eval("|x - 5|", 2.), 3.);
+    assert!((eval("sin(pi / 2) + cos x", 0.) - 2.).abs() < 1e-12);
+    assert!(eval("sqrt(x)", -1.).is_nan());
+  }
+
+  #[test]
+  fn test_errors() {
+    assert_eq!(Expression::parse(""), Err(ParseError::Empty));
+    assert_eq!(Expression::parse("1 +"), Err(ParseError::UnexpectedEnd));
+    assert_eq!(Expression::parse("(1 + 2"), Err(ParseError::UnexpectedEnd));
+    assert_eq!(Expression::parse("1 + $"), Err(ParseError::UnexpectedCharacter(4, '$')));
+    assert_eq!(Expression::parse("(1))"), Err(ParseError::UnexpectedCharacter(3, ')')));
+    assert_eq!(Expression::parse_with_variables("a x", &["x"]), Err(ParseError::UnknownVariable("a".to_string())));
+  }
+
+  #[test]
+  fn test_substitute() {
+    let expr = Expression::parse("a x + b").unwrap().substitute(&|name: &str| if name == "a" { Some(2.) } else { None });
+    assert_eq!(expr.variables(), vec!["x".to_string(), "b".to_string()]);
+    assert_eq!(expr.evaluate(&|name: &str| if name == "x" { Some(3.) } else { Some(1.) }), 7.);
+  }
+
+  #[test]
+  fn test_rename() {
+    let expr = Expression::parse("a x + b").unwrap().rename(&|name: &str| if name == "a" { Some("c".to_string()) } else { None });
+    assert_eq!(expr, Expression::parse("c x + b").unwrap());
+  }
+
+  #[test]
+  fn test_display_round_trip() {
+    for s in &["x^2 - 3x + 1", "-(x + 1)^2", "2^x^2", "(x - 1) / (x + 1)", "sin(x) / x"] {
+      let expr = Expression::parse(s).unwrap();
+      assert_eq!(Expression::parse(&expr.to_string()).unwrap(), expr);
+    }
+  }
+}
\ No newline at end of file
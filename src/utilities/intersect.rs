@@ -67,4 +67,40 @@ impl Intersect<AABB> for Line {
       }
     }
   }
+}
+
+/// Clip a segment (from, to) against an AABB
+impl Intersect<AABB> for (Vector2, Vector2) {
+  type Output = (Vector2, Vector2);
+
+  fn intersect(self, AABB { x: x_min, y: y_min, width, height }: AABB) -> Option<Self::Output> {
+    let (from, to) = self;
+    let d = to - from;
+    let mut t_min = 0.0f64;
+    let mut t_max = 1.0f64;
+    for &(p, q) in &[
+      (-d.x, from.x - x_min),
+      (d.x, x_min + width - from.x),
+      (-d.y, from.y - y_min),
+      (d.y, y_min + height - from.y),
+    ] {
+      if p == 0.0 {
+        if q < 0.0 {
+          return None;
+        }
+      } else {
+        let r = q / p;
+        if p < 0.0 {
+          t_min = t_min.max(r);
+        } else {
+          t_max = t_max.min(r);
+        }
+      }
+    }
+    if t_min > t_max {
+      None
+    } else {
+      Some((from + t_min * d, from + t_max * d))
+    }
+  }
 }
\ No newline at end of file
@@ -1,13 +1,23 @@
 #[macro_use] mod vector2;
 mod line;
+mod arc;
+mod conic;
+mod expression;
+mod plot;
 mod aabb;
 mod intersect;
 mod color;
 mod key;
+mod preferences;
 
 pub use vector2::Vector2;
 pub use line::Line;
+pub use arc::Arc;
+pub use conic::Conic;
+pub use expression::{Expression, ParseError};
+pub use plot::{Plot, find_root_near};
 pub use aabb::AABB;
 pub use intersect::Intersect;
 pub use color::Color;
-pub use key::*;
\ No newline at end of file
+pub use key::*;
+pub use preferences::preferences_file;
\ No newline at end of file
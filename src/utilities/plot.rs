@@ -0,0 +1,218 @@
+use super::Vector2;
+
+static INITIAL_SAMPLES : usize = 64;
+static MAX_DEPTH : u32 = 10;
+
+/// Sampled curve in virtual space, split into continuous pieces. Each sample
+/// keeps the parameter it was evaluated at, so that a position found on the
+/// polylines can be mapped back to the curve's own parameter.
+#[derive(Debug, Clone)]
+pub struct Plot {
+  pub polylines: Vec<Vec<(f64, Vector2)>>, // (parameter, position)
+}
+
+impl Plot {
+
+  /// Sample `f` over `[t_0, t_1]`. Intervals are subdivided until each piece
+  /// deviates from a straight segment by less than `tolerance`. Undefined
+  /// (`None`) values and jumps that do not shrink when subdivided split the
+  /// plot into separate polylines.
+  pub fn sample<F: Fn(f64) -> Option<Vector2>>(f: F, t_0: f64, t_1: f64, tolerance: f64) -> Self {
+    let mut sampler = Sampler { f, tolerance, polylines: vec![], curr: vec![] };
+    let step = (t_1 - t_0) / INITIAL_SAMPLES as f64;
+    let mut prev = (t_0, sampler.eval(t_0));
+    sampler.push(prev.0, prev.1);
+    for i in 1..=INITIAL_SAMPLES {
+      let t = t_0 + i as f64 * step;
+      let next = (t, sampler.eval(t));
+      sampler.refine(prev, next, 0);
+      prev = next;
+    }
+    sampler.split();
+    Self { polylines: sampler.polylines }
+  }
+
+  /// Parameter and position of the point on the polylines closest to `p`.
+  /// The parameter is linearly interpolated within the closest segment
+  pub fn closest(&self, p: Vector2) -> Option<(f64, Vector2)> {
+    let mut result : Option<(f64, Vector2, f64)> = None;
+    for polyline in &self.polylines {
+      for seg in polyline.windows(2) {
+        let ((t_a, a), (t_b, b)) = (seg[0], seg[1]);
+        let ab = b - a;
+        let len_sq = ab.dot(ab);
+        let s = if len_sq == 0.0 { 0.0 } else { ((p - a).dot(ab) / len_sq).max(0.0).min(1.0) };
+        let q = a + s * ab;
+        let dist = (q - p).magnitude();
+        if result.is_none() || dist < result.unwrap().2 {
+          result = Some((t_a + s * (t_b - t_a), q, dist));
+        }
+      }
+    }
+    result.map(|(t, q, _)| (t, q))
+  }
+
+  pub fn positions(&self) -> Vec<Vec<Vector2>> {
+    self.polylines.iter().map(|pl| pl.iter().map(|(_, p)| *p).collect()).collect()
+  }
+}
+
+struct Sampler<F: Fn(f64) -> Option<Vector2>> {
+  f: F,
+  tolerance: f64,
+  polylines: Vec<Vec<(f64, Vector2)>>,
+  curr: Vec<(f64, Vector2)>,
+}
+
+impl<F: Fn(f64) -> Option<Vector2>> Sampler<F> {
+  fn eval(&self, t: f64) -> Option<Vector2> {
+    (self.f)(t).filter(|p| p.x.is_finite() && p.y.is_finite())
+  }
+
+  fn push(&mut self, t: f64, p: Option<Vector2>) {
+    match p {
+      Some(p) => self.curr.push((t, p)),
+      None => self.split(),
+    }
+  }
+
+  fn split(&mut self) {
+    if self.curr.len() > 1 {
+      self.polylines.push(std::mem::replace(&mut self.curr, vec![]));
+    } else {
+      self.curr.clear();
+    }
+  }
+
+  /// `a` is already pushed, push everything in between and `b`
+  fn refine(&mut self, a: (f64, Option<Vector2>), b: (f64, Option<Vector2>), depth: u32) {
+    let t_m = (a.0 + b.0) / 2.0;
+    let m = self.eval(t_m);
+    match (a.1, m, b.1) {
+      (Some(p_a), Some(p_m), Some(p_b)) => {
+        let deviation = (p_m - (p_a + p_b) / 2.0).magnitude();
+        let length = (p_b - p_a).magnitude();
+        if deviation < self.tolerance && length < 16.0 * self.tolerance {
+          self.push(b.0, b.1);
+        } else if depth >= MAX_DEPTH {
+
+          // A continuous piece shrinks when halved. If one half still holds
+          // almost all of the distance, the curve jumps here
+          let longer_half = (p_m - p_a).magnitude().max((p_b - p_m).magnitude());
+          if length > 16.0 * self.tolerance && longer_half > 0.9 * length {
+            self.split();
+            self.push(b.0, b.1);
+          } else {
+            self.push(t_m, m);
+            self.push(b.0, b.1);
+          }
+        } else {
+          self.refine(a, (t_m, m), depth + 1);
+          self.refine((t_m, m), b, depth + 1);
+        }
+      },
+      (None, None, None) => self.push(b.0, b.1),
+      _ => if depth >= MAX_DEPTH {
+        self.push(t_m, m);
+        self.push(b.0, b.1);
+      } else {
+
+        // Locate where the curve becomes (un)defined
+        self.refine(a, (t_m, m), depth + 1);
+        self.refine((t_m, m), b, depth + 1);
+      },
+    }
+  }
+}
+
+/// Root of `g` closest to `hint` within `radius`, found by stepping outwards
+/// from `hint` until the sign changes and then bisecting
+pub fn find_root_near<G: Fn(f64) -> f64>(g: G, hint: f64, radius: f64) -> Option<f64> {
+  static STEPS : usize = 512;
+  let h = radius / STEPS as f64;
+  if g(hint) == 0.0 {
+    return Some(hint);
+  }
+  for k in 0..STEPS {
+    let near = k as f64 * h;
+    let far = near + h;
+    for (a, b) in &[(hint + near, hint + far), (hint - far, hint - near)] {
+      let (g_a, g_b) = (g(*a), g(*b));
+      if g_a.is_finite() && g_b.is_finite() && g_a * g_b <= 0.0 {
+        return bisect(&g, *a, *b);
+      }
+    }
+  }
+  None
+}
+
+fn bisect<G: Fn(f64) -> f64>(g: &G, mut a: f64, mut b: f64) -> Option<f64> {
+  let sign_a = g(a).signum();
+  for _ in 0..64 {
+    let m = (a + b) / 2.0;
+    let g_m = g(m);
+    if !g_m.is_finite() {
+      return None;
+    } else if g_m == 0.0 {
+      return Some(m);
+    } else if g_m.signum() == sign_a {
+      a = m;
+    } else {
+      b = m;
+    }
+  }
+
+  // Reject sign changes caused by poles, where |g| does not vanish
+  let m = (a + b) / 2.0;
+  if g(m).abs() < 1e-6 { Some(m) } else { None }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn graph<G: Fn(f64) -> f64>(g: G) -> impl Fn(f64) -> Option<Vector2> {
+    move |x| Some(vec2![x, g(x)])
+  }
+
+  #[test]
+  fn test_sample_continuous() {
+    let plot = Plot::sample(graph(|x| x * x), -2., 2., 0.01);
+    assert_eq!(plot.polylines.len(), 1);
+    for (x, p) in &plot.polylines[0] {
+      assert!((p.y - x * x).abs() < 1e-12);
+    }
+  }
+
+  #[test]
+  fn test_sample_discontinuity() {
+    let plot = Plot::sample(graph(|x| 1.0 / x), -1.01, 1., 0.01);
+    assert_eq!(plot.polylines.len(), 2);
+    let plot = Plot::sample(graph(|x| x.floor()), 0.1, 2.9, 0.01);
+    assert_eq!(plot.polylines.len(), 3);
+  }
+
+  #[test]
+  fn test_sample_undefined() {
+    let plot = Plot::sample(graph(|x| (1.0 - x * x).sqrt()), -3., 3., 0.01);
+    assert_eq!(plot.polylines.len(), 1);
+    let (first, last) = (plot.polylines[0][0].0, plot.polylines[0].last().unwrap().0);
+    assert!((first + 1.0).abs() < 0.01 && (last - 1.0).abs() < 0.01);
+  }
+
+  #[test]
+  fn test_closest() {
+    let plot = Plot::sample(graph(|x| x), 0., 4., 0.01);
+    let (t, p) = plot.closest(vec2![3., 1.]).unwrap();
+    assert!((t - 2.).abs() < 1e-9 && (p - vec2![2., 2.]).magnitude() < 1e-9);
+  }
+
+  #[test]
+  fn test_find_root_near() {
+    let root = find_root_near(|x| x * x - 2.0, 1.0, 10.0).unwrap();
+    assert!((root - 2f64.sqrt()).abs() < 1e-9);
+    let root = find_root_near(|x| x * x - 2.0, -0.5, 10.0).unwrap();
+    assert!((root + 2f64.sqrt()).abs() < 1e-9);
+    assert!(find_root_near(|x| 1.0 / x, 0.3, 1.0).is_none());
+  }
+}
\ No newline at end of file
@@ -0,0 +1,25 @@
+use std::{env, fs, path::PathBuf};
+
+static DIRECTORY_VARIABLE : &str = "GEOMETRY_SKETCHPAD_DIR";
+static DIRECTORY_NAME : &str = ".geometry-sketchpad";
+
+/// The directory the preferences and the custom tool library are kept in:
+/// `$GEOMETRY_SKETCHPAD_DIR` if it is set, else `.geometry-sketchpad` in the
+/// home directory, else the working directory
+pub fn preferences_dir() -> PathBuf {
+  match env::var_os(DIRECTORY_VARIABLE) {
+    Some(dir) => PathBuf::from(dir),
+    None => match env::var_os("HOME").or_else(|| env::var_os("USERPROFILE")) {
+      Some(home) => PathBuf::from(home).join(DIRECTORY_NAME),
+      None => PathBuf::new(),
+    },
+  }
+}
+
+/// The path of a file in the preferences directory. The directory is
+/// created if it is missing, so that the file can be saved
+pub fn preferences_file(name: &str) -> PathBuf {
+  let dir = preferences_dir();
+  let _ = fs::create_dir_all(&dir); // Saving the file reports the error, if any
+  dir.join(name)
+}